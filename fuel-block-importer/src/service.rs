@@ -1,6 +1,10 @@
 use crate::Config;
-use fuel_core_interfaces::block_importer::{ImportBlockBroadcast, ImportBlockMpsc};
+use fuel_core_interfaces::{
+    block_importer::{ImportBlockBroadcast, ImportBlockMpsc},
+    model::SealedFuelBlock,
+};
 use parking_lot::Mutex;
+use std::sync::Arc;
 use tokio::{
     sync::{broadcast, mpsc},
     task::JoinHandle,
@@ -42,6 +46,17 @@ impl Service {
         self.broadcast.subscribe()
     }
 
+    /// Notify subscribers about a sealed block that was committed to the database.
+    pub fn notify_block_imported(&self, block: Arc<SealedFuelBlock>, is_created_by_self: bool) {
+        // it is fine if nobody is listening
+        let _ = self
+            .broadcast
+            .send(ImportBlockBroadcast::SealedFuelBlockImported {
+                block,
+                is_created_by_self,
+            });
+    }
+
     pub fn sender(&self) -> &mpsc::Sender<ImportBlockMpsc> {
         &self.sender
     }
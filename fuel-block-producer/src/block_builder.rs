@@ -0,0 +1,203 @@
+use crate::Config;
+use fuel_core_interfaces::{
+    common::{
+        fuel_tx::{Input, Output, TxId},
+        fuel_types::ContractId,
+    },
+    model::ArcTx,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Picks the transactions of the next block out of the includable transactions of the txpool,
+/// sorted by gas price, until the block limits of `config` are reached. Transactions depending
+/// on each other through the txpool are kept together: they are either all included, ordered
+/// after the transactions they depend on, or all left out.
+pub fn pack(includable: Vec<ArcTx>, config: &Config) -> Vec<ArcTx> {
+    let index: HashMap<TxId, usize> = includable
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| (tx.id(), i))
+        .collect();
+    let created_contracts: HashMap<ContractId, usize> = includable
+        .iter()
+        .enumerate()
+        .flat_map(|(i, tx)| {
+            tx.outputs().iter().filter_map(move |output| match output {
+                Output::ContractCreated { contract_id, .. } => Some((*contract_id, i)),
+                _ => None,
+            })
+        })
+        .collect();
+    let dependencies: Vec<Vec<usize>> = includable
+        .iter()
+        .map(|tx| {
+            tx.inputs()
+                .iter()
+                .filter_map(|input| match input {
+                    Input::CoinSigned { utxo_id, .. } | Input::CoinPredicate { utxo_id, .. } => {
+                        index.get(utxo_id.tx_id()).copied()
+                    }
+                    Input::Contract { contract_id, .. } => {
+                        created_contracts.get(contract_id).copied()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // group the dependent transactions, in the order of their best priced transaction
+    let mut roots: Vec<usize> = (0..includable.len()).collect();
+    for (i, dependencies) in dependencies.iter().enumerate() {
+        for dependency in dependencies {
+            let (a, b) = (root(&mut roots, i), root(&mut roots, *dependency));
+            roots[a.max(b)] = a.min(b);
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of_root = HashMap::new();
+    for i in 0..includable.len() {
+        let group = *group_of_root.entry(root(&mut roots, i)).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[group].push(i);
+    }
+
+    let mut block = vec![];
+    let mut gas = 0u64;
+    let mut size = 0u64;
+    for mut group in groups {
+        let group_gas = group
+            .iter()
+            .map(|i| includable[*i].gas_limit())
+            .fold(gas, u64::saturating_add);
+        let group_size = group
+            .iter()
+            .map(|i| includable[*i].metered_bytes_size() as u64)
+            .fold(size, u64::saturating_add);
+        let exceeds = matches!(config.max_gas_per_block, Some(max) if group_gas > max)
+            || matches!(config.max_block_size, Some(max) if group_size > max);
+        if exceeds {
+            continue;
+        }
+        gas = group_gas;
+        size = group_size;
+
+        // every pass adds the transactions whose dependencies were added by the previous ones
+        let mut added = HashSet::new();
+        while !group.is_empty() {
+            group.retain(|i| {
+                let ready = dependencies[*i]
+                    .iter()
+                    .all(|dependency| added.contains(dependency));
+                if ready {
+                    added.insert(*i);
+                    block.push(includable[*i].clone());
+                }
+                !ready
+            });
+        }
+    }
+    block
+}
+
+/// The representative of the group of the `i`th transaction
+fn root(roots: &mut [usize], mut i: usize) -> usize {
+    while roots[i] != i {
+        roots[i] = roots[roots[i]];
+        i = roots[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuel_core_interfaces::common::fuel_tx::{Transaction, UtxoId};
+    use std::sync::Arc;
+
+    fn tx(gas_price: u64, gas_limit: u64, inputs: Vec<Input>) -> ArcTx {
+        Arc::new(Transaction::script(
+            gas_price,
+            gas_limit,
+            0,
+            0,
+            vec![],
+            vec![],
+            inputs,
+            vec![],
+            vec![],
+        ))
+    }
+
+    fn spending(parent: &ArcTx) -> Input {
+        Input::coin_signed(
+            UtxoId::new(parent.id(), 0),
+            Default::default(),
+            100,
+            Default::default(),
+            0,
+            0,
+        )
+    }
+
+    fn ids(txs: &[ArcTx]) -> Vec<TxId> {
+        txs.iter().map(|tx| tx.id()).collect()
+    }
+
+    #[test]
+    fn transactions_are_packed_by_gas_price_up_to_the_gas_limit() {
+        let txs = vec![tx(3, 500, vec![]), tx(2, 600, vec![]), tx(1, 400, vec![])];
+        let config = Config {
+            max_gas_per_block: Some(1000),
+            ..Default::default()
+        };
+
+        let block = pack(txs.clone(), &config);
+
+        // the second transaction doesn't fit, the third one still does
+        assert_eq!(ids(&block), ids(&[txs[0].clone(), txs[2].clone()]));
+    }
+
+    #[test]
+    fn transactions_are_packed_up_to_the_block_size() {
+        let txs = vec![tx(2, 100, vec![]), tx(1, 100, vec![])];
+        let config = Config {
+            max_block_size: Some(txs[0].metered_bytes_size() as u64),
+            ..Default::default()
+        };
+
+        let block = pack(txs.clone(), &config);
+
+        assert_eq!(ids(&block), ids(&txs[..1]));
+    }
+
+    #[test]
+    fn dependent_transactions_are_included_after_the_transactions_they_depend_on() {
+        let parent = tx(1, 100, vec![]);
+        let child = tx(5, 100, vec![spending(&parent)]);
+        let other = tx(3, 100, vec![]);
+
+        let block = pack(
+            vec![child.clone(), other.clone(), parent.clone()],
+            &Config::default(),
+        );
+
+        assert_eq!(ids(&block), ids(&[parent, child, other]));
+    }
+
+    #[test]
+    fn dependent_transactions_are_left_out_with_the_transactions_they_depend_on() {
+        let parent = tx(2, 600, vec![]);
+        let child = tx(1, 100, vec![spending(&parent)]);
+        let other = tx(3, 500, vec![]);
+        let config = Config {
+            max_gas_per_block: Some(1000),
+            ..Default::default()
+        };
+
+        let block = pack(vec![other.clone(), parent, child], &config);
+
+        assert_eq!(ids(&block), ids(&[other]));
+    }
+}
@@ -1,2 +1,34 @@
+use fuel_core_interfaces::common::fuel_types::Address;
+use std::time::Duration;
+
 #[derive(Default, Debug, Clone)]
-pub struct Config {}
+pub struct Config {
+    /// When blocks are produced from the transactions of the txpool
+    pub trigger: Trigger,
+    /// Maximum total gas limit of the transactions of a block, unlimited if not set. The node
+    /// caps it at the limit of its chain.
+    pub max_gas_per_block: Option<u64>,
+    /// Maximum total size in bytes of the transactions of a block, unlimited if not set. The
+    /// node caps it at the limit of its chain.
+    pub max_block_size: Option<u64>,
+    /// Address the fees of the produced blocks are paid to, they are burned if not set
+    pub coinbase_recipient: Option<Address>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// The producer doesn't produce blocks on its own.
+    #[default]
+    Never,
+    /// A block is produced as soon as a transaction is submitted to the txpool.
+    Instant,
+    /// A block is produced every `block_time`, or as soon as the includable transactions of the
+    /// txpool reach either threshold.
+    Interval {
+        block_time: Duration,
+        /// Total gas limit of the includable transactions
+        gas_threshold: Option<u64>,
+        /// Total size in bytes of the includable transactions
+        size_threshold: Option<usize>,
+    },
+}
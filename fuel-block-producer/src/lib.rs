@@ -1,3 +1,4 @@
+pub mod block_builder;
 pub mod config;
 pub mod service;
 
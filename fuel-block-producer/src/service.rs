@@ -1,38 +1,492 @@
-use crate::Config;
-use fuel_core_interfaces::{block_producer::BlockProducerMpsc, txpool};
+use crate::{block_builder, config::Trigger, Config};
+use chrono::{DateTime, Utc};
+use fuel_core_interfaces::{
+    block_producer::{BlockExecutor, BlockProducerMpsc, ExecutedBlock},
+    model::{ArcTx, SealedFuelBlock},
+    txpool::{self, TxStatus, TxStatusBroadcast},
+};
 use parking_lot::Mutex;
-use tokio::{sync::mpsc, task::JoinHandle};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+    time::Instant,
+};
+use tracing::{info, warn};
 
 pub struct Service {
-    join: Mutex<Option<JoinHandle<()>>>,
+    config: Config,
+    join: Mutex<Option<JoinHandle<mpsc::Receiver<BlockProducerMpsc>>>>,
     sender: mpsc::Sender<BlockProducerMpsc>,
+    receiver: Arc<Mutex<Option<mpsc::Receiver<BlockProducerMpsc>>>>,
 }
 
 impl Service {
-    pub async fn new(_config: &Config, _db: ()) -> Result<Self, anyhow::Error> {
-        let (sender, _receiver) = mpsc::channel(100);
+    pub async fn new(config: &Config, _db: ()) -> Result<Self, anyhow::Error> {
+        let (sender, receiver) = mpsc::channel(100);
         Ok(Self {
+            config: config.clone(),
             sender,
             join: Mutex::new(None),
+            receiver: Arc::new(Mutex::new(Some(receiver))),
         })
     }
 
-    pub async fn start(&self, _txpool: txpool::Sender) {
+    /// Starts producing blocks from the transactions of `txpool`, as soon as its `tx_status`
+    /// broadcasts show they reach the thresholds of the trigger or on every block time.
+    pub async fn start(
+        &self,
+        txpool: txpool::Sender,
+        tx_status: broadcast::Receiver<TxStatusBroadcast>,
+        executor: Arc<dyn BlockExecutor>,
+    ) {
         let mut join = self.join.lock();
         if join.is_none() {
-            *join = Some(tokio::spawn(async {}));
+            if let Some(receiver) = self.receiver.lock().take() {
+                let producer = Producer {
+                    config: self.config.clone(),
+                    txpool,
+                    executor,
+                };
+                *join = Some(tokio::spawn(producer.run(receiver, tx_status)));
+            } else {
+                warn!("Starting BlockProducer service that is stopping");
+            }
+        } else {
+            warn!("Service BlockProducer is already started");
         }
     }
 
     pub async fn stop(&self) -> Option<JoinHandle<()>> {
         let join = self.join.lock().take();
-        if join.is_some() {
-            let _ = self.sender.send(BlockProducerMpsc::Stop);
+        if let Some(join) = join {
+            let _ = self.sender.send(BlockProducerMpsc::Stop).await;
+            let receiver = self.receiver.clone();
+            Some(tokio::spawn(async move {
+                let ret = join.await;
+                *receiver.lock() = ret.ok();
+            }))
+        } else {
+            None
         }
-        join
     }
 
     pub fn sender(&self) -> &mpsc::Sender<BlockProducerMpsc> {
         &self.sender
     }
 }
+
+struct Producer {
+    config: Config,
+    txpool: txpool::Sender,
+    executor: Arc<dyn BlockExecutor>,
+}
+
+impl Producer {
+    async fn run(
+        self,
+        mut receiver: mpsc::Receiver<BlockProducerMpsc>,
+        mut tx_status: broadcast::Receiver<TxStatusBroadcast>,
+    ) -> mpsc::Receiver<BlockProducerMpsc> {
+        let mut last_block = Instant::now();
+        let mut txpool_closed = false;
+        loop {
+            let next_block = match self.config.trigger {
+                Trigger::Interval { block_time, .. } => Some(last_block + block_time),
+                Trigger::Never | Trigger::Instant => None,
+            };
+            tokio::select! {
+                event = receiver.recv() => {
+                    match event {
+                        Some(BlockProducerMpsc::Stop) | None => break,
+                        Some(BlockProducerMpsc::ProduceBlocks {
+                            count,
+                            start_time,
+                            block_time_interval,
+                            response,
+                        }) => {
+                            let blocks = self
+                                .produce_blocks(count, start_time, block_time_interval)
+                                .await;
+                            let _ = response.send(blocks);
+                        }
+                        // blocks are only produced on the trigger of the producer for now
+                        Some(BlockProducerMpsc::Produce { .. }) => {}
+                    }
+                }
+                _ = tokio::time::sleep_until(next_block.unwrap_or(last_block)), if next_block.is_some() => {
+                    self.produce().await;
+                    last_block = Instant::now();
+                }
+                status = tx_status.recv(), if !txpool_closed => {
+                    let submitted = match status {
+                        Ok(TxStatusBroadcast { status, .. }) => status == TxStatus::Submitted,
+                        Err(broadcast::error::RecvError::Lagged(_)) => true,
+                        Err(broadcast::error::RecvError::Closed) => {
+                            txpool_closed = true;
+                            false
+                        }
+                    };
+                    if submitted && self.config.trigger == Trigger::Instant {
+                        // the transactions which didn't fit go into the next blocks right away
+                        while self.produce().await {}
+                        last_block = Instant::now();
+                    } else if submitted && self.thresholds_reached().await {
+                        self.produce().await;
+                        last_block = Instant::now();
+                    }
+                }
+            }
+        }
+        receiver
+    }
+
+    /// Produces a block from the includable transactions of the txpool, if there are any.
+    /// Returns whether transactions which didn't fit into the block are left in the txpool.
+    async fn produce(&self) -> bool {
+        let produced = match self.txpool.includable().await {
+            Ok(includable) => {
+                let includable_len = includable.len();
+                let txs = block_builder::pack(includable, &self.config);
+                let txs_len = txs.len();
+                if txs.is_empty() {
+                    Ok(false)
+                } else {
+                    self.produce_block(txs, Utc::now())
+                        .await
+                        .map(|_| txs_len < includable_len)
+                }
+            }
+            Err(e) => Err(e),
+        };
+        produced.unwrap_or_else(|e| {
+            warn!("Failed to produce a block: {}", e);
+            false
+        })
+    }
+
+    /// Produces `count` blocks on demand, the first one at `start_time` and the next ones
+    /// `block_time_interval` apart. Blocks are produced even if the txpool is empty.
+    async fn produce_blocks(
+        &self,
+        count: u32,
+        start_time: DateTime<Utc>,
+        block_time_interval: Duration,
+    ) -> anyhow::Result<Vec<Arc<SealedFuelBlock>>> {
+        let block_time_interval = chrono::Duration::from_std(block_time_interval)?;
+        let mut time = start_time;
+        let mut blocks = vec![];
+        for _ in 0..count {
+            let txs = block_builder::pack(self.txpool.includable().await?, &self.config);
+            blocks.push(self.produce_block(txs, time).await?);
+            time = time + block_time_interval;
+        }
+        Ok(blocks)
+    }
+
+    async fn produce_block(
+        &self,
+        txs: Vec<ArcTx>,
+        time: DateTime<Utc>,
+    ) -> anyhow::Result<Arc<SealedFuelBlock>> {
+        let ExecutedBlock { block, rejected } = self.executor.execute_block(txs, time).await?;
+        // the transactions left out of the block failed to execute at its height, they are
+        // rejected so they aren't packed again and their submitters learn why
+        if !rejected.is_empty() {
+            self.txpool.reject(rejected).await?;
+        }
+        self.txpool
+            .remove(block.transactions.iter().map(|tx| tx.id()).collect())
+            .await?;
+        info!(
+            "Produced block {:#x} at height {} with {} transactions",
+            block.id(),
+            block.header.height,
+            block.transactions.len()
+        );
+        Ok(block)
+    }
+
+    /// Whether the includable transactions reach either threshold of the trigger
+    async fn thresholds_reached(&self) -> bool {
+        let (gas_threshold, size_threshold) = match self.config.trigger {
+            Trigger::Interval {
+                gas_threshold,
+                size_threshold,
+                ..
+            } if gas_threshold.is_some() || size_threshold.is_some() => {
+                (gas_threshold, size_threshold)
+            }
+            _ => return false,
+        };
+        let txs = match self.txpool.includable().await {
+            Ok(txs) => txs,
+            Err(e) => {
+                warn!("Failed to get the includable transactions: {}", e);
+                return false;
+            }
+        };
+
+        let gas = txs
+            .iter()
+            .map(|tx| tx.gas_limit())
+            .fold(0u64, u64::saturating_add);
+        let size: usize = txs.iter().map(|tx| tx.metered_bytes_size()).sum();
+        matches!(gas_threshold, Some(threshold) if gas >= threshold)
+            || matches!(size_threshold, Some(threshold) if size >= threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use fuel_core_interfaces::{
+        common::fuel_tx::Transaction, model::FuelBlock, txpool::TxPoolMpsc,
+    };
+
+    /// Includes the transactions with a gas limit in the blocks it executes, and rejects the
+    /// others
+    #[derive(Default)]
+    struct MockExecutor {
+        blocks: Mutex<Vec<Vec<ArcTx>>>,
+    }
+
+    #[async_trait]
+    impl BlockExecutor for MockExecutor {
+        async fn execute_block(
+            &self,
+            txs: Vec<ArcTx>,
+            time: DateTime<Utc>,
+        ) -> anyhow::Result<ExecutedBlock> {
+            let (txs, rejected): (Vec<_>, Vec<_>) =
+                txs.into_iter().partition(|tx| tx.gas_limit() > 0);
+            let mut blocks = self.blocks.lock();
+            let mut block = FuelBlock::default();
+            block.header.height = (blocks.len() as u32 + 1).into();
+            block.header.time = time;
+            block.transactions = txs.iter().map(|tx| tx.as_ref().clone()).collect();
+            blocks.push(txs);
+            Ok(ExecutedBlock {
+                block: Arc::new(SealedFuelBlock {
+                    block,
+                    consensus: Default::default(),
+                }),
+                rejected: rejected
+                    .iter()
+                    .map(|tx| (tx.id(), "no gas".to_string()))
+                    .collect(),
+            })
+        }
+    }
+
+    /// A txpool holding the transactions of `pool`, all of them are includable
+    fn txpool(pool: Arc<Mutex<Vec<ArcTx>>>) -> txpool::Sender {
+        let (sender, mut receiver) = mpsc::channel(100);
+        tokio::spawn(async move {
+            while let Some(request) = receiver.recv().await {
+                match request {
+                    TxPoolMpsc::Includable { response } => {
+                        let _ = response.send(pool.lock().clone());
+                    }
+                    TxPoolMpsc::Remove { ids } => pool.lock().retain(|tx| !ids.contains(&tx.id())),
+                    TxPoolMpsc::Reject { rejected } => pool
+                        .lock()
+                        .retain(|tx| !rejected.iter().any(|(id, _)| *id == tx.id())),
+                    _ => {}
+                }
+            }
+        });
+        txpool::Sender::new(sender)
+    }
+
+    fn tx(script: u8, gas_limit: u64) -> ArcTx {
+        Arc::new(Transaction::script(
+            0,
+            gas_limit,
+            0,
+            0,
+            vec![script],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ))
+    }
+
+    async fn start(
+        trigger: Trigger,
+        pool: Arc<Mutex<Vec<ArcTx>>>,
+    ) -> (
+        Service,
+        Arc<MockExecutor>,
+        broadcast::Sender<TxStatusBroadcast>,
+    ) {
+        let executor = Arc::new(MockExecutor::default());
+        let (tx_status, _) = broadcast::channel(100);
+        let config = Config {
+            trigger,
+            ..Default::default()
+        };
+        let service = Service::new(&config, ()).await.unwrap();
+        service
+            .start(txpool(pool), tx_status.subscribe(), executor.clone())
+            .await;
+        (service, executor, tx_status)
+    }
+
+    async fn wait_for_blocks(executor: &MockExecutor, count: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while executor.blocks.lock().len() < count {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the blocks weren't produced");
+    }
+
+    #[tokio::test]
+    async fn blocks_are_produced_every_block_time() {
+        let pool = Arc::new(Mutex::new(vec![tx(0, 100), tx(1, 100), tx(2, 100)]));
+        let trigger = Trigger::Interval {
+            block_time: Duration::from_millis(50),
+            gas_threshold: None,
+            size_threshold: None,
+        };
+        let (service, executor, _) = start(trigger, pool.clone()).await;
+
+        wait_for_blocks(&executor, 1).await;
+        assert_eq!(executor.blocks.lock()[0].len(), 3);
+        assert!(pool.lock().is_empty());
+
+        // there are no empty blocks
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(executor.blocks.lock().len(), 1);
+
+        pool.lock().push(tx(3, 100));
+        wait_for_blocks(&executor, 2).await;
+        assert_eq!(executor.blocks.lock()[1].len(), 1);
+        service.stop().await.unwrap().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocks_are_produced_once_the_gas_threshold_is_reached() {
+        let pool = Arc::new(Mutex::new(vec![tx(0, 100)]));
+        let trigger = Trigger::Interval {
+            block_time: Duration::from_secs(3600),
+            gas_threshold: Some(200),
+            size_threshold: None,
+        };
+        let (service, executor, tx_status) = start(trigger, pool.clone()).await;
+        let submitted = |tx: ArcTx| TxStatusBroadcast {
+            tx,
+            status: TxStatus::Submitted,
+        };
+
+        tx_status.send(submitted(pool.lock()[0].clone())).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(executor.blocks.lock().is_empty());
+
+        let second = tx(1, 100);
+        pool.lock().push(second.clone());
+        tx_status.send(submitted(second)).unwrap();
+        wait_for_blocks(&executor, 1).await;
+        assert_eq!(executor.blocks.lock()[0].len(), 2);
+        service.stop().await.unwrap().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocks_are_produced_as_soon_as_transactions_are_submitted() {
+        let pool = Arc::new(Mutex::new(vec![]));
+        let (service, executor, tx_status) = start(Trigger::Instant, pool.clone()).await;
+
+        let tx = tx(0, 100);
+        pool.lock().push(tx.clone());
+        tx_status
+            .send(TxStatusBroadcast {
+                tx,
+                status: TxStatus::Submitted,
+            })
+            .unwrap();
+        wait_for_blocks(&executor, 1).await;
+        assert_eq!(executor.blocks.lock()[0].len(), 1);
+        assert!(pool.lock().is_empty());
+        service.stop().await.unwrap().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocks_are_produced_on_demand_at_the_given_times() {
+        let pool = Arc::new(Mutex::new(vec![tx(0, 100), tx(1, 100)]));
+        let (service, executor, _) = start(Trigger::Never, pool.clone()).await;
+        let start_time = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+
+        let (response, receiver) = tokio::sync::oneshot::channel();
+        service
+            .sender()
+            .send(BlockProducerMpsc::ProduceBlocks {
+                count: 3,
+                start_time,
+                block_time_interval: Duration::from_secs(10),
+                response,
+            })
+            .await
+            .unwrap_or_else(|_| panic!("the producer is stopped"));
+        let blocks = receiver.await.unwrap().unwrap();
+
+        let times = blocks
+            .iter()
+            .map(|block| block.header.time)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec![
+                start_time,
+                start_time + chrono::Duration::seconds(10),
+                start_time + chrono::Duration::seconds(20)
+            ]
+        );
+        // the pending transactions are in the first block
+        let txs = executor
+            .blocks
+            .lock()
+            .iter()
+            .map(Vec::len)
+            .collect::<Vec<_>>();
+        assert_eq!(txs, vec![2, 0, 0]);
+        assert!(pool.lock().is_empty());
+        service.stop().await.unwrap().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn transactions_rejected_by_the_executor_leave_the_txpool() {
+        let pool = Arc::new(Mutex::new(vec![]));
+        let (service, executor, tx_status) = start(Trigger::Instant, pool.clone()).await;
+
+        let (rejected, included) = (tx(0, 0), tx(1, 100));
+        pool.lock().extend([rejected.clone(), included.clone()]);
+        tx_status
+            .send(TxStatusBroadcast {
+                tx: included,
+                status: TxStatus::Submitted,
+            })
+            .unwrap();
+        wait_for_blocks(&executor, 1).await;
+        assert_eq!(executor.blocks.lock()[0].len(), 1);
+        // the rejected transaction isn't packed into the next blocks again
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(pool.lock().is_empty());
+        assert_eq!(executor.blocks.lock().len(), 1);
+        service.stop().await.unwrap().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn no_blocks_are_produced_without_trigger() {
+        let pool = Arc::new(Mutex::new(vec![tx(0, 100)]));
+        let (service, executor, _) = start(Trigger::Never, pool).await;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        service.stop().await.unwrap().await.unwrap();
+        assert!(executor.blocks.lock().is_empty());
+    }
+}
@@ -1,17 +1,21 @@
-use crate::client::schema::contract::ContractBalanceQueryArgs;
+use crate::client::schema::contract::{ContractBalanceQueryArgs, ContractStorageSlotArgs};
 use anyhow::Context;
-use cynic::{http::SurfExt, Id, MutationBuilder, Operation, QueryBuilder};
+use cynic::{
+    http::SurfExt, GraphQlResponse, Id, MutationBuilder, Operation, QueryBuilder,
+    StreamingOperation, SubscriptionBuilder,
+};
 use fuel_vm::prelude::*;
+use futures::{Stream, StreamExt};
 use itertools::Itertools;
 use schema::{
     balance::BalanceArgs,
-    block::BlockByIdArgs,
+    block::{BlockByHeightArgs, BlockByIdArgs, ProduceBlocks, ProduceBlocksArgs},
     coin::{Coin, CoinByIdArgs, SpendQueryElementInput},
     contract::{Contract, ContractByIdArgs},
-    tx::{TxArg, TxIdArgs},
-    Bytes, ContinueTx, ContinueTxArgs, ConversionError, HexString, IdArg, MemoryArgs, RegisterArgs,
-    RunResult, SetBreakpoint, SetBreakpointArgs, SetSingleStepping, SetSingleSteppingArgs, StartTx,
-    StartTxArgs, TransactionId, U64,
+    tx::{SubmitArg, TxIdArgs},
+    Bytes, ContinueTx, ContinueTxArgs, ConversionError, CreateCheckpoint, CreateCheckpointArgs,
+    HexString, IdArg, MemoryArgs, RegisterArgs, RunResult, SetBreakpoint, SetBreakpointArgs,
+    SetSingleStepping, SetSingleSteppingArgs, StartTx, StartTxArgs, TransactionId, U64,
 };
 use std::{
     convert::TryInto,
@@ -19,17 +23,19 @@ use std::{
     net,
     str::{self, FromStr},
 };
-use types::{TransactionResponse, TransactionStatus};
+use types::{DryRunResult, TransactionResponse, TransactionStatus};
 
 use crate::client::schema::tx::DryRunArg;
 pub use schema::{PageDirection, PaginatedResult, PaginationRequest};
 
 pub mod schema;
+mod subscription;
 pub mod types;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FuelClient {
     url: surf::Url,
+    admin_token: Option<String>,
 }
 
 impl FromStr for FuelClient {
@@ -44,7 +50,10 @@ impl FromStr for FuelClient {
         let mut url = surf::Url::parse(&raw_url)
             .with_context(|| format!("Invalid fuel-core URL: {}", str))?;
         url.set_path("/graphql");
-        Ok(Self { url })
+        Ok(Self {
+            url,
+            admin_token: None,
+        })
     }
 }
 
@@ -65,27 +74,37 @@ impl FuelClient {
         Self::from_str(url.as_ref())
     }
 
+    /// Authenticates the requests with the admin token of the node, required by the debugger
+    /// operations if the node has one configured
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
     async fn query<'a, R: 'a>(&self, q: Operation<'a, R>) -> io::Result<R> {
-        let response = surf::post(&self.url)
+        let mut request = surf::post(&self.url);
+        if let Some(token) = &self.admin_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let response = request
             .run_graphql(q)
             .await
             .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
 
-        match (response.data, response.errors) {
-            (Some(d), _) => Ok(d),
-            (_, Some(e)) => {
-                let e = e.into_iter().map(|e| e.message).fold(
-                    String::from("Response errors"),
-                    |mut s, e| {
-                        s.push_str("; ");
-                        s.push_str(e.as_str());
-                        s
-                    },
-                );
-                Err(io::Error::new(io::ErrorKind::Other, e))
-            }
-            _ => Err(io::Error::new(io::ErrorKind::Other, "Invalid response")),
-        }
+        into_result(response)
+    }
+
+    async fn subscribe<R: Send + 'static>(
+        &self,
+        s: StreamingOperation<'static, R>,
+    ) -> io::Result<impl Stream<Item = io::Result<R>> + Unpin> {
+        let mut url = self.url.clone();
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        url.set_scheme(scheme)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Invalid websocket URL"))?;
+        url.set_path("/graphql-ws");
+
+        subscription::subscribe(url.as_str(), s).await
     }
 
     pub async fn health(&self) -> io::Result<bool> {
@@ -115,22 +134,62 @@ impl FuelClient {
         // Disable utxo input checks (exists, unspent, and valid signature)
         utxo_validation: Option<bool>,
     ) -> io::Result<Vec<Receipt>> {
-        let tx = tx.clone().to_bytes();
+        let result = self
+            .dry_run_many_opt(std::slice::from_ref(tx), utxo_validation)
+            .await?
+            .pop()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "missing dry-run result"))?;
+        Ok(result.receipts)
+    }
+
+    /// Dry run the transactions in order, each one seeing the state changes of the previous ones
+    pub async fn dry_run_many(&self, txs: &[Transaction]) -> io::Result<Vec<DryRunResult>> {
+        self.dry_run_many_opt(txs, None).await
+    }
+
+    /// Dry run multiple transactions with options to override the node behavior
+    pub async fn dry_run_many_opt(
+        &self,
+        txs: &[Transaction],
+        // Disable utxo input checks (exists, unspent, and valid signature)
+        utxo_validation: Option<bool>,
+    ) -> io::Result<Vec<DryRunResult>> {
+        let txs = txs
+            .iter()
+            .map(|tx| HexString(Bytes(tx.clone().to_bytes())))
+            .collect();
         let query = schema::tx::DryRun::build(&DryRunArg {
-            tx: HexString(Bytes(tx)),
+            txs,
             utxo_validation,
         });
-        let receipts = self.query(query).await.map(|r| r.dry_run)?;
-        receipts
+        let results = self.query(query).await.map(|r| r.dry_run)?;
+        results
             .into_iter()
-            .map(|receipt| receipt.try_into().map_err(Into::into))
+            .map(|result| result.try_into().map_err(Into::into))
             .collect()
     }
 
+    /// Submits the transaction to the txpool, it is executed in one of the next blocks
     pub async fn submit(&self, tx: &Transaction) -> io::Result<TransactionId> {
+        self.submit_opt(tx, None).await
+    }
+
+    /// Submits the transaction and waits until the block executing it is committed
+    pub async fn submit_and_await_commit(&self, tx: &Transaction) -> io::Result<TransactionId> {
+        self.submit_opt(tx, Some(true)).await
+    }
+
+    /// Submit with options to wait for the transaction to be executed
+    pub async fn submit_opt(
+        &self,
+        tx: &Transaction,
+        // Return once the block executing the transaction is committed
+        await_commit: Option<bool>,
+    ) -> io::Result<TransactionId> {
         let tx = tx.clone().to_bytes();
-        let query = schema::tx::Submit::build(&TxArg {
+        let query = schema::tx::Submit::build(&SubmitArg {
             tx: HexString(Bytes(tx)),
+            await_commit,
         });
 
         let id = self.query(query).await.map(|r| r.submit)?.id;
@@ -233,6 +292,16 @@ impl FuelClient {
         Ok(response)
     }
 
+    /// Writes a checkpoint of the node's database to `path`, a new directory in the checkpoint
+    /// directory of the node
+    pub async fn create_checkpoint(&self, path: &str) -> io::Result<bool> {
+        let operation = CreateCheckpoint::build(CreateCheckpointArgs {
+            path: path.to_string(),
+        });
+        let response = self.query(operation).await?.create_checkpoint;
+        Ok(response)
+    }
+
     pub async fn transaction(&self, id: &str) -> io::Result<Option<TransactionResponse>> {
         let query = schema::tx::TransactionQuery::build(&TxIdArgs { id: id.parse()? });
 
@@ -261,6 +330,23 @@ impl FuelClient {
         Ok(status)
     }
 
+    /// Subscribe to the status changes of a transaction. The stream ends once the transaction
+    /// reaches a final status.
+    pub async fn subscribe_transaction_status(
+        &self,
+        id: &str,
+    ) -> io::Result<impl Stream<Item = io::Result<TransactionStatus>> + Unpin> {
+        let subscription =
+            schema::tx::StatusChangeSubscription::build(&TxIdArgs { id: id.parse()? });
+
+        let stream = self.subscribe(subscription).await?.map(|r| {
+            let status = r?.status_change.try_into()?;
+            Ok(status)
+        });
+
+        Ok(stream)
+    }
+
     /// returns a paginated set of transactions sorted by block height
     pub async fn transactions(
         &self,
@@ -309,6 +395,48 @@ impl FuelClient {
         Ok(block)
     }
 
+    pub async fn block_by_height(&self, height: u64) -> io::Result<Option<schema::block::Block>> {
+        let query = schema::block::BlockByHeightQuery::build(&BlockByHeightArgs {
+            height: Some(U64(height)),
+        });
+
+        let block = self.query(query).await?.block;
+
+        Ok(block)
+    }
+
+    /// Produces `count` blocks on a node producing blocks on demand, the first one at
+    /// `start_time` and the next ones `block_time_interval` seconds apart. Returns the height of
+    /// the latest block.
+    pub async fn produce_blocks(
+        &self,
+        count: u64,
+        start_time: Option<chrono::DateTime<chrono::Utc>>,
+        block_time_interval: Option<u64>,
+    ) -> io::Result<u64> {
+        let operation = ProduceBlocks::build(ProduceBlocksArgs {
+            count: U64(count),
+            start_time,
+            block_time_interval: block_time_interval.map(U64),
+        });
+        let height = self.query(operation).await?.produce_blocks;
+        Ok(height.0)
+    }
+
+    /// Subscribe to the blocks committed by the node
+    pub async fn subscribe_new_blocks(
+        &self,
+    ) -> io::Result<impl Stream<Item = io::Result<schema::block::Block>> + Unpin> {
+        let subscription = schema::block::NewBlocksSubscription::build(());
+
+        let stream = self
+            .subscribe(subscription)
+            .await?
+            .map(|r| r.map(|r| r.new_blocks));
+
+        Ok(stream)
+    }
+
     /// Retrieve multiple blocks
     pub async fn blocks(
         &self,
@@ -324,6 +452,17 @@ impl FuelClient {
     pub async fn coin(&self, id: &str) -> io::Result<Option<Coin>> {
         let query = schema::coin::CoinByIdQuery::build(CoinByIdArgs {
             utxo_id: id.parse()?,
+            at_height: None,
+        });
+        let coin = self.query(query).await?.coin;
+        Ok(coin)
+    }
+
+    /// Retrieve a coin as it was at the block `height`
+    pub async fn coin_at_height(&self, id: &str, height: u64) -> io::Result<Option<Coin>> {
+        let query = schema::coin::CoinByIdQuery::build(CoinByIdArgs {
+            utxo_id: id.parse()?,
+            at_height: Some(height.into()),
         });
         let coin = self.query(query).await?.coin;
         Ok(coin)
@@ -392,19 +531,84 @@ impl FuelClient {
         let query = schema::contract::ContractBalanceQuery::build(ContractBalanceQueryArgs {
             id: id.parse()?,
             asset: asset_id,
+            at_height: None,
         });
 
         let balance = self.query(query).await.unwrap().contract_balance.amount;
         Ok(balance.into())
     }
 
+    /// Retrieve the balance of a contract as it was at the block `height`
+    pub async fn contract_balance_at_height(
+        &self,
+        id: &str,
+        asset: Option<&str>,
+        height: u64,
+    ) -> io::Result<u64> {
+        let asset_id: schema::AssetId = match asset {
+            Some(asset) => asset.parse()?,
+            None => schema::AssetId::default(),
+        };
+
+        let query = schema::contract::ContractBalanceQuery::build(ContractBalanceQueryArgs {
+            id: id.parse()?,
+            asset: asset_id,
+            at_height: Some(height.into()),
+        });
+
+        let balance = self.query(query).await?.contract_balance.amount;
+        Ok(balance.into())
+    }
+
+    /// Retrieve the value of a contract storage slot, at the block `at_height` or the latest one
+    pub async fn contract_storage_slot(
+        &self,
+        id: &str,
+        key: &str,
+        at_height: Option<u64>,
+    ) -> io::Result<schema::Bytes32> {
+        let query = schema::contract::ContractStorageSlotQuery::build(ContractStorageSlotArgs {
+            id: id.parse()?,
+            key: key.parse()?,
+            at_height: at_height.map(Into::into),
+        });
+
+        let slot = self.query(query).await?.contract_storage_slot;
+        Ok(slot.value)
+    }
+
     pub async fn balance(&self, owner: &str, asset_id: Option<&str>) -> io::Result<u64> {
         let owner: schema::Address = owner.parse()?;
         let asset_id: schema::AssetId = match asset_id {
             Some(asset_id) => asset_id.parse()?,
             None => schema::AssetId::default(),
         };
-        let query = schema::balance::BalanceQuery::build(BalanceArgs { owner, asset_id });
+        let query = schema::balance::BalanceQuery::build(BalanceArgs {
+            owner,
+            asset_id,
+            at_height: None,
+        });
+        let balance = self.query(query).await?.balance;
+        Ok(balance.amount.into())
+    }
+
+    /// Retrieve the balance of an owner as it was at the block `height`
+    pub async fn balance_at_height(
+        &self,
+        owner: &str,
+        asset_id: Option<&str>,
+        height: u64,
+    ) -> io::Result<u64> {
+        let owner: schema::Address = owner.parse()?;
+        let asset_id: schema::AssetId = match asset_id {
+            Some(asset_id) => asset_id.parse()?,
+            None => schema::AssetId::default(),
+        };
+        let query = schema::balance::BalanceQuery::build(BalanceArgs {
+            owner,
+            asset_id,
+            at_height: Some(height.into()),
+        });
         let balance = self.query(query).await?.balance;
         Ok(balance.amount.into())
     }
@@ -436,6 +640,25 @@ impl FuelClient {
     }
 }
 
+/// Extracts the data of a response, or combines its errors into a single error.
+fn into_result<R>(response: GraphQlResponse<R>) -> io::Result<R> {
+    match (response.data, response.errors) {
+        (Some(d), _) => Ok(d),
+        (_, Some(e)) => {
+            let e = e.into_iter().map(|e| e.message).fold(
+                String::from("Response errors"),
+                |mut s, e| {
+                    s.push_str("; ");
+                    s.push_str(e.as_str());
+                    s
+                },
+            );
+            Err(io::Error::new(io::ErrorKind::Other, e))
+        }
+        _ => Err(io::Error::new(io::ErrorKind::Other, "Invalid response")),
+    }
+}
+
 #[cfg(any(test, feature = "test-helpers"))]
 impl FuelClient {
     pub async fn transparent_transaction(
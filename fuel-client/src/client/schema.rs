@@ -187,6 +187,22 @@ pub struct ContinueTx {
     pub continue_tx: RunResult,
 }
 
+#[derive(cynic::FragmentArguments, Debug)]
+pub struct CreateCheckpointArgs {
+    pub path: String,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    schema_path = "./assets/schema.sdl",
+    graphql_type = "Mutation",
+    argument_struct = "CreateCheckpointArgs"
+)]
+pub struct CreateCheckpoint {
+    #[arguments(path = &args.path)]
+    pub create_checkpoint: bool,
+}
+
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(schema_path = "./assets/schema.sdl")]
 pub struct RunResult {
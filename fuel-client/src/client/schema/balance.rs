@@ -5,6 +5,8 @@ use crate::client::{PageDirection, PaginatedResult, PaginationRequest};
 pub struct BalanceArgs {
     pub owner: Address,
     pub asset_id: AssetId,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -14,7 +16,7 @@ pub struct BalanceArgs {
     argument_struct = "BalanceArgs"
 )]
 pub struct BalanceQuery {
-    #[arguments(owner = &args.owner, asset_id = &args.asset_id)]
+    #[arguments(owner = &args.owner, asset_id = &args.asset_id, at_height = &args.at_height)]
     pub balance: Balance,
 }
 
@@ -23,6 +25,8 @@ pub struct BalanceQuery {
 pub struct BalanceFilterInput {
     /// Filter coins based on the `owner` field
     pub owner: Address,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::FragmentArguments, Debug)]
@@ -44,14 +48,20 @@ impl From<(Address, PaginationRequest<String>)> for BalancesConnectionArgs {
     fn from(r: (Address, PaginationRequest<String>)) -> Self {
         match r.1.direction {
             PageDirection::Forward => BalancesConnectionArgs {
-                filter: BalanceFilterInput { owner: r.0 },
+                filter: BalanceFilterInput {
+                    owner: r.0,
+                    at_height: None,
+                },
                 after: r.1.cursor,
                 before: None,
                 first: Some(r.1.results as i32),
                 last: None,
             },
             PageDirection::Backward => BalancesConnectionArgs {
-                filter: BalanceFilterInput { owner: r.0 },
+                filter: BalanceFilterInput {
+                    owner: r.0,
+                    at_height: None,
+                },
                 after: None,
                 before: r.1.cursor,
                 first: None,
@@ -115,6 +125,7 @@ mod tests {
         let operation = BalanceQuery::build(BalanceArgs {
             owner: Address::default(),
             asset_id: AssetId::default(),
+            at_height: None,
         });
         insta::assert_snapshot!(operation.query)
     }
@@ -125,6 +136,7 @@ mod tests {
         let operation = BalancesQuery::build(BalancesConnectionArgs {
             filter: BalanceFilterInput {
                 owner: Address::default(),
+                at_height: None,
             },
             after: None,
             before: None,
@@ -1,5 +1,6 @@
 use crate::client::schema::{
-    primitives::Address, primitives::DateTime, schema, BlockId, ConnectionArgs, PageInfo, U64,
+    primitives::Address, primitives::DateTime, schema, BlockId, Bytes32, ConnectionArgs, PageInfo,
+    U64,
 };
 use crate::client::PaginatedResult;
 
@@ -21,6 +22,22 @@ pub struct BlockByIdQuery {
     pub block: Option<Block>,
 }
 
+#[derive(cynic::FragmentArguments, Debug)]
+pub struct BlockByHeightArgs {
+    pub height: Option<U64>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    schema_path = "./assets/schema.sdl",
+    graphql_type = "Query",
+    argument_struct = "BlockByHeightArgs"
+)]
+pub struct BlockByHeightQuery {
+    #[arguments(height = &args.height)]
+    pub block: Option<Block>,
+}
+
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(
     schema_path = "./assets/schema.sdl",
@@ -32,6 +49,30 @@ pub struct BlocksQuery {
     pub blocks: BlockConnection,
 }
 
+#[derive(cynic::FragmentArguments, Debug)]
+pub struct ProduceBlocksArgs {
+    pub count: U64,
+    pub start_time: Option<DateTime>,
+    pub block_time_interval: Option<U64>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    schema_path = "./assets/schema.sdl",
+    graphql_type = "Mutation",
+    argument_struct = "ProduceBlocksArgs"
+)]
+pub struct ProduceBlocks {
+    #[arguments(count = &args.count, start_time = &args.start_time, block_time_interval = &args.block_time_interval)]
+    pub produce_blocks: U64,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl", graphql_type = "Subscription")]
+pub struct NewBlocksSubscription {
+    pub new_blocks: Block,
+}
+
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(schema_path = "./assets/schema.sdl")]
 pub struct BlockConnection {
@@ -65,6 +106,36 @@ pub struct Block {
     pub time: DateTime,
     pub producer: Address,
     pub transactions: Vec<TransactionIdFragment>,
+    pub header: Header,
+    pub consensus: Option<Consensus>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct Header {
+    pub id: BlockId,
+    pub height: U64,
+    pub da_height: U64,
+    pub parent_hash: Bytes32,
+    pub prev_root: Bytes32,
+    pub transactions_root: Bytes32,
+    pub time: DateTime,
+    pub producer: Address,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct Consensus {
+    pub required_stake: U64,
+    pub validators: Vec<ValidatorSignature>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct ValidatorSignature {
+    pub consensus_key: Address,
+    pub stake: U64,
+    pub signature: Address,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -86,6 +157,15 @@ mod tests {
         insta::assert_snapshot!(operation.query)
     }
 
+    #[test]
+    fn block_by_height_query_gql_output() {
+        use cynic::QueryBuilder;
+        let operation = BlockByHeightQuery::build(BlockByHeightArgs {
+            height: Some(U64(0)),
+        });
+        insta::assert_snapshot!(operation.query)
+    }
+
     #[test]
     fn blocks_connection_query_gql_output() {
         use cynic::QueryBuilder;
@@ -97,4 +177,23 @@ mod tests {
         });
         insta::assert_snapshot!(operation.query)
     }
+
+    #[test]
+    fn produce_blocks_mutation_gql_output() {
+        use cynic::MutationBuilder;
+        let operation = ProduceBlocks::build(ProduceBlocksArgs {
+            count: U64(1),
+            start_time: None,
+            block_time_interval: None,
+        });
+        insta::assert_snapshot!(operation.query)
+    }
+
+    #[test]
+    fn new_blocks_subscription_gql_output() {
+        use cynic::SubscriptionBuilder;
+        let operation = NewBlocksSubscription::build(());
+        let operation = serde_json::to_value(&operation).unwrap();
+        insta::assert_snapshot!(operation["query"].as_str().unwrap())
+    }
 }
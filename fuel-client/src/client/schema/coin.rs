@@ -4,6 +4,8 @@ use crate::client::{PageDirection, PaginatedResult, PaginationRequest};
 #[derive(cynic::FragmentArguments, Debug)]
 pub struct CoinByIdArgs {
     pub utxo_id: UtxoId,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -13,7 +15,7 @@ pub struct CoinByIdArgs {
     argument_struct = "CoinByIdArgs"
 )]
 pub struct CoinByIdQuery {
-    #[arguments(utxo_id = &args.utxo_id)]
+    #[arguments(utxo_id = &args.utxo_id, at_height = &args.at_height)]
     pub coin: Option<Coin>,
 }
 
@@ -24,6 +26,8 @@ pub struct CoinFilterInput {
     pub owner: Address,
     /// Filter coins based on the `asset_id` field
     pub asset_id: Option<AssetId>,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::FragmentArguments, Debug)]
@@ -48,6 +52,7 @@ impl From<(Address, AssetId, PaginationRequest<String>)> for CoinsConnectionArgs
                 filter: CoinFilterInput {
                     owner: r.0,
                     asset_id: Some(r.1),
+                    at_height: None,
                 },
                 after: r.2.cursor,
                 before: None,
@@ -58,6 +63,7 @@ impl From<(Address, AssetId, PaginationRequest<String>)> for CoinsConnectionArgs
                 filter: CoinFilterInput {
                     owner: r.0,
                     asset_id: Some(r.1),
+                    at_height: None,
                 },
                 after: None,
                 before: r.2.cursor,
@@ -188,6 +194,7 @@ mod tests {
         use cynic::QueryBuilder;
         let operation = CoinByIdQuery::build(CoinByIdArgs {
             utxo_id: UtxoId::default(),
+            at_height: None,
         });
         insta::assert_snapshot!(operation.query)
     }
@@ -199,6 +206,7 @@ mod tests {
             filter: CoinFilterInput {
                 owner: Address::default(),
                 asset_id: Some(AssetId::default()),
+                at_height: None,
             },
             after: None,
             before: None,
@@ -1,4 +1,4 @@
-use crate::client::schema::{schema, AssetId, ContractId, HexString, PageInfo, Salt, U64};
+use crate::client::schema::{schema, AssetId, Bytes32, ContractId, HexString, PageInfo, Salt, U64};
 use crate::client::{PageDirection, PaginatedResult, PaginationRequest};
 
 #[derive(cynic::FragmentArguments, Debug)]
@@ -29,6 +29,8 @@ pub struct ContractBalance {
 pub struct ContractBalanceQueryArgs {
     pub id: ContractId,
     pub asset: AssetId,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -38,10 +40,37 @@ pub struct ContractBalanceQueryArgs {
     argument_struct = "ContractBalanceQueryArgs"
 )]
 pub struct ContractBalanceQuery {
-    #[arguments(contract = &args.id, asset = &args.asset)]
+    #[arguments(contract = &args.id, asset = &args.asset, at_height = &args.at_height)]
     pub contract_balance: ContractBalance,
 }
 
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct ContractStorageSlot {
+    pub contract: ContractId,
+    pub key: Bytes32,
+    pub value: Bytes32,
+}
+
+#[derive(cynic::FragmentArguments, Debug)]
+pub struct ContractStorageSlotArgs {
+    pub id: ContractId,
+    pub key: Bytes32,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    schema_path = "./assets/schema.sdl",
+    graphql_type = "Query",
+    argument_struct = "ContractStorageSlotArgs"
+)]
+pub struct ContractStorageSlotQuery {
+    #[arguments(contract = &args.id, key = &args.key, at_height = &args.at_height)]
+    pub contract_storage_slot: ContractStorageSlot,
+}
+
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(schema_path = "./assets/schema.sdl")]
 pub struct Contract {
@@ -61,6 +90,8 @@ pub struct ContractIdFragment {
 pub struct ContractBalanceFilterInput {
     /// Filter asset balances based on the `contract` field
     pub contract: ContractId,
+    /// Height of the state to query, the latest one if `None`
+    pub at_height: Option<U64>,
 }
 
 #[derive(cynic::FragmentArguments, Debug)]
@@ -118,14 +149,20 @@ impl From<(ContractId, PaginationRequest<String>)> for ContractBalancesConnectio
     fn from(r: (ContractId, PaginationRequest<String>)) -> Self {
         match r.1.direction {
             PageDirection::Forward => ContractBalancesConnectionArgs {
-                filter: ContractBalanceFilterInput { contract: r.0 },
+                filter: ContractBalanceFilterInput {
+                    contract: r.0,
+                    at_height: None,
+                },
                 after: r.1.cursor,
                 before: None,
                 first: Some(r.1.results as i32),
                 last: None,
             },
             PageDirection::Backward => ContractBalancesConnectionArgs {
-                filter: ContractBalanceFilterInput { contract: r.0 },
+                filter: ContractBalanceFilterInput {
+                    contract: r.0,
+                    at_height: None,
+                },
                 after: None,
                 before: r.1.cursor,
                 first: None,
@@ -147,4 +184,15 @@ mod tests {
         });
         insta::assert_snapshot!(operation.query)
     }
+
+    #[test]
+    fn contract_storage_slot_query_gql_output() {
+        use cynic::QueryBuilder;
+        let operation = ContractStorageSlotQuery::build(ContractStorageSlotArgs {
+            id: ContractId::default(),
+            key: Bytes32::default(),
+            at_height: Some(U64(1)),
+        });
+        insta::assert_snapshot!(operation.query)
+    }
 }
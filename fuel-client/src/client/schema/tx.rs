@@ -1,11 +1,11 @@
-use super::block::BlockIdFragment;
+use super::{block::BlockIdFragment, coin::Coin, contract::ContractIdFragment};
 use crate::client::schema::{
-    schema, Address, ConnectionArgs, ConversionError, HexString, PageInfo, TransactionId,
+    schema, Address, AssetId, Bytes32, ConnectionArgs, ConversionError, HexString, PageInfo,
+    TransactionId, U64,
 };
 use crate::client::types::TransactionResponse;
 use crate::client::{PageDirection, PaginatedResult, PaginationRequest};
 use fuel_types::bytes::Deserializable;
-use fuel_types::Bytes32;
 use std::convert::{TryFrom, TryInto};
 
 #[derive(cynic::FragmentArguments, Debug)]
@@ -25,6 +25,18 @@ pub struct TransactionQuery {
     pub transaction: Option<OpaqueTransaction>,
 }
 
+/// Streams the status transitions of a transaction
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    schema_path = "./assets/schema.sdl",
+    graphql_type = "Subscription",
+    argument_struct = "TxIdArgs"
+)]
+pub struct StatusChangeSubscription {
+    #[arguments(id = &args.id)]
+    pub status_change: TransactionStatus,
+}
+
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(
     schema_path = "./assets/schema.sdl",
@@ -132,7 +144,7 @@ impl TryFrom<ProgramState> for fuel_vm::prelude::ProgramState {
                 u64::from_be_bytes(b)
             }),
             ReturnType::ReturnData => fuel_vm::prelude::ProgramState::ReturnData({
-                Bytes32::try_from(state.data.0 .0.as_slice())?
+                fuel_types::Bytes32::try_from(state.data.0 .0.as_slice())?
             }),
             ReturnType::Revert => fuel_vm::prelude::ProgramState::Revert({
                 let b = state.data.0 .0;
@@ -150,6 +162,7 @@ pub enum TransactionStatus {
     SubmittedStatus(SubmittedStatus),
     SuccessStatus(SuccessStatus),
     FailureStatus(FailureStatus),
+    SqueezedOutStatus(SqueezedOutStatus),
 }
 
 #[derive(cynic::QueryFragment, Debug)]
@@ -175,6 +188,12 @@ pub struct FailureStatus {
     pub program_state: Option<ProgramState>,
 }
 
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct SqueezedOutStatus {
+    pub reason: String,
+}
+
 #[derive(cynic::FragmentArguments, Debug)]
 pub struct TransactionsByOwnerConnectionArgs {
     /// Select transactions based on related `owner`s
@@ -224,14 +243,9 @@ pub struct TransactionsByOwnerQuery {
 
 // mutations
 
-#[derive(cynic::FragmentArguments)]
-pub struct TxArg {
-    pub tx: HexString,
-}
-
 #[derive(cynic::FragmentArguments)]
 pub struct DryRunArg {
-    pub tx: HexString,
+    pub txs: Vec<HexString>,
     pub utxo_validation: Option<bool>,
 }
 
@@ -242,18 +256,104 @@ pub struct DryRunArg {
     argument_struct = "DryRunArg"
 )]
 pub struct DryRun {
-    #[arguments(tx = &args.tx, utxo_validation = &args.utxo_validation)]
-    pub dry_run: Vec<OpaqueReceipt>,
+    #[arguments(txs = &args.txs, utxo_validation = &args.utxo_validation)]
+    pub dry_run: Vec<DryRunTransactionResult>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct DryRunTransactionResult {
+    pub transaction: RawTransaction,
+    pub status: DryRunTransactionStatus,
+    pub receipts: Vec<OpaqueReceipt>,
+    pub fee: DryRunFee,
+    pub coins_created: Vec<Coin>,
+    pub coins_spent: Vec<Coin>,
+    pub contract_changes: Vec<ContractChanges>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct DryRunFee {
+    pub byte_fee: U64,
+    pub gas_fee: U64,
+    pub total: U64,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct ContractChanges {
+    pub contract: ContractIdFragment,
+    pub storage: Vec<StorageSlotChange>,
+    pub balances: Vec<BalanceChange>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct StorageSlotChange {
+    pub key: Bytes32,
+    pub before: Option<Bytes32>,
+    pub after: Option<Bytes32>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct BalanceChange {
+    pub asset_id: AssetId,
+    pub before: U64,
+    pub after: U64,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Transaction", schema_path = "./assets/schema.sdl")]
+pub struct RawTransaction {
+    pub raw_payload: HexString,
+}
+
+impl TryFrom<RawTransaction> for fuel_tx::Transaction {
+    type Error = ConversionError;
+
+    fn try_from(value: RawTransaction) -> Result<Self, Self::Error> {
+        let bytes = value.raw_payload.0 .0;
+        fuel_tx::Transaction::from_bytes(bytes.as_slice())
+            .map_err(ConversionError::TransactionFromBytesError)
+    }
+}
+
+#[derive(cynic::InlineFragments, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub enum DryRunTransactionStatus {
+    DryRunSuccessStatus(DryRunSuccessStatus),
+    DryRunFailureStatus(DryRunFailureStatus),
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct DryRunSuccessStatus {
+    pub program_state: ProgramState,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "./assets/schema.sdl")]
+pub struct DryRunFailureStatus {
+    pub reason: String,
+    pub program_state: Option<ProgramState>,
+}
+
+#[derive(cynic::FragmentArguments)]
+pub struct SubmitArg {
+    pub tx: HexString,
+    pub await_commit: Option<bool>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(
     schema_path = "./assets/schema.sdl",
     graphql_type = "Mutation",
-    argument_struct = "TxArg"
+    argument_struct = "SubmitArg"
 )]
 pub struct Submit {
-    #[arguments(tx = &args.tx)]
+    #[arguments(tx = &args.tx, await_commit = &args.await_commit)]
     pub submit: TransactionIdFragment,
 }
 
@@ -284,6 +384,16 @@ pub mod tests {
         insta::assert_snapshot!(operation.query)
     }
 
+    #[test]
+    fn status_change_subscription_gql_output() {
+        use cynic::SubscriptionBuilder;
+        let operation = StatusChangeSubscription::build(TxIdArgs {
+            id: TransactionId::default(),
+        });
+        let operation = serde_json::to_value(&operation).unwrap();
+        insta::assert_snapshot!(operation["query"].as_str().unwrap())
+    }
+
     #[test]
     fn transactions_connection_query_gql_output() {
         use cynic::QueryBuilder;
@@ -314,7 +424,7 @@ pub mod tests {
         use cynic::MutationBuilder;
         let mut tx = fuel_tx::Transaction::default();
         let query = DryRun::build(DryRunArg {
-            tx: HexString(Bytes(tx.to_bytes())),
+            txs: vec![HexString(Bytes(tx.to_bytes()))],
             utxo_validation: None,
         });
         insta::assert_snapshot!(query.query)
@@ -324,8 +434,9 @@ pub mod tests {
     fn submit_tx_gql_output() {
         use cynic::MutationBuilder;
         let mut tx = fuel_tx::Transaction::default();
-        let query = Submit::build(TxArg {
+        let query = Submit::build(SubmitArg {
             tx: HexString(Bytes(tx.to_bytes())),
+            await_commit: None,
         });
         insta::assert_snapshot!(query.query)
     }
@@ -0,0 +1,148 @@
+//! Minimal client side of the `graphql-transport-ws` protocol, enough to run a single
+//! subscription per websocket connection.
+//!
+//! See <https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md>
+
+use super::into_result;
+use cynic::{GraphQlError, GraphQlResponse, StreamingOperation};
+use futures::{
+    stream::{self, BoxStream},
+    Sink, SinkExt, Stream, StreamExt,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{self, ErrorKind};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Error as WsError, Message},
+};
+
+const PROTOCOL: &str = "graphql-transport-ws";
+
+/// Only one operation runs on each connection, so its id is fixed.
+const SUBSCRIPTION_ID: &str = "1";
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    ConnectionAck,
+    Ping,
+    Pong,
+    Next {
+        payload: GraphQlResponse<serde_json::Value>,
+    },
+    Error {
+        payload: Vec<GraphQlError>,
+    },
+    Complete,
+}
+
+/// Opens a websocket connection to `url` and starts the subscription. Every event of the
+/// subscription is decoded into `R`, the stream ends once the server completes the subscription
+/// or closes the connection.
+pub(crate) async fn subscribe<R>(
+    url: &str,
+    operation: StreamingOperation<'static, R>,
+) -> io::Result<BoxStream<'static, io::Result<R>>>
+where
+    R: Send + 'static,
+{
+    let mut request = url.into_client_request().map_err(other)?;
+    request
+        .headers_mut()
+        .insert("Sec-WebSocket-Protocol", HeaderValue::from_static(PROTOCOL));
+    let (mut socket, _) = connect_async(request).await.map_err(other)?;
+
+    send(&mut socket, json!({ "type": "connection_init" })).await?;
+    loop {
+        match receive(&mut socket).await? {
+            Some(ServerMessage::ConnectionAck) => break,
+            Some(ServerMessage::Ping) => send(&mut socket, json!({ "type": "pong" })).await?,
+            Some(_) => continue,
+            None => {
+                return Err(io::Error::new(
+                    ErrorKind::ConnectionAborted,
+                    "Connection closed before it was acknowledged",
+                ))
+            }
+        }
+    }
+
+    send(
+        &mut socket,
+        json!({
+            "id": SUBSCRIPTION_ID,
+            "type": "subscribe",
+            "payload": operation,
+        }),
+    )
+    .await?;
+
+    Ok(
+        stream::unfold(Some((socket, operation)), |state| async move {
+            let (mut socket, operation) = state?;
+            loop {
+                let event = match receive(&mut socket).await {
+                    Ok(Some(ServerMessage::Next { payload })) => operation
+                        .decode_response(payload)
+                        .map_err(other)
+                        .and_then(into_result),
+                    Ok(Some(ServerMessage::Ping)) => {
+                        match send(&mut socket, json!({ "type": "pong" })).await {
+                            Ok(()) => continue,
+                            Err(e) => Err(e),
+                        }
+                    }
+                    Ok(Some(ServerMessage::Error { payload })) => into_result(GraphQlResponse {
+                        data: None,
+                        errors: Some(payload),
+                    }),
+                    Ok(Some(ServerMessage::ConnectionAck | ServerMessage::Pong)) => continue,
+                    Ok(Some(ServerMessage::Complete) | None) => {
+                        let _ = socket.close(None).await;
+                        return None;
+                    }
+                    Err(e) => Err(e),
+                };
+
+                // the subscription is over once it failed
+                let state = event.is_ok().then(|| (socket, operation));
+                return Some((event, state));
+            }
+        })
+        .boxed(),
+    )
+}
+
+async fn send<S>(socket: &mut S, message: serde_json::Value) -> io::Result<()>
+where
+    S: Sink<Message, Error = WsError> + Unpin,
+{
+    socket
+        .send(Message::Text(message.to_string()))
+        .await
+        .map_err(other)
+}
+
+/// Returns the next protocol message, or `None` if the connection was closed.
+async fn receive<S>(socket: &mut S) -> io::Result<Option<ServerMessage>>
+where
+    S: Stream<Item = Result<Message, WsError>> + Unpin,
+{
+    while let Some(message) = socket.next().await {
+        match message.map_err(other)? {
+            Message::Text(text) => return Ok(Some(serde_json::from_str(&text)?)),
+            Message::Close(_) => return Ok(None),
+            // websocket level pings are answered by tungstenite
+            _ => continue,
+        }
+    }
+    Ok(None)
+}
+
+fn other<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(ErrorKind::Other, e)
+}
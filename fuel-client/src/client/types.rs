@@ -1,7 +1,11 @@
-use crate::client::schema::tx::{OpaqueTransaction, TransactionStatus as SchemaTxStatus};
+use crate::client::schema::coin::Coin;
+use crate::client::schema::tx::{
+    ContractChanges, DryRunFee, DryRunTransactionResult, DryRunTransactionStatus,
+    OpaqueTransaction, TransactionStatus as SchemaTxStatus,
+};
 use crate::client::schema::ConversionError;
 use chrono::{DateTime, Utc};
-use fuel_tx::Transaction;
+use fuel_tx::{Receipt, Transaction};
 use fuel_types::bytes::Deserializable;
 use fuel_vm::prelude::ProgramState;
 use serde::{Deserialize, Serialize};
@@ -28,6 +32,9 @@ pub enum TransactionStatus {
         reason: String,
         program_state: Option<ProgramState>,
     },
+    SqueezedOut {
+        reason: String,
+    },
 }
 
 impl TryFrom<SchemaTxStatus> for TransactionStatus {
@@ -49,6 +56,9 @@ impl TryFrom<SchemaTxStatus> for TransactionStatus {
                 reason: s.reason,
                 program_state: s.program_state.map(TryInto::try_into).transpose()?,
             },
+            SchemaTxStatus::SqueezedOutStatus(s) => {
+                TransactionStatus::SqueezedOut { reason: s.reason }
+            }
         })
     }
 }
@@ -71,3 +81,64 @@ impl TryFrom<OpaqueTransaction> for TransactionResponse {
         })
     }
 }
+
+/// Outcome of a single transaction of a dry-run, no changes were committed by the node.
+#[derive(Debug)]
+pub struct DryRunResult {
+    /// The transaction as it was executed, with its malleable fields set.
+    pub transaction: Transaction,
+    pub status: DryRunStatus,
+    pub receipts: Vec<Receipt>,
+    pub fee: DryRunFee,
+    pub coins_created: Vec<Coin>,
+    pub coins_spent: Vec<Coin>,
+    /// Storage slots and balances modified by the transaction, per contract.
+    pub contract_changes: Vec<ContractChanges>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DryRunStatus {
+    Success {
+        program_state: ProgramState,
+    },
+    Failure {
+        reason: String,
+        program_state: Option<ProgramState>,
+    },
+}
+
+impl TryFrom<DryRunTransactionStatus> for DryRunStatus {
+    type Error = ConversionError;
+
+    fn try_from(status: DryRunTransactionStatus) -> Result<Self, Self::Error> {
+        Ok(match status {
+            DryRunTransactionStatus::DryRunSuccessStatus(s) => DryRunStatus::Success {
+                program_state: s.program_state.try_into()?,
+            },
+            DryRunTransactionStatus::DryRunFailureStatus(s) => DryRunStatus::Failure {
+                reason: s.reason,
+                program_state: s.program_state.map(TryInto::try_into).transpose()?,
+            },
+        })
+    }
+}
+
+impl TryFrom<DryRunTransactionResult> for DryRunResult {
+    type Error = ConversionError;
+
+    fn try_from(value: DryRunTransactionResult) -> Result<Self, Self::Error> {
+        Ok(Self {
+            transaction: value.transaction.try_into()?,
+            status: value.status.try_into()?,
+            receipts: value
+                .receipts
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            fee: value.fee,
+            coins_created: value.coins_created,
+            coins_spent: value.coins_spent,
+            contract_changes: value.contract_changes,
+        })
+    }
+}
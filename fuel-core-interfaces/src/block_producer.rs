@@ -1,4 +1,8 @@
-use crate::model::{BlockHeight, FuelBlock};
+use crate::model::{ArcTx, BlockHeight, FuelBlock, SealedFuelBlock};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fuel_tx::TxId;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::oneshot;
 
 pub enum BlockProducerMpsc {
@@ -7,5 +11,33 @@ pub enum BlockProducerMpsc {
         height: BlockHeight,
         response: oneshot::Sender<Box<FuelBlock>>,
     },
+    /// Produces `count` blocks from the includable transactions of the txpool, the first one at
+    /// `start_time` and the next ones `block_time_interval` apart.
+    ProduceBlocks {
+        count: u32,
+        start_time: DateTime<Utc>,
+        block_time_interval: Duration,
+        response: oneshot::Sender<anyhow::Result<Vec<Arc<SealedFuelBlock>>>>,
+    },
     Stop,
 }
+
+/// A block committed by a [`BlockExecutor`].
+#[derive(Clone, Debug)]
+pub struct ExecutedBlock {
+    pub block: Arc<SealedFuelBlock>,
+    /// The transactions left out of the block because they can't be executed, with the reason.
+    pub rejected: Vec<(TxId, String)>,
+}
+
+/// Executes the blocks assembled by the block producer.
+#[async_trait]
+pub trait BlockExecutor: Send + Sync {
+    /// Executes the transactions in a new block at `time` on top of the latest one, then commits
+    /// and seals it. Transactions which can't be executed are left out of the block.
+    async fn execute_block(
+        &self,
+        txs: Vec<ArcTx>,
+        time: DateTime<Utc>,
+    ) -> anyhow::Result<ExecutedBlock>;
+}
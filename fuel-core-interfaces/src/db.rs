@@ -14,6 +14,10 @@ pub enum Error {
     ChainUninitialized,
     #[error("Invalid database version")]
     InvalidDatabaseVersion,
+    #[error("Database belongs to chain `{found}` instead of `{expected}`")]
+    WrongChain { expected: String, found: String },
+    #[error("Block at height {0} can't be rolled back, its undo data is missing")]
+    MissingUndoData(u32),
     #[error("error occurred in the underlying datastore `{0}`")]
     DatabaseError(Box<dyn std::error::Error + Send + Sync>),
 }
@@ -30,6 +34,8 @@ pub enum KvStoreError {
     Error(Box<dyn std::error::Error + Send + Sync>),
     #[error("resource not found")]
     NotFound,
+    #[error("resource was pruned, the node only keeps the history of recent blocks")]
+    Pruned,
 }
 
 impl From<Error> for KvStoreError {
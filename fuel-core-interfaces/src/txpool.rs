@@ -84,6 +84,12 @@ impl Sender {
             .await
             .map_err(Into::into)
     }
+
+    pub async fn reject(&self, rejected: Vec<(TxId, String)>) -> Result<(), anyhow::Error> {
+        self.send(TxPoolMpsc::Reject { rejected })
+            .await
+            .map_err(Into::into)
+    }
 }
 
 #[derive(Debug)]
@@ -120,6 +126,8 @@ pub enum TxPoolMpsc {
     },
     /// remove transaction from pool needed on user demand. Low priority
     Remove { ids: Vec<TxId> },
+    /// remove transactions which the block producer can't execute, with the reason why.
+    Reject { rejected: Vec<(TxId, String)> },
     /// Iterete over `hashes` and return all hashes that we dont have.
     /// Needed when we receive list of new hashed from peer with
     /// **BroadcastTransactionHashes**, so txpool needs to return
@@ -197,7 +205,13 @@ pub enum Error {
     NotInsertedIoWithdrawalInput,
     #[error("Transaction is not inserted. Maximum depth of dependent transaction chain reached")]
     NotInsertedMaxDepth,
+    #[error("Transaction is not inserted. Its gas limit exceeds the gas limit of a block")]
+    NotInsertedGasLimitAboveBlockLimit,
+    #[error("Transaction is not inserted. Its size exceeds the size limit of a block")]
+    NotInsertedSizeAboveBlockLimit,
     // small todo for now it can pass but in future we should include better messages
     #[error("Transaction removed.")]
     Removed,
+    #[error("Transaction rejected by the block producer: {0}")]
+    Rejected(String),
 }
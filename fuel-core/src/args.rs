@@ -1,8 +1,12 @@
 use clap::Parser;
-use fuel_core::config::{Config, DbType, VMConfig};
+use fuel_core::config::{
+    Config, DbMode, DbType, GraphQLConfig, PruningConfig, ReplicaConfig, VMConfig,
+};
+use fuel_core_interfaces::common::fuel_types::Address;
 use std::str::FromStr;
-use std::{env, io, net, path::PathBuf};
+use std::{env, io, net, path::PathBuf, time::Duration};
 use strum::VariantNames;
+use strum_macros::{EnumString, EnumVariantNames};
 use tracing_subscriber::filter::EnvFilter;
 
 lazy_static::lazy_static! {
@@ -37,10 +41,34 @@ pub struct Opt {
     #[clap(long = "db-type", default_value = "rocks-db", possible_values = &*DbType::VARIANTS, ignore_case = true)]
     pub database_type: DbType,
 
+    /// `read-only-replica` follows the RocksDB database of a primary node at `--db-path` and
+    /// only serves the API, without producing blocks
+    #[clap(long = "db-mode", default_value = "primary", possible_values = &*DbMode::VARIANTS, ignore_case = true)]
+    pub database_mode: DbMode,
+
+    /// The number of seconds between two reads of the latest writes of the primary by a
+    /// read-only replica
+    #[clap(long = "replica-catch-up-interval", default_value = "1")]
+    pub replica_catch_up_interval: u64,
+
+    /// Address of the API of the primary node, e.g. `http://127.0.0.1:4000`. A read-only
+    /// replica forwards mutations to it, or rejects them if not set.
+    #[clap(long = "primary-url")]
+    pub primary_url: Option<String>,
+
+    /// Run the pending database migrations without committing their changes, then exit
+    #[clap(long = "migrate-dry-run")]
+    pub migrate_dry_run: bool,
+
     /// Specify either an alias to a built-in configuration or filepath to a JSON file.
     #[clap(name = "CHAIN_CONFIG", long = "chain", default_value = "local_testnet")]
     pub chain_config: String,
 
+    /// JSON or binary state file the chain starts from, replacing the initial state of the
+    /// chain config
+    #[clap(long = "genesis-state", parse(from_os_str))]
+    pub genesis_state: Option<PathBuf>,
+
     /// Enable logging of backtraces from vm errors
     #[clap(long = "vm-backtrace")]
     pub vm_backtrace: bool,
@@ -58,10 +86,128 @@ pub struct Opt {
     #[clap(long = "min-byte-price", default_value = "0")]
     pub min_byte_price: u64,
 
+    /// Address the fees of the blocks produced by the node are paid to. Fees are burned if
+    /// not set.
+    #[clap(long = "coinbase-recipient")]
+    pub coinbase_recipient: Option<Address>,
+
     /// Enable predicate execution on transaction inputs.
     /// Will reject any transactions with predicates if set to false.
     #[clap(long = "predicates")]
     pub predicates: bool,
+
+    /// The maximum nesting depth of a GraphQL query
+    #[clap(long = "max-query-depth", default_value = "16")]
+    pub max_query_depth: usize,
+
+    /// The maximum complexity of a GraphQL query. Every field costs one, connections
+    /// multiply the cost of their nodes by the requested page size.
+    #[clap(long = "max-query-complexity", default_value = "20000")]
+    pub max_query_complexity: usize,
+
+    /// The maximum number of items returned per page by GraphQL connections
+    #[clap(long = "max-page-size", default_value = "1000")]
+    pub max_page_size: usize,
+
+    /// The maximum number of transactions executed by a single dry-run request
+    #[clap(long = "max-dry-run-transactions", default_value = "100")]
+    pub max_dry_run_transactions: usize,
+
+    /// The maximum number of blocks below the latest one which can be queried with `atHeight`.
+    /// Their changes are reverted on every such query.
+    #[clap(long = "max-history-depth", default_value = "1000")]
+    pub max_history_depth: u32,
+
+    /// Bearer token required to use the debugger and other admin operations of the API.
+    /// They are available to everyone if no token is set.
+    #[clap(long = "admin-token", env = "FUEL_CORE_ADMIN_TOKEN")]
+    pub admin_token: Option<String>,
+
+    /// Directory the database checkpoints requested through the API are written to. The API
+    /// can't create checkpoints if not set.
+    #[clap(long = "checkpoint-dir", parse(from_os_str))]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// Origins allowed to make cross-origin requests to the API, any origin is allowed if none
+    /// is given
+    #[clap(long = "allowed-origins", use_value_delimiter = true)]
+    pub allowed_origins: Option<Vec<String>>,
+
+    /// The maximum size of a request body in bytes
+    #[clap(long = "max-body-size", default_value = "16777216")]
+    pub max_body_size: usize,
+
+    /// The maximum number of seconds spent serving a request before it fails
+    #[clap(long = "request-timeout", default_value = "30")]
+    pub request_timeout: u64,
+
+    /// The maximum number of requests served at the same time, further requests are rejected
+    #[clap(long = "max-concurrent-requests", default_value = "1024")]
+    pub max_concurrent_requests: usize,
+
+    /// The maximum number of requests per second accepted from a single IP address. Requests
+    /// aren't rate limited if not set.
+    #[clap(long = "max-requests-per-second")]
+    pub max_requests_per_second: Option<u32>,
+
+    /// The maximum number of websocket connections open at the same time from a single IP
+    /// address, further subscription connections are rejected
+    #[clap(long = "max-websocket-connections-per-ip", default_value = "16")]
+    pub max_websocket_connections_per_ip: usize,
+
+    /// Delete spent coins and the transactions, receipts and statuses of the blocks older than
+    /// the given number of most recent blocks. The history is kept forever if not set.
+    #[clap(long = "prune-keep-blocks")]
+    pub prune_keep_blocks: Option<u32>,
+
+    /// The number of seconds between two pruning runs
+    #[clap(long = "prune-interval", default_value = "60")]
+    pub prune_interval: u64,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Export the state of the database as a chain config, to start a new chain from it
+    Snapshot {
+        /// File the chain config is written to, printed if not set
+        #[clap(long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+
+        /// `json` writes a chain config, `binary` writes a compact state file which can be
+        /// loaded with `--genesis-state`
+        #[clap(long = "format", default_value = "json", possible_values = &*SnapshotFormat::VARIANTS, ignore_case = true)]
+        format: SnapshotFormat,
+    },
+    /// Revert the blocks above the given height, then exit
+    Rollback {
+        /// Height of the block which becomes the latest one
+        #[clap(long = "height")]
+        height: u32,
+    },
+    /// Write a checkpoint of the database to a new directory, then exit. Running nodes are
+    /// backed up with the `createCheckpoint` mutation of the API instead.
+    Backup {
+        /// Directory the checkpoint is written to, it must not exist yet
+        #[clap(long = "output", parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Replace the database by a copy of a checkpoint of the same chain, then exit. The replaced
+    /// database is kept with the `replaced` extension.
+    Restore {
+        /// Directory of the checkpoint
+        #[clap(long = "checkpoint", parse(from_os_str))]
+        checkpoint: PathBuf,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum SnapshotFormat {
+    Json,
+    Binary,
 }
 
 impl Opt {
@@ -105,12 +251,34 @@ impl Opt {
             port,
             database_path,
             database_type,
+            database_mode,
+            replica_catch_up_interval,
+            primary_url,
+            migrate_dry_run: _,
+            command: _,
             chain_config,
+            genesis_state,
             vm_backtrace,
             utxo_validation,
             min_gas_price,
             min_byte_price,
+            coinbase_recipient,
             predicates,
+            max_query_depth,
+            max_query_complexity,
+            max_page_size,
+            max_dry_run_transactions,
+            max_history_depth,
+            admin_token,
+            checkpoint_dir,
+            allowed_origins,
+            max_body_size,
+            request_timeout,
+            max_concurrent_requests,
+            max_requests_per_second,
+            max_websocket_connections_per_ip,
+            prune_keep_blocks,
+            prune_interval,
         } = self;
 
         let addr = net::SocketAddr::new(ip, port);
@@ -118,11 +286,28 @@ impl Opt {
             addr,
             database_path,
             database_type,
+            database_mode,
             chain_conf: chain_config.as_str().parse()?,
+            genesis_state,
             utxo_validation,
             vm: VMConfig {
                 backtrace: vm_backtrace,
             },
+            graphql: GraphQLConfig {
+                max_depth: max_query_depth,
+                max_complexity: max_query_complexity,
+                max_page_size,
+                max_dry_run_transactions,
+                max_history_depth,
+                admin_token,
+                checkpoint_dir,
+                allowed_origins,
+                max_body_size,
+                request_timeout: Duration::from_secs(request_timeout),
+                max_concurrent_requests,
+                max_requests_per_second,
+                max_websocket_connections_per_ip,
+            },
             txpool: fuel_txpool::Config {
                 min_gas_price,
                 min_byte_price,
@@ -130,10 +315,21 @@ impl Opt {
             },
             predicates,
             block_importer: Default::default(),
-            block_producer: Default::default(),
+            block_producer: fuel_block_producer::Config {
+                coinbase_recipient,
+                ..Default::default()
+            },
             block_executor: Default::default(),
             bft: Default::default(),
             sync: Default::default(),
+            pruning: prune_keep_blocks.map(|keep_blocks| PruningConfig {
+                keep_blocks,
+                interval: Duration::from_secs(prune_interval),
+            }),
+            replica: ReplicaConfig {
+                catch_up_interval: Duration::from_secs(replica_catch_up_interval),
+                primary_url,
+            },
         })
     }
 }
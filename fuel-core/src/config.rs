@@ -1,10 +1,12 @@
 pub mod chain_config;
 pub mod serialization;
+pub mod state_file;
 
 use chain_config::ChainConfig;
 use std::{
     net::{Ipv4Addr, SocketAddr},
     path::PathBuf,
+    time::Duration,
 };
 use strum_macros::{Display, EnumString, EnumVariantNames};
 #[derive(Clone, Debug)]
@@ -12,18 +14,27 @@ pub struct Config {
     pub addr: SocketAddr,
     pub database_path: PathBuf,
     pub database_type: DbType,
+    pub database_mode: DbMode,
     pub chain_conf: ChainConfig,
+    /// State file the chain starts from, replacing the initial state of the chain config. It is
+    /// streamed, so it can be larger than the available memory.
+    pub genesis_state: Option<PathBuf>,
     // default to false until downstream consumers stabilize
     pub utxo_validation: bool,
     // default to false until predicates have fully stabilized
     pub predicates: bool,
     pub vm: VMConfig,
+    pub graphql: GraphQLConfig,
     pub txpool: fuel_txpool::Config,
     pub block_importer: fuel_block_importer::Config,
     pub block_producer: fuel_block_producer::Config,
     pub block_executor: fuel_block_executor::Config,
     pub bft: fuel_core_bft::Config,
     pub sync: fuel_sync::Config,
+    /// The history of old blocks is kept forever if not set
+    pub pruning: Option<PruningConfig>,
+    /// Only used by read-only replicas
+    pub replica: ReplicaConfig,
 }
 
 impl Config {
@@ -32,8 +43,11 @@ impl Config {
             addr: SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 0),
             database_path: Default::default(),
             database_type: DbType::InMemory,
+            database_mode: DbMode::Primary,
             chain_conf: ChainConfig::local_testnet(),
+            genesis_state: None,
             vm: Default::default(),
+            graphql: Default::default(),
             utxo_validation: false,
             predicates: false,
             txpool: Default::default(),
@@ -42,6 +56,37 @@ impl Config {
             block_executor: Default::default(),
             bft: Default::default(),
             sync: Default::default(),
+            pruning: None,
+            replica: Default::default(),
+        }
+    }
+}
+
+/// Settings of the deletion of spent coins and of the transactions of old blocks, along with
+/// their receipts, statuses and owner indexes. Block headers are always kept.
+#[derive(Clone, Debug)]
+pub struct PruningConfig {
+    /// The number of most recent blocks whose history is kept
+    pub keep_blocks: u32,
+    /// The time between two pruning runs
+    pub interval: Duration,
+}
+
+/// Settings of a read-only replica, which serves the API from the database of a primary node.
+#[derive(Clone, Debug)]
+pub struct ReplicaConfig {
+    /// The time between two reads of the latest writes of the primary
+    pub catch_up_interval: Duration,
+    /// Address of the API of the primary, e.g. `http://127.0.0.1:4000`. Mutations are forwarded
+    /// to it, they are rejected if not set.
+    pub primary_url: Option<String>,
+}
+
+impl Default for ReplicaConfig {
+    fn default() -> Self {
+        Self {
+            catch_up_interval: Duration::from_secs(1),
+            primary_url: None,
         }
     }
 }
@@ -51,9 +96,76 @@ pub struct VMConfig {
     pub backtrace: bool,
 }
 
+/// Settings of the GraphQL API
+#[derive(Clone, Debug)]
+pub struct GraphQLConfig {
+    /// The maximum nesting depth of a query
+    pub max_depth: usize,
+    /// The maximum complexity of a query. Every field costs one, connections multiply the cost
+    /// of their nodes by the requested page size.
+    pub max_complexity: usize,
+    /// The maximum number of items a connection returns per page
+    pub max_page_size: usize,
+    /// The maximum number of transactions executed by a single dry-run
+    pub max_dry_run_transactions: usize,
+    /// The maximum number of blocks reverted to answer a query at a past height
+    pub max_history_depth: u32,
+    /// Bearer token required to use the debugger and other admin operations. They are
+    /// available to everyone if no token is set.
+    pub admin_token: Option<String>,
+    /// Directory the database checkpoints requested through the API are written to. They can't
+    /// be requested if not set.
+    pub checkpoint_dir: Option<PathBuf>,
+    /// Origins allowed to make cross-origin requests, any origin is allowed if not set
+    pub allowed_origins: Option<Vec<String>>,
+    /// The maximum size of a request body in bytes
+    pub max_body_size: usize,
+    /// The maximum time spent serving a request before it fails
+    pub request_timeout: Duration,
+    /// The maximum number of requests served at the same time, further requests are rejected
+    pub max_concurrent_requests: usize,
+    /// The maximum number of requests per second accepted from a single IP address, unlimited
+    /// if not set
+    pub max_requests_per_second: Option<u32>,
+    /// The maximum number of websocket connections open at the same time from a single IP
+    /// address
+    pub max_websocket_connections_per_ip: usize,
+}
+
+impl Default for GraphQLConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 16,
+            max_complexity: 20_000,
+            max_page_size: 1_000,
+            max_dry_run_transactions: 100,
+            max_history_depth: 1_000,
+            admin_token: None,
+            checkpoint_dir: None,
+            allowed_origins: None,
+            max_body_size: 16 * 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+            max_concurrent_requests: 1024,
+            max_requests_per_second: None,
+            max_websocket_connections_per_ip: 16,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Display, PartialEq, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab_case")]
 pub enum DbType {
     InMemory,
     RocksDb,
 }
+
+/// How the node uses its database
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum DbMode {
+    /// The node produces blocks and writes them to its database
+    Primary,
+    /// The node opens the RocksDB database of a primary node as a secondary instance, which
+    /// only follows its writes, and serves the API without producing blocks
+    ReadOnlyReplica,
+}
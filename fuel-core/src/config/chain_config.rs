@@ -1,18 +1,28 @@
 use super::serialization::{HexNumber, HexType};
 use crate::model::BlockHeight;
+use fuel_block_producer::config::Trigger;
 use fuel_core_interfaces::common::{
     fuel_tx::ConsensusParameters,
-    fuel_types::{Address, AssetId, Bytes32, Salt},
+    fuel_types::{Address, AssetId, Bytes32, ContractId, Salt, Word},
 };
 use itertools::Itertools;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
-use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, skip_serializing_none};
-use std::{io::ErrorKind, path::PathBuf, str::FromStr};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none, DurationMilliSeconds};
+use std::{
+    fs::File,
+    io::{BufReader, ErrorKind},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
 
 pub const LOCAL_TESTNET: &str = "local_testnet";
 pub const TESTNET_INITIAL_BALANCE: u64 = 10_000_000;
+/// Room for 10 transactions using the maximum gas per transaction
+pub const DEFAULT_MAX_GAS_PER_BLOCK: Word = 10 * ConsensusParameters::DEFAULT.max_gas_per_tx;
+pub const DEFAULT_MAX_BLOCK_SIZE: u64 = 16 * 1024 * 1024;
 
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -21,7 +31,54 @@ pub struct ChainConfig {
     pub block_production: ProductionStrategy,
     #[serde(default)]
     pub initial_state: Option<StateConfig>,
+    /// The chain config file the initial state is streamed from at genesis, when the config
+    /// was loaded from a file. Its `initial_state` isn't kept in memory then.
+    #[serde(skip)]
+    pub initial_state_file: Option<PathBuf>,
     pub transaction_parameters: ConsensusParameters,
+    /// Maximum total gas limit of the transactions of a block
+    #[serde(default = "default_max_gas_per_block")]
+    pub max_gas_per_block: Word,
+    /// Maximum total size in bytes of the transactions of a block, witnesses excluded
+    #[serde(default = "default_max_block_size")]
+    pub max_block_size: u64,
+}
+
+/// A chain config file read without its `initial_state`, which can be far larger than the
+/// available memory. It is streamed from the file when the genesis state is imported.
+#[derive(Deserialize)]
+struct ChainConfigFile {
+    chain_name: String,
+    block_production: ProductionStrategy,
+    #[serde(default, rename = "initial_state")]
+    _initial_state: IgnoredAny,
+    transaction_parameters: ConsensusParameters,
+    #[serde(default = "default_max_gas_per_block")]
+    max_gas_per_block: Word,
+    #[serde(default = "default_max_block_size")]
+    max_block_size: u64,
+}
+
+impl ChainConfigFile {
+    fn into_chain_config(self, path: PathBuf) -> ChainConfig {
+        ChainConfig {
+            chain_name: self.chain_name,
+            block_production: self.block_production,
+            initial_state: None,
+            initial_state_file: Some(path),
+            transaction_parameters: self.transaction_parameters,
+            max_gas_per_block: self.max_gas_per_block,
+            max_block_size: self.max_block_size,
+        }
+    }
+}
+
+fn default_max_gas_per_block() -> Word {
+    DEFAULT_MAX_GAS_PER_BLOCK
+}
+
+fn default_max_block_size() -> u64 {
+    DEFAULT_MAX_BLOCK_SIZE
 }
 
 impl Default for ChainConfig {
@@ -31,6 +88,9 @@ impl Default for ChainConfig {
             block_production: ProductionStrategy::Instant,
             transaction_parameters: ConsensusParameters::DEFAULT,
             initial_state: None,
+            initial_state_file: None,
+            max_gas_per_block: DEFAULT_MAX_GAS_PER_BLOCK,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
         }
     }
 }
@@ -69,9 +129,25 @@ impl ChainConfig {
                 coins: Some(initial_coins),
                 ..StateConfig::default()
             }),
+            initial_state_file: None,
             transaction_parameters: ConsensusParameters::DEFAULT,
+            max_gas_per_block: DEFAULT_MAX_GAS_PER_BLOCK,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
         }
     }
+
+    /// Checks that a transaction using the maximum gas per transaction fits into a block.
+    fn check_block_limits(&self) -> anyhow::Result<()> {
+        let max_gas_per_tx = self.transaction_parameters.max_gas_per_tx;
+        if self.max_gas_per_block < max_gas_per_tx {
+            anyhow::bail!(
+                "max_gas_per_block {} is below the max_gas_per_tx {} of the transactions",
+                self.max_gas_per_block,
+                max_gas_per_tx
+            );
+        }
+        Ok(())
+    }
 }
 
 impl FromStr for ChainConfig {
@@ -81,32 +157,71 @@ impl FromStr for ChainConfig {
         match s {
             LOCAL_TESTNET => Ok(Self::local_testnet()),
             s => {
-                // Attempt to load chain config from path
+                // Attempt to load chain config from path, leaving its initial state in the file
                 let path = PathBuf::from(s.to_string());
-                let contents = std::fs::read(path)?;
-                serde_json::from_slice(&contents).map_err(|e| {
-                    std::io::Error::new(
-                        ErrorKind::InvalidData,
-                        anyhow::Error::new(e).context(format!(
-                            "an error occurred while loading the chain config file {}",
-                            s
-                        )),
-                    )
-                })
+                let reader = BufReader::new(File::open(&path)?);
+                serde_json::from_reader::<_, ChainConfigFile>(reader)
+                    .map(|config| config.into_chain_config(path))
+                    .map_err(anyhow::Error::new)
+                    .and_then(|config| config.check_block_limits().map(|_| config))
+                    .map_err(|e| {
+                        std::io::Error::new(
+                            ErrorKind::InvalidData,
+                            e.context(format!(
+                                "an error occurred while loading the chain config file {}",
+                                s
+                            )),
+                        )
+                    })
             }
         }
     }
 }
 
+#[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum ProductionStrategy {
     Instant,
     Manual,
+    /// Blocks are produced from the transactions of the txpool every `block_time`, or as soon
+    /// as the pending transactions reach the gas or size threshold.
+    Interval {
+        #[serde_as(as = "DurationMilliSeconds<u64>")]
+        #[serde(rename = "block_time_ms")]
+        block_time: Duration,
+        #[serde(default)]
+        gas_threshold: Option<Word>,
+        #[serde(default)]
+        size_threshold: Option<usize>,
+    },
     RoundRobin,
     ProofOfStake,
 }
 
-// TODO: do streaming deserialization to handle large state configs
+impl ProductionStrategy {
+    /// When the block producer produces blocks on its own
+    pub fn trigger(&self) -> Trigger {
+        match self {
+            ProductionStrategy::Interval {
+                block_time,
+                gas_threshold,
+                size_threshold,
+            } => Trigger::Interval {
+                block_time: *block_time,
+                gas_threshold: *gas_threshold,
+                size_threshold: *size_threshold,
+            },
+            ProductionStrategy::Manual => Trigger::Never,
+            // there is no consensus yet, so the other strategies produce blocks like `Instant`
+            ProductionStrategy::Instant
+            | ProductionStrategy::RoundRobin
+            | ProductionStrategy::ProofOfStake => Trigger::Instant,
+        }
+    }
+}
+
+/// Initial state of a chain. States too large for memory are loaded from a state file instead,
+/// see [`super::state_file`].
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
@@ -155,6 +270,19 @@ pub struct ContractConfig {
     pub code: Vec<u8>,
     #[serde_as(as = "HexType")]
     pub salt: Salt,
+    /// derived from the code and salt if None,
+    /// used if contract is forked from another chain to preserve id
+    #[serde_as(as = "Option<HexType>")]
+    #[serde(default)]
+    pub contract_id: Option<ContractId>,
+    /// auto-generated if None
+    #[serde_as(as = "Option<HexType>")]
+    #[serde(default)]
+    pub tx_id: Option<Bytes32>,
+    #[serde_as(as = "Option<HexNumber>")]
+    #[serde(default)]
+    pub output_index: Option<u64>,
+    /// Serialized after the other fields, so state files can be streamed one slot at a time
     #[serde_as(as = "Option<Vec<(HexType, HexType)>>")]
     #[serde(default)]
     pub state: Option<Vec<(Bytes32, Bytes32)>>,
@@ -182,7 +310,29 @@ mod tests {
 
         // test loading config from file path string
         let load_config: ChainConfig = tmp_file.to_string_lossy().into_owned().parse().unwrap();
-        assert_eq!(disk_config, load_config);
+        // the initial state is left in the file, to be streamed from it at genesis
+        let expected = ChainConfig {
+            initial_state: None,
+            initial_state_file: Some(tmp_file),
+            ..disk_config
+        };
+        assert_eq!(expected, load_config);
+    }
+
+    #[test]
+    fn from_str_rejects_blocks_smaller_than_a_transaction() {
+        let tmp_file = tmp_path();
+        let mut disk_config = ChainConfig::local_testnet();
+        disk_config.max_gas_per_block = disk_config.transaction_parameters.max_gas_per_tx - 1;
+        let json = serde_json::to_string_pretty(&disk_config).unwrap();
+        write(tmp_file.clone(), json).unwrap();
+
+        let err = tmp_file
+            .to_string_lossy()
+            .into_owned()
+            .parse::<ChainConfig>()
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
     }
 
     #[test]
@@ -229,6 +379,42 @@ mod tests {
         assert_eq!(config, deserialized_config);
     }
 
+    #[test]
+    fn interval_block_time_is_in_milliseconds() {
+        let strategy: ProductionStrategy =
+            serde_json::from_str(r#"{"Interval":{"block_time_ms":500,"gas_threshold":1000}}"#)
+                .unwrap();
+        assert_eq!(
+            strategy,
+            ProductionStrategy::Interval {
+                block_time: Duration::from_millis(500),
+                gas_threshold: Some(1000),
+                size_threshold: None,
+            }
+        );
+
+        let config = ChainConfig {
+            block_production: strategy,
+            ..ChainConfig::local_testnet()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let deserialized_config: ChainConfig = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(config, deserialized_config);
+    }
+
+    #[test]
+    fn block_limits_default_when_missing() {
+        let mut json = serde_json::to_value(ChainConfig::default()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.remove("max_gas_per_block");
+        fields.remove("max_block_size");
+
+        let config: ChainConfig = serde_json::from_value(json).unwrap();
+
+        assert_eq!(config.max_gas_per_block, DEFAULT_MAX_GAS_PER_BLOCK);
+        assert_eq!(config.max_block_size, DEFAULT_MAX_BLOCK_SIZE);
+    }
+
     #[test]
     fn snapshot_simple_contract() {
         let config = test_config_contract(false, false);
@@ -315,6 +501,9 @@ mod tests {
                     salt: Default::default(),
                     state,
                     balances,
+                    contract_id: None,
+                    tx_id: None,
+                    output_index: None,
                 }]),
                 ..Default::default()
             }),
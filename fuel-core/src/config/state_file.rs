@@ -0,0 +1,807 @@
+//! Reads and writes the initial state of a chain as a stream of [`StateEntry`], so state
+//! files far larger than the available memory can be imported and exported.
+//!
+//! Two formats are supported: the JSON encoding of a [`StateConfig`], or of a chain config
+//! holding it as `initial_state`, and a compact binary format made of one record per entry.
+//! Both are streamed one coin, contract, storage slot or contract balance at a time.
+
+use super::{
+    chain_config::{CoinConfig, ContractConfig, StateConfig},
+    serialization::{HexNumber, HexType},
+};
+use crate::model::BlockHeight;
+use anyhow::{anyhow, Context};
+use fuel_core_interfaces::common::fuel_types::{Address, AssetId, Bytes32, ContractId, Salt};
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_with::{de::DeserializeAsWrap, serde_as, DeserializeAs, SerializeAs};
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    marker::PhantomData,
+    ops::Deref,
+    path::Path,
+};
+
+/// Starts every binary state file.
+const MAGIC: &[u8] = b"FUELSTATE";
+/// Version of the binary format, written after [`MAGIC`].
+const BINARY_FORMAT_VERSION: u8 = 1;
+
+/// A part of the initial state of a chain.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StateEntry {
+    Height(BlockHeight),
+    Coin(CoinConfig),
+    /// A contract, the storage slot and balance entries following it belong to it.
+    Contract(ContractConfig),
+    ContractState(Bytes32, Bytes32),
+    ContractBalance(AssetId, u64),
+}
+
+impl StateConfig {
+    /// The entries of the state, with storage slots and balances inlined in their contracts.
+    /// Every entry is cloned only once it is visited.
+    pub fn entries(&self) -> impl Iterator<Item = StateEntry> + '_ {
+        self.height
+            .map(StateEntry::Height)
+            .into_iter()
+            .chain(self.coins.iter().flatten().cloned().map(StateEntry::Coin))
+            .chain(
+                self.contracts
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .map(StateEntry::Contract),
+            )
+    }
+
+    /// Collects the entries of a state into a config.
+    pub fn from_entries(
+        entries: impl IntoIterator<Item = StateEntry>,
+    ) -> anyhow::Result<StateConfig> {
+        let mut state = StateConfig::default();
+        for entry in entries {
+            match entry {
+                StateEntry::Height(height) => state.height = Some(height),
+                StateEntry::Coin(coin) => state.coins.get_or_insert_with(Vec::new).push(coin),
+                StateEntry::Contract(contract) => {
+                    state.contracts.get_or_insert_with(Vec::new).push(contract)
+                }
+                StateEntry::ContractState(key, value) => last_contract(&mut state)?
+                    .state
+                    .get_or_insert_with(Vec::new)
+                    .push((key, value)),
+                StateEntry::ContractBalance(asset_id, amount) => last_contract(&mut state)?
+                    .balances
+                    .get_or_insert_with(Vec::new)
+                    .push((asset_id, amount)),
+            }
+        }
+        Ok(state)
+    }
+}
+
+fn last_contract(state: &mut StateConfig) -> anyhow::Result<&mut ContractConfig> {
+    state
+        .contracts
+        .as_mut()
+        .and_then(|contracts| contracts.last_mut())
+        .ok_or_else(|| anyhow!("Contract entry expected before its storage"))
+}
+
+/// Streams the entries of a JSON or binary state file into `sink`.
+pub fn read_state_file<F>(path: &Path, sink: F) -> anyhow::Result<()>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    let mut reader = BufReader::new(
+        File::open(path).with_context(|| format!("Failed to open state file {:?}", path))?,
+    );
+    let result = if reader.fill_buf()?.starts_with(MAGIC) {
+        read_binary(reader, sink)
+    } else {
+        read_json(reader, sink)
+    };
+    result.with_context(|| format!("Failed to load state file {:?}", path))
+}
+
+fn read_json<R, F>(reader: R, mut sink: F) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    StateSeed { sink: &mut sink }.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(())
+}
+
+/// The fields of a chain config besides its `initial_state`, skipped when a chain config is
+/// read as a state file.
+const CHAIN_CONFIG_FIELDS: &[&str] = &[
+    "chain_name",
+    "block_production",
+    "transaction_parameters",
+    "max_gas_per_block",
+    "max_block_size",
+];
+
+/// The fields a JSON state file can have: those of a state config, or those of a chain config
+/// such as the ones written by `snapshot --format json`.
+const STATE_FILE_FIELDS: &[&str] = &[
+    "coins",
+    "contracts",
+    "height",
+    "initial_state",
+    "chain_name",
+    "block_production",
+    "transaction_parameters",
+    "max_gas_per_block",
+    "max_block_size",
+];
+
+/// Visits the fields of a JSON state config, or of a chain config and its `initial_state`,
+/// passing the coins and contracts to the sink as soon as they are parsed.
+struct StateSeed<'a, F> {
+    sink: &'a mut F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for StateSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+/// Visits the optional `initial_state` of a chain config.
+struct InitialStateSeed<'a, F> {
+    sink: &'a mut F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for InitialStateSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for InitialStateSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an optional state config")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        StateSeed { sink: self.sink }.deserialize(deserializer)
+    }
+}
+
+impl<'de, F> Visitor<'de> for StateSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a state config")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "coins" => map.next_value_seed(EntriesSeed {
+                    sink: &mut *self.sink,
+                    entry: StateEntry::Coin,
+                    _item: PhantomData,
+                })?,
+                "contracts" => map.next_value_seed(ContractsSeed {
+                    sink: &mut *self.sink,
+                })?,
+                "height" => {
+                    if let Height(Some(height)) = map.next_value()? {
+                        (self.sink)(StateEntry::Height(height)).map_err(de::Error::custom)?;
+                    }
+                }
+                "initial_state" => map.next_value_seed(InitialStateSeed {
+                    sink: &mut *self.sink,
+                })?,
+                key if CHAIN_CONFIG_FIELDS.contains(&key) => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+                key => return Err(de::Error::unknown_field(key, STATE_FILE_FIELDS)),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct Height(#[serde_as(as = "Option<HexNumber>")] Option<BlockHeight>);
+
+/// Passes every item of an optional JSON array to the sink.
+struct EntriesSeed<'a, F, T> {
+    sink: &'a mut F,
+    entry: fn(T) -> StateEntry,
+    _item: PhantomData<T>,
+}
+
+impl<'de, F, T> DeserializeSeed<'de> for EntriesSeed<'_, F, T>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+    T: DeserializeOwned,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, F, T> Visitor<'de> for EntriesSeed<'_, F, T>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+    T: DeserializeOwned,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an optional array")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(item) = seq.next_element::<T>()? {
+            (self.sink)((self.entry)(item)).map_err(de::Error::custom)?;
+        }
+        Ok(())
+    }
+}
+
+/// Passes every contract of an optional JSON array to the sink, followed by its storage slots
+/// and balances one at a time.
+struct ContractsSeed<'a, F> {
+    sink: &'a mut F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for ContractsSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for ContractsSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an optional array of contracts")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(ContractSeed {
+                sink: &mut *self.sink,
+            })?
+            .is_some()
+        {}
+        Ok(())
+    }
+}
+
+/// Visits the fields of a JSON contract. The contract is passed to the sink once its `state` or
+/// `balances` start, so the fields identifying it have to come before them, as they are
+/// serialized.
+struct ContractSeed<'a, F> {
+    sink: &'a mut F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for ContractSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<F> ContractSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    /// Passes the contract to the sink, unless it was already.
+    fn emit<E: de::Error>(&mut self, contract: &mut Option<ContractFields>) -> Result<(), E> {
+        if let Some(fields) = contract.take() {
+            let contract = ContractConfig {
+                code: fields.code.ok_or_else(|| E::missing_field("code"))?,
+                salt: fields.salt.ok_or_else(|| E::missing_field("salt"))?,
+                contract_id: fields.contract_id,
+                tx_id: fields.tx_id,
+                output_index: fields.output_index,
+                state: None,
+                balances: None,
+            };
+            (self.sink)(StateEntry::Contract(contract)).map_err(E::custom)?;
+        }
+        Ok(())
+    }
+}
+
+/// The fields of a contract besides its storage slots and balances.
+#[derive(Default)]
+struct ContractFields {
+    code: Option<Vec<u8>>,
+    salt: Option<Salt>,
+    contract_id: Option<ContractId>,
+    tx_id: Option<Bytes32>,
+    output_index: Option<u64>,
+}
+
+impl ContractFields {
+    /// The fields to set `key` of, if the contract wasn't passed to the sink yet.
+    fn get<'a, E: de::Error>(
+        contract: &'a mut Option<ContractFields>,
+        key: &str,
+    ) -> Result<&'a mut ContractFields, E> {
+        contract.as_mut().ok_or_else(|| {
+            E::custom(format!(
+                "field `{}` of a contract must come before its `state` and `balances`",
+                key
+            ))
+        })
+    }
+}
+
+type Hex<T> = DeserializeAsWrap<T, HexType>;
+type OptionalHex<T, U = HexType> = DeserializeAsWrap<Option<T>, Option<U>>;
+
+impl<'de, F> Visitor<'de> for ContractSeed<'_, F>
+where
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a contract config")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut contract = Some(ContractFields::default());
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "code" => {
+                    let code = map.next_value::<Hex<_>>()?.into_inner();
+                    ContractFields::get(&mut contract, &key)?.code = Some(code);
+                }
+                "salt" => {
+                    let salt = map.next_value::<Hex<_>>()?.into_inner();
+                    ContractFields::get(&mut contract, &key)?.salt = Some(salt);
+                }
+                "contract_id" => {
+                    let contract_id = map.next_value::<OptionalHex<_>>()?.into_inner();
+                    ContractFields::get(&mut contract, &key)?.contract_id = contract_id;
+                }
+                "tx_id" => {
+                    let tx_id = map.next_value::<OptionalHex<_>>()?.into_inner();
+                    ContractFields::get(&mut contract, &key)?.tx_id = tx_id;
+                }
+                "output_index" => {
+                    let output_index = map.next_value::<OptionalHex<_, HexNumber>>()?.into_inner();
+                    ContractFields::get(&mut contract, &key)?.output_index = output_index;
+                }
+                "state" => {
+                    self.emit(&mut contract)?;
+                    map.next_value_seed(EntriesSeed {
+                        sink: &mut *self.sink,
+                        entry: |slot: DeserializeAsWrap<_, (HexType, HexType)>| {
+                            let (key, value) = slot.into_inner();
+                            StateEntry::ContractState(key, value)
+                        },
+                        _item: PhantomData,
+                    })?
+                }
+                "balances" => {
+                    self.emit(&mut contract)?;
+                    map.next_value_seed(EntriesSeed {
+                        sink: &mut *self.sink,
+                        entry: |balance: DeserializeAsWrap<_, (HexType, HexNumber)>| {
+                            let (asset_id, amount) = balance.into_inner();
+                            StateEntry::ContractBalance(asset_id, amount)
+                        },
+                        _item: PhantomData,
+                    })?
+                }
+                // unknown fields are ignored, like they are by the derived deserializer
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        self.emit(&mut contract)
+    }
+}
+
+/// A record of the binary format. Unlike the JSON config, every storage slot and balance of a
+/// contract is a record of its own.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+enum Record {
+    Height(BlockHeight),
+    Coin {
+        #[serde_as(as = "Option<RawBytes>")]
+        tx_id: Option<Bytes32>,
+        output_index: Option<u64>,
+        block_created: Option<BlockHeight>,
+        maturity: Option<BlockHeight>,
+        #[serde_as(as = "RawBytes")]
+        owner: Address,
+        amount: u64,
+        #[serde_as(as = "RawBytes")]
+        asset_id: AssetId,
+    },
+    Contract {
+        code: Vec<u8>,
+        #[serde_as(as = "RawBytes")]
+        salt: Salt,
+        #[serde_as(as = "Option<RawBytes>")]
+        contract_id: Option<ContractId>,
+        #[serde_as(as = "Option<RawBytes>")]
+        tx_id: Option<Bytes32>,
+        output_index: Option<u64>,
+    },
+    ContractState(
+        #[serde_as(as = "RawBytes")] Bytes32,
+        #[serde_as(as = "RawBytes")] Bytes32,
+    ),
+    ContractBalance(#[serde_as(as = "RawBytes")] AssetId, u64),
+    /// Marks the end of the file, so truncated files are detected.
+    End,
+}
+
+/// Encodes the 32 bytes types as plain bytes instead of the hex strings they serialize to.
+struct RawBytes;
+
+impl<T: Deref<Target = [u8; 32]>> SerializeAs<T> for RawBytes {
+    fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        source.deref().serialize(serializer)
+    }
+}
+
+impl<'de, T: From<[u8; 32]>> DeserializeAs<'de, T> for RawBytes {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        <[u8; 32]>::deserialize(deserializer).map(T::from)
+    }
+}
+
+fn read_binary<R, F>(mut reader: R, mut sink: F) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(StateEntry) -> anyhow::Result<()>,
+{
+    let mut header = [0u8; MAGIC.len() + 1];
+    reader.read_exact(&mut header)?;
+    if !header.starts_with(MAGIC) {
+        return Err(anyhow!("Not a binary state file"));
+    }
+    if header[MAGIC.len()] != BINARY_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Unsupported state file version {}",
+            header[MAGIC.len()]
+        ));
+    }
+
+    loop {
+        let entry = match bincode::deserialize_from(&mut reader)? {
+            Record::Height(height) => StateEntry::Height(height),
+            Record::Coin {
+                tx_id,
+                output_index,
+                block_created,
+                maturity,
+                owner,
+                amount,
+                asset_id,
+            } => StateEntry::Coin(CoinConfig {
+                tx_id,
+                output_index,
+                block_created,
+                maturity,
+                owner,
+                amount,
+                asset_id,
+            }),
+            Record::Contract {
+                code,
+                salt,
+                contract_id,
+                tx_id,
+                output_index,
+            } => StateEntry::Contract(ContractConfig {
+                code,
+                salt,
+                state: None,
+                balances: None,
+                contract_id,
+                tx_id,
+                output_index,
+            }),
+            Record::ContractState(key, value) => StateEntry::ContractState(key, value),
+            Record::ContractBalance(asset_id, amount) => {
+                StateEntry::ContractBalance(asset_id, amount)
+            }
+            Record::End => return Ok(()),
+        };
+        sink(entry)?;
+    }
+}
+
+/// Writes state entries in the binary format.
+pub struct BinaryStateWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> BinaryStateWriter<W> {
+    pub fn new(mut writer: W) -> anyhow::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[BINARY_FORMAT_VERSION])?;
+        Ok(Self { writer })
+    }
+
+    pub fn write(&mut self, entry: StateEntry) -> anyhow::Result<()> {
+        match entry {
+            StateEntry::Height(height) => self.write_record(&Record::Height(height)),
+            StateEntry::Coin(coin) => self.write_record(&Record::Coin {
+                tx_id: coin.tx_id,
+                output_index: coin.output_index,
+                block_created: coin.block_created,
+                maturity: coin.maturity,
+                owner: coin.owner,
+                amount: coin.amount,
+                asset_id: coin.asset_id,
+            }),
+            StateEntry::Contract(contract) => {
+                self.write_record(&Record::Contract {
+                    code: contract.code,
+                    salt: contract.salt,
+                    contract_id: contract.contract_id,
+                    tx_id: contract.tx_id,
+                    output_index: contract.output_index,
+                })?;
+                for (key, value) in contract.state.into_iter().flatten() {
+                    self.write_record(&Record::ContractState(key, value))?;
+                }
+                for (asset_id, amount) in contract.balances.into_iter().flatten() {
+                    self.write_record(&Record::ContractBalance(asset_id, amount))?;
+                }
+                Ok(())
+            }
+            StateEntry::ContractState(key, value) => {
+                self.write_record(&Record::ContractState(key, value))
+            }
+            StateEntry::ContractBalance(asset_id, amount) => {
+                self.write_record(&Record::ContractBalance(asset_id, amount))
+            }
+        }
+    }
+
+    /// Marks the end of the state and returns the underlying writer.
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.write_record(&Record::End)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_record(&mut self, record: &Record) -> anyhow::Result<()> {
+        bincode::serialize_into(&mut self.writer, record)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::chain_config::ChainConfig;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::io::Cursor;
+
+    fn state() -> StateConfig {
+        let mut rng = StdRng::seed_from_u64(10);
+        let coin = |rng: &mut StdRng| CoinConfig {
+            tx_id: Some(rng.gen()),
+            output_index: Some(rng.gen_range(0..255)),
+            block_created: None,
+            maturity: Some(rng.gen::<u32>().into()),
+            owner: rng.gen(),
+            amount: rng.gen(),
+            asset_id: rng.gen(),
+        };
+        StateConfig {
+            coins: Some(vec![coin(&mut rng), coin(&mut rng)]),
+            contracts: Some(vec![ContractConfig {
+                code: vec![1, 2, 3],
+                salt: rng.gen(),
+                state: Some(vec![(rng.gen(), rng.gen()), (rng.gen(), rng.gen())]),
+                balances: Some(vec![(rng.gen(), rng.gen())]),
+                contract_id: Some(rng.gen()),
+                tx_id: None,
+                output_index: None,
+            }]),
+            height: Some(42u32.into()),
+        }
+    }
+
+    fn collect(read: impl FnOnce(&mut dyn FnMut(StateEntry) -> anyhow::Result<()>)) -> StateConfig {
+        let mut entries = vec![];
+        read(&mut |entry| {
+            entries.push(entry);
+            Ok(())
+        });
+        StateConfig::from_entries(entries).unwrap()
+    }
+
+    #[test]
+    fn json_state_is_streamed() {
+        let json = serde_json::to_vec(&state()).unwrap();
+
+        let streamed = collect(|sink| read_json(json.as_slice(), sink).unwrap());
+        assert_eq!(streamed, state());
+    }
+
+    #[test]
+    fn json_contract_storage_is_streamed_in_separate_entries() {
+        let state = state();
+        let json = serde_json::to_vec(&state).unwrap();
+
+        let mut entries = vec![];
+        read_json(json.as_slice(), |entry| {
+            entries.push(entry);
+            Ok(())
+        })
+        .unwrap();
+
+        let contract = state.contracts.unwrap().remove(0);
+        let (slots, balances) = (
+            contract.state.clone().unwrap(),
+            contract.balances.clone().unwrap(),
+        );
+        let start = entries
+            .iter()
+            .position(|entry| matches!(entry, StateEntry::Contract(_)))
+            .unwrap();
+        let contract_entries = &entries[start..start + 4];
+        assert_eq!(
+            contract_entries,
+            [
+                StateEntry::Contract(ContractConfig {
+                    state: None,
+                    balances: None,
+                    ..contract
+                }),
+                StateEntry::ContractState(slots[0].0, slots[0].1),
+                StateEntry::ContractState(slots[1].0, slots[1].1),
+                StateEntry::ContractBalance(balances[0].0, balances[0].1),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_contract_fields_after_its_storage_are_rejected() {
+        let json = format!(
+            r#"{{ "contracts": [{{ "code": "0x01", "salt": "{:#x}", "state": [], "contract_id": "{:#x}" }}] }}"#,
+            Salt::zeroed(),
+            ContractId::zeroed()
+        );
+
+        let error = read_json(json.as_bytes(), |_| Ok(())).unwrap_err();
+        assert!(error.to_string().contains("must come before"));
+    }
+
+    #[test]
+    fn json_chain_config_state_is_streamed() {
+        let chain_config = ChainConfig {
+            initial_state: Some(state()),
+            ..ChainConfig::local_testnet()
+        };
+        let json = serde_json::to_vec(&chain_config).unwrap();
+
+        let streamed = collect(|sink| read_json(json.as_slice(), sink).unwrap());
+        assert_eq!(streamed, state());
+    }
+
+    #[test]
+    fn json_state_with_unknown_fields_is_rejected() {
+        let json = br#"{ "coins": [], "initial_stat": { "coins": [] } }"#;
+
+        assert!(read_json(&json[..], |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn binary_state_roundtrips() {
+        let mut writer = BinaryStateWriter::new(vec![]).unwrap();
+        for entry in state().entries() {
+            writer.write(entry).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        assert!(bytes.len() < serde_json::to_vec(&state()).unwrap().len());
+
+        let streamed = collect(|sink| read_binary(Cursor::new(&bytes), sink).unwrap());
+        assert_eq!(streamed, state());
+
+        // the end marker is missing if the file was truncated
+        assert!(read_binary(Cursor::new(&bytes[..bytes.len() - 4]), |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn state_file_format_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (json_path, binary_path) =
+            (dir.path().join("state.json"), dir.path().join("state.bin"));
+        std::fs::write(&json_path, serde_json::to_vec(&state()).unwrap()).unwrap();
+        let mut writer = BinaryStateWriter::new(File::create(&binary_path).unwrap()).unwrap();
+        for entry in state().entries() {
+            writer.write(entry).unwrap();
+        }
+        writer.finish().unwrap();
+
+        for path in [json_path, binary_path] {
+            let streamed = collect(|sink| read_state_file(&path, sink).unwrap());
+            assert_eq!(streamed, state());
+        }
+    }
+}
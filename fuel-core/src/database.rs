@@ -35,6 +35,7 @@ use tempfile::TempDir;
 
 use self::columns::METADATA;
 
+pub mod backup;
 pub mod balances;
 pub mod block;
 pub mod code_root;
@@ -42,17 +43,22 @@ pub mod coin;
 pub mod contracts;
 pub mod delegates_index;
 pub mod deposit_coin;
+pub mod merkle;
 pub mod metadata;
+pub mod migration;
+pub mod pruning;
 mod receipts;
+pub mod rollback;
+mod snapshot;
 pub mod staking_diffs;
 pub mod state;
 pub mod transaction;
 pub mod transactional;
 pub mod validator_set;
 
-// Crude way to invalidate incompatible databases,
-// can be used to perform migrations in the future.
-pub const VERSION: u32 = 0;
+/// Version of the on-disk layout, databases of older versions are upgraded by the
+/// [`migration::migrations`] registered for every version in between.
+pub const VERSION: u32 = 1;
 
 pub mod columns {
     pub const METADATA: u32 = 0;
@@ -80,10 +86,27 @@ pub mod columns {
     pub const STAKING_DIFFS: u32 = 16;
     /// Maps delegate address with validator_set_diff index where last delegate change happened
     pub const DELEGATES_INDEX: u32 = 17;
+    /// Maps block id -> consensus data the block was sealed with
+    pub const BLOCK_CONSENSUS: u32 = 18;
+    /// Maps block height -> operations reverting the execution of the block
+    pub const BLOCK_UNDO: u32 = 19;
+    /// (contract id, node key) -> node of the sparse Merkle tree of the contract storage slots
+    pub const CONTRACTS_STATE_MERKLE_DATA: u32 = 20;
+    /// contract id -> root of the sparse Merkle tree of the contract storage slots
+    pub const CONTRACTS_STATE_MERKLE_METADATA: u32 = 21;
+    /// (contract id, node key) -> node of the sparse Merkle tree of the contract balances
+    pub const CONTRACTS_ASSETS_MERKLE_DATA: u32 = 22;
+    /// contract id -> root of the sparse Merkle tree of the contract balances
+    pub const CONTRACTS_ASSETS_MERKLE_METADATA: u32 = 23;
+    /// tx id -> height of the pruned block which included the transaction
+    pub const PRUNED_TRANSACTIONS: u32 = 24;
+    /// utxo id -> height of the pruned block which spent the coin, for the coins not created by
+    /// a transaction
+    pub const PRUNED_COINS: u32 = 25;
 
     // Number of columns
     #[cfg(feature = "rocksdb")]
-    pub const COLUMN_NUM: u32 = 18;
+    pub const COLUMN_NUM: u32 = 26;
 }
 
 #[derive(Clone, Debug)]
@@ -144,6 +167,27 @@ impl Database {
         })
     }
 
+    /// Opens the database of a primary node at `path` as a read-only replica, which follows the
+    /// writes of the primary once they are caught up with.
+    #[cfg(feature = "rocksdb")]
+    pub fn open_replica(path: &Path) -> Result<Self, Error> {
+        let secondary = TempDir::new().map_err(|e| Error::DatabaseError(Box::new(e)))?;
+        let db = RocksDb::open_secondary(path, secondary.path(), COLUMN_NUM)?;
+
+        Ok(Database {
+            data: Arc::new(db),
+            _drop: Arc::new(
+                {
+                    move || {
+                        // cleanup the logs of the secondary
+                        drop(secondary);
+                    }
+                }
+                .into(),
+            ),
+        })
+    }
+
     pub fn in_memory() -> Self {
         Self {
             data: Arc::new(MemoryStore::default()),
@@ -151,6 +195,14 @@ impl Database {
         }
     }
 
+    #[cfg(test)]
+    pub(crate) fn with_data_source(data: DataSource) -> Self {
+        Self {
+            data,
+            _drop: Default::default(),
+        }
+    }
+
     fn insert<K: Into<Vec<u8>>, V: Serialize + DeserializeOwned>(
         &self,
         key: K,
@@ -216,6 +268,11 @@ impl Database {
     pub fn transaction(&self) -> DatabaseTransaction {
         self.into()
     }
+
+    /// Reads the latest writes of the primary if the database is a read-only replica.
+    pub fn catch_up_with_primary(&self) -> Result<(), Error> {
+        self.data.catch_up_with_primary()
+    }
 }
 
 impl AsRef<Database> for Database {
@@ -375,9 +432,19 @@ impl RelayerDb for Database {
         }
     }
 
-    async fn get_sealed_block(&self, _height: BlockHeight) -> Option<Arc<SealedFuelBlock>> {
-        // TODO
-        Some(Arc::new(SealedFuelBlock::default()))
+    async fn get_sealed_block(&self, height: BlockHeight) -> Option<Arc<SealedFuelBlock>> {
+        let block_id = match self.get_block_id(height) {
+            Ok(res) => res?,
+            Err(err) => {
+                panic!("get_block_id database curruption, err:{:?}", err);
+            }
+        };
+        match self.get_sealed_block_by_id(&block_id) {
+            Ok(res) => res.map(Arc::new),
+            Err(err) => {
+                panic!("get_sealed_block_by_id database curruption, err:{:?}", err);
+            }
+        }
     }
 
     async fn set_finalized_da_height(&self, block: DaBlockHeight) {
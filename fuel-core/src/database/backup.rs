@@ -0,0 +1,167 @@
+//! Backups of the database taken while the node is running.
+//!
+//! A checkpoint is a consistent copy of the database in a new directory, made of hard links to
+//! the immutable files of RocksDB where possible, so it's cheap to take. A checkpoint is itself a
+//! database, which a stopped node is restored from or a new node is bootstrapped with.
+
+use crate::{
+    database::{Database, VERSION},
+    state::Error,
+};
+use std::path::Path;
+#[cfg(feature = "rocksdb")]
+use std::{fs, io, path::PathBuf};
+#[cfg(feature = "rocksdb")]
+use tracing::info;
+
+impl Database {
+    /// Writes a checkpoint of the database to the new directory `path`.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), Error> {
+        self.data.checkpoint(path)
+    }
+
+    /// Checks that the database can replace the one of the chain `chain_name`: it belongs to the
+    /// same chain, and its version is one this node can open, after migrating it if needed.
+    pub fn check_restorable(&self, chain_name: &str) -> Result<(), Error> {
+        match self.version()? {
+            Some(version) if version <= VERSION => {}
+            _ => return Err(Error::InvalidDatabaseVersion),
+        }
+        let found = self.get_chain_name()?.ok_or(Error::ChainUninitialized)?;
+        if found != chain_name {
+            return Err(Error::WrongChain {
+                expected: chain_name.to_string(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Replaces the database at `path` by a copy of the checkpoint at `checkpoint`, once checked
+    /// with [`Database::check_restorable`]. The node using the database must be stopped.
+    ///
+    /// The replaced database is kept next to the restored one with the `replaced` extension, its
+    /// path is returned.
+    #[cfg(feature = "rocksdb")]
+    pub fn restore_checkpoint(
+        checkpoint: &Path,
+        path: &Path,
+        chain_name: &str,
+    ) -> Result<Option<PathBuf>, Error> {
+        let replaced = path.with_extension("replaced");
+        if replaced.exists() {
+            return Err(io_error(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} must be moved away first", replaced.display()),
+            )));
+        }
+
+        // the checkpoint is copied before being opened, so it can be restored again later
+        let staging = path.with_extension("restoring");
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(io_error)?;
+        }
+        copy_dir(checkpoint, &staging).map_err(io_error)?;
+        let restorable = Database::open(&staging).and_then(|db| db.check_restorable(chain_name));
+        if let Err(e) = restorable {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+
+        let replaced = if path.exists() {
+            fs::rename(path, &replaced).map_err(io_error)?;
+            Some(replaced)
+        } else {
+            None
+        };
+        fs::rename(&staging, path).map_err(io_error)?;
+        info!(
+            "Restored the database at {} from {}",
+            path.display(),
+            checkpoint.display()
+        );
+        Ok(replaced)
+    }
+}
+
+/// Copies the files of the directory `from`, checkpoints have no subdirectories.
+#[cfg(feature = "rocksdb")]
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        fs::copy(entry.path(), to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "rocksdb")]
+fn io_error(e: io::Error) -> Error {
+    Error::DatabaseError(Box::new(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_databases_of_the_same_chain_are_restorable() {
+        let db = Database::in_memory();
+        db.init_chain_name("local_testnet".to_string()).unwrap();
+        // the version is missing
+        assert!(matches!(
+            db.check_restorable("local_testnet"),
+            Err(Error::InvalidDatabaseVersion)
+        ));
+
+        db.migrate(false).unwrap();
+        db.check_restorable("local_testnet").unwrap();
+        assert!(matches!(
+            db.check_restorable("beta"),
+            Err(Error::WrongChain { found, .. }) if found == "local_testnet"
+        ));
+    }
+
+    #[test]
+    fn in_memory_databases_have_no_checkpoints() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let db = Database::in_memory();
+
+        assert!(db
+            .create_checkpoint(&tmp_dir.path().join("checkpoint"))
+            .is_err());
+    }
+
+    #[cfg(feature = "rocksdb")]
+    #[test]
+    fn database_is_restored_from_checkpoint() {
+        use crate::database::columns::METADATA;
+
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let path = tmp_dir.path().join("db");
+        let checkpoint = tmp_dir.path().join("checkpoint");
+        {
+            let db = Database::open(&path).unwrap();
+            db.migrate(false).unwrap();
+            db.init_chain_name("local_testnet".to_string()).unwrap();
+            db.create_checkpoint(&checkpoint).unwrap();
+            db.insert(b"after", METADATA, 1u32).unwrap();
+        }
+
+        assert!(matches!(
+            Database::restore_checkpoint(&checkpoint, &path, "beta"),
+            Err(Error::WrongChain { .. })
+        ));
+        let replaced = Database::restore_checkpoint(&checkpoint, &path, "local_testnet").unwrap();
+
+        assert_eq!(replaced, Some(path.with_extension("replaced")));
+        let db = Database::open(&path).unwrap();
+        assert!(!db.exists(b"after", METADATA).unwrap());
+        assert_eq!(
+            db.get_chain_name().unwrap(),
+            Some("local_testnet".to_string())
+        );
+        // the checkpoint can be restored again
+        assert!(checkpoint.exists());
+    }
+}
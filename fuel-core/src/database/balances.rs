@@ -1,16 +1,12 @@
 use crate::state::Error;
 use crate::{
-    database::{columns::BALANCES, Database},
-    state::{IterDirection, MultiKey},
+    database::{columns::BALANCES, merkle::CONTRACTS_ASSETS_MERKLE, Database},
+    state::MultiKey,
 };
 use fuel_core_interfaces::common::{
     fuel_storage::MerkleRoot,
-    fuel_vm::{
-        crypto,
-        prelude::{AssetId, ContractId, MerkleStorage, Word},
-    },
+    fuel_vm::prelude::{AssetId, ContractId, MerkleStorage, Word},
 };
-use itertools::Itertools;
 use std::borrow::Cow;
 
 impl MerkleStorage<ContractId, AssetId, Word> for Database {
@@ -22,13 +18,24 @@ impl MerkleStorage<ContractId, AssetId, Word> for Database {
         key: &AssetId,
         value: &Word,
     ) -> Result<Option<Word>, Error> {
-        let key = MultiKey::new((parent, key));
-        Database::insert(self, key.as_ref().to_vec(), BALANCES, *value)
+        let db_key = MultiKey::new((parent, key));
+        let previous = Database::insert(self, db_key.as_ref().to_vec(), BALANCES, *value)?;
+        self.update_contract_merkle(
+            CONTRACTS_ASSETS_MERKLE,
+            parent,
+            key,
+            Some(&value.to_be_bytes()),
+        )?;
+        Ok(previous)
     }
 
     fn remove(&mut self, parent: &ContractId, key: &AssetId) -> Result<Option<Word>, Error> {
-        let key = MultiKey::new((parent, key));
-        Database::remove(self, key.as_ref(), BALANCES)
+        let db_key = MultiKey::new((parent, key));
+        let previous: Option<Word> = Database::remove(self, db_key.as_ref(), BALANCES)?;
+        if previous.is_some() {
+            self.update_contract_merkle(CONTRACTS_ASSETS_MERKLE, parent, key, None)?;
+        }
+        Ok(previous)
     }
 
     fn get(&self, parent: &ContractId, key: &AssetId) -> Result<Option<Cow<Word>>, Error> {
@@ -42,24 +49,7 @@ impl MerkleStorage<ContractId, AssetId, Word> for Database {
     }
 
     fn root(&mut self, parent: &ContractId) -> Result<MerkleRoot, Error> {
-        let items: Vec<_> = Database::iter_all::<Vec<u8>, Word>(
-            self,
-            BALANCES,
-            Some(parent.as_ref().to_vec()),
-            None,
-            Some(IterDirection::Forward),
-        )
-        .try_collect()?;
-
-        let root = items
-            .iter()
-            .filter_map(|(key, value)| {
-                (&key[..parent.len()] == parent.as_ref()).then(|| (key, value))
-            })
-            .sorted_by_key(|t| t.0)
-            .map(|(_, value)| value.to_be_bytes());
-
-        Ok(crypto::ephemeral_merkle_root(root).into())
+        self.contract_merkle_root(CONTRACTS_ASSETS_MERKLE, parent)
     }
 }
 
@@ -1,9 +1,16 @@
 use crate::{
-    database::{columns::BLOCKS, columns::BLOCK_IDS, Database, KvStoreError},
-    model::{BlockHeight, FuelBlockDb},
+    database::{
+        columns::{BLOCKS, BLOCK_CONSENSUS, BLOCK_IDS},
+        Database, KvStoreError,
+    },
+    model::{BlockHeight, FuelBlock, FuelBlockConsensus, FuelBlockDb, SealedFuelBlock},
     state::{Error, IterDirection},
 };
-use fuel_core_interfaces::common::{fuel_storage::Storage, fuel_tx::Bytes32};
+use fuel_core_interfaces::common::{
+    fuel_storage::Storage,
+    fuel_tx::{Bytes32, Transaction},
+};
+use itertools::Itertools;
 use std::borrow::Cow;
 use std::convert::{TryFrom, TryInto};
 
@@ -37,7 +44,64 @@ impl Storage<Bytes32, FuelBlockDb> for Database {
     }
 }
 
+impl Storage<Bytes32, FuelBlockConsensus> for Database {
+    type Error = KvStoreError;
+
+    fn insert(
+        &mut self,
+        key: &Bytes32,
+        value: &FuelBlockConsensus,
+    ) -> Result<Option<FuelBlockConsensus>, KvStoreError> {
+        Database::insert(self, key.as_ref(), BLOCK_CONSENSUS, value.clone()).map_err(Into::into)
+    }
+
+    fn remove(&mut self, key: &Bytes32) -> Result<Option<FuelBlockConsensus>, KvStoreError> {
+        Database::remove(self, key.as_ref(), BLOCK_CONSENSUS).map_err(Into::into)
+    }
+
+    fn get(&self, key: &Bytes32) -> Result<Option<Cow<FuelBlockConsensus>>, KvStoreError> {
+        Database::get(self, key.as_ref(), BLOCK_CONSENSUS).map_err(Into::into)
+    }
+
+    fn contains_key(&self, key: &Bytes32) -> Result<bool, KvStoreError> {
+        Database::exists(self, key.as_ref(), BLOCK_CONSENSUS).map_err(Into::into)
+    }
+}
+
 impl Database {
+    /// Retrieve the block with all of its transactions, along with the consensus data it was
+    /// sealed with. Returns `None` if the block is unknown or wasn't sealed.
+    pub fn get_sealed_block_by_id(
+        &self,
+        block_id: &Bytes32,
+    ) -> Result<Option<SealedFuelBlock>, KvStoreError> {
+        let block = match Storage::<Bytes32, FuelBlockDb>::get(self, block_id)? {
+            Some(block) => block.into_owned(),
+            None => return Ok(None),
+        };
+        let consensus = match Storage::<Bytes32, FuelBlockConsensus>::get(self, block_id)? {
+            Some(consensus) => consensus.into_owned(),
+            None => return Ok(None),
+        };
+        let transactions = block
+            .transactions
+            .iter()
+            .map(|tx_id| {
+                Storage::<Bytes32, Transaction>::get(self, tx_id)
+                    .and_then(|tx| tx.ok_or(KvStoreError::NotFound))
+                    .map(Cow::into_owned)
+            })
+            .try_collect()?;
+
+        Ok(Some(SealedFuelBlock {
+            block: FuelBlock {
+                header: block.headers,
+                transactions,
+            },
+            consensus,
+        }))
+    }
+
     pub fn get_block_height(&self) -> Result<Option<BlockHeight>, Error> {
         let block_entry: Option<(Vec<u8>, Bytes32)> = self
             .iter_all(BLOCK_IDS, None, None, Some(IterDirection::Reverse))
@@ -25,7 +25,7 @@ fn owner_coin_id_key(owner: &Address, coin_id: &UtxoId) -> Vec<u8> {
 // 32 Bytes for Tx_id + 1 byte for output_index
 const SIZE_OF_UTXO_ID: usize = 264;
 
-fn utxo_id_to_bytes(utxo_id: &UtxoId) -> Vec<u8> {
+pub(crate) fn utxo_id_to_bytes(utxo_id: &UtxoId) -> Vec<u8> {
     let mut out = Vec::with_capacity(SIZE_OF_UTXO_ID);
     out.extend(utxo_id.tx_id().as_ref().iter());
     out.push(utxo_id.output_index());
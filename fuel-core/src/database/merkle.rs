@@ -0,0 +1,335 @@
+//! Sparse Merkle trees of the storage slots and balances of every contract.
+//!
+//! The trees are persisted and updated incrementally with every write, so a root is read
+//! directly instead of being recomputed from all the entries of the contract. The nodes of each
+//! tree are stored under the id of their contract, next to the latest root of the tree.
+
+use crate::{
+    database::{
+        columns::{
+            BALANCES, CONTRACTS_ASSETS_MERKLE_DATA, CONTRACTS_ASSETS_MERKLE_METADATA,
+            CONTRACTS_STATE, CONTRACTS_STATE_MERKLE_DATA, CONTRACTS_STATE_MERKLE_METADATA,
+        },
+        migration::Migration,
+        Database,
+    },
+    state::{ColumnId, Error, IterDirection, MultiKey},
+};
+use fuel_core_interfaces::common::{
+    fuel_merkle::{
+        common::Bytes32,
+        sparse::{MerkleTree, MerkleTreeError},
+    },
+    fuel_types::{self, ContractId, Word},
+};
+use fuel_merkle_storage::Storage;
+use itertools::Itertools;
+use std::{borrow::Cow, convert::TryFrom, sync::Arc};
+
+/// A serialized node of a sparse Merkle tree
+type Node = [u8; 69];
+
+/// Root of a tree without any leaf
+const EMPTY_ROOT: Bytes32 = [0; 32];
+
+/// Number of leaves added to the trees in a single batch by the migration building them
+const MIGRATION_BATCH_SIZE: usize = 10_000;
+
+/// The columns storing one kind of contract trees
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MerkleColumns {
+    /// (contract id, node key) -> node
+    nodes: ColumnId,
+    /// contract id -> root
+    roots: ColumnId,
+}
+
+/// Trees of the storage slots of the contracts
+pub(crate) const CONTRACTS_STATE_MERKLE: MerkleColumns = MerkleColumns {
+    nodes: CONTRACTS_STATE_MERKLE_DATA,
+    roots: CONTRACTS_STATE_MERKLE_METADATA,
+};
+
+/// Trees of the asset balances of the contracts
+pub(crate) const CONTRACTS_ASSETS_MERKLE: MerkleColumns = MerkleColumns {
+    nodes: CONTRACTS_ASSETS_MERKLE_DATA,
+    roots: CONTRACTS_ASSETS_MERKLE_METADATA,
+};
+
+/// Errors of the trees, which require them to be cloneable.
+#[derive(Clone, Debug, thiserror::Error)]
+enum MerkleError {
+    #[error(transparent)]
+    Tree(#[from] MerkleTreeError),
+    #[error(transparent)]
+    Storage(Arc<Error>),
+}
+
+impl From<Error> for MerkleError {
+    fn from(e: Error) -> Self {
+        MerkleError::Storage(Arc::new(e))
+    }
+}
+
+impl From<MerkleError> for Error {
+    fn from(e: MerkleError) -> Self {
+        Error::DatabaseError(Box::new(e))
+    }
+}
+
+/// The nodes of the tree of a single contract. The trees only accept `'static` storages.
+struct ContractNodes {
+    database: Database,
+    contract: ContractId,
+    column: ColumnId,
+}
+
+impl ContractNodes {
+    fn key(&self, key: &Bytes32) -> Vec<u8> {
+        MultiKey::new((&self.contract, key)).into()
+    }
+
+    fn decode(node: Option<Vec<u8>>) -> Result<Option<Node>, MerkleError> {
+        node.map(|node| Node::try_from(node.as_slice()).map_err(|_| Error::Codec.into()))
+            .transpose()
+    }
+}
+
+impl Storage<Bytes32, Node> for ContractNodes {
+    type Error = MerkleError;
+
+    fn insert(&mut self, key: &Bytes32, value: &Node) -> Result<Option<Node>, MerkleError> {
+        let previous = self
+            .database
+            .data
+            .put(self.key(key), self.column, value.to_vec())?;
+        Self::decode(previous)
+    }
+
+    fn remove(&mut self, key: &Bytes32) -> Result<Option<Node>, MerkleError> {
+        let previous = self.database.data.delete(&self.key(key), self.column)?;
+        Self::decode(previous)
+    }
+
+    fn get(&self, key: &Bytes32) -> Result<Option<Cow<'_, Node>>, MerkleError> {
+        let node = self.database.data.get(&self.key(key), self.column)?;
+        Ok(Self::decode(node)?.map(Cow::Owned))
+    }
+
+    fn contains_key(&self, key: &Bytes32) -> Result<bool, MerkleError> {
+        Ok(self.database.data.exists(&self.key(key), self.column)?)
+    }
+}
+
+impl Database {
+    /// Sets the leaf `key` of the tree of `contract` to `value`, or removes it if `value` is
+    /// `None`.
+    pub(crate) fn update_contract_merkle(
+        &self,
+        columns: MerkleColumns,
+        contract: &ContractId,
+        key: &Bytes32,
+        value: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let mut nodes = ContractNodes {
+            database: self.clone(),
+            contract: *contract,
+            column: columns.nodes,
+        };
+        let root = self.contract_merkle_root(columns, contract)?;
+        let mut tree = if root == EMPTY_ROOT {
+            MerkleTree::new(&mut nodes)
+        } else {
+            MerkleTree::load(&mut nodes, &root)?
+        };
+        match value {
+            Some(value) => tree.update(key, value)?,
+            None => tree.delete(key)?,
+        }
+
+        let root = tree.root();
+        // the empty root isn't a stored node, so the tree can't be loaded from it
+        if root == EMPTY_ROOT {
+            let _: Option<Bytes32> = self.remove(contract.as_ref(), columns.roots)?;
+        } else {
+            let _: Option<Bytes32> = self.insert(contract.as_ref(), columns.roots, root)?;
+        }
+        Ok(())
+    }
+
+    /// The root of the tree of `contract`
+    pub(crate) fn contract_merkle_root(
+        &self,
+        columns: MerkleColumns,
+        contract: &ContractId,
+    ) -> Result<Bytes32, Error> {
+        Ok(self
+            .get(contract.as_ref(), columns.roots)?
+            .unwrap_or(EMPTY_ROOT))
+    }
+}
+
+/// Builds the trees of the contracts from their storage slots and balances, which used to have
+/// their roots recomputed on every read.
+pub(crate) struct ContractMerkleTrees;
+
+impl Migration for ContractMerkleTrees {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn description(&self) -> &'static str {
+        "Persist the sparse Merkle trees of the contract storage slots and balances"
+    }
+
+    fn migrate(&self, db: &Database) -> Result<(), Error> {
+        let slots = db
+            .iter_all::<Vec<u8>, fuel_types::Bytes32>(
+                CONTRACTS_STATE,
+                None,
+                None,
+                Some(IterDirection::Forward),
+            )
+            .map_ok(|(key, value)| (CONTRACTS_STATE_MERKLE, key, value.to_vec()));
+        let balances = db
+            .iter_all::<Vec<u8>, Word>(BALANCES, None, None, Some(IterDirection::Forward))
+            .map_ok(|(key, amount)| (CONTRACTS_ASSETS_MERKLE, key, amount.to_be_bytes().to_vec()));
+
+        // the leaves already added by an interrupted run are added again, to the same trees
+        let mut batch = db.transaction();
+        for (leaves, leaf) in slots.chain(balances).enumerate() {
+            let (columns, key, value) = leaf?;
+            let (contract, key) = split_key(&key)?;
+            batch.update_contract_merkle(columns, &contract, &key, Some(&value))?;
+            if (leaves + 1) % MIGRATION_BATCH_SIZE == 0 {
+                std::mem::replace(&mut batch, db.transaction()).commit()?;
+            }
+        }
+        batch.commit()
+    }
+
+    fn is_resumable(&self) -> bool {
+        true
+    }
+}
+
+/// Splits a `(contract id, key)` database key
+fn split_key(key: &[u8]) -> Result<(ContractId, Bytes32), Error> {
+    if key.len() != 64 {
+        return Err(Error::Codec);
+    }
+    let contract = ContractId::try_from(&key[..32]).map_err(|_| Error::Codec)?;
+    let key = Bytes32::try_from(&key[32..]).map_err(|_| Error::Codec)?;
+    Ok((contract, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuel_core_interfaces::common::{
+        fuel_merkle::common::{StorageError, StorageMap},
+        fuel_vm::prelude::MerkleStorage,
+    };
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    type Slot = (fuel_types::Bytes32, fuel_types::Bytes32);
+
+    /// The root of a tree built from scratch in memory
+    fn expected_root(slots: &[Slot]) -> Bytes32 {
+        let mut storage = StorageMap::<Bytes32, Node>::new();
+        let mut tree = MerkleTree::<StorageError>::new(&mut storage);
+        for (key, value) in slots {
+            tree.update(key, value.as_ref()).unwrap();
+        }
+        tree.root()
+    }
+
+    fn insert(db: &mut Database, contract: &ContractId, (key, value): &Slot) {
+        MerkleStorage::<ContractId, fuel_types::Bytes32, fuel_types::Bytes32>::insert(
+            db, contract, key, value,
+        )
+        .unwrap();
+    }
+
+    fn remove(db: &mut Database, contract: &ContractId, (key, _): &Slot) {
+        MerkleStorage::<ContractId, fuel_types::Bytes32, fuel_types::Bytes32>::remove(
+            db, contract, key,
+        )
+        .unwrap();
+    }
+
+    fn state_root(db: &mut Database, contract: &ContractId) -> Bytes32 {
+        MerkleStorage::<ContractId, fuel_types::Bytes32, fuel_types::Bytes32>::root(db, contract)
+            .unwrap()
+    }
+
+    #[test]
+    fn roots_are_updated_incrementally() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let contract: ContractId = rng.gen();
+        let other: ContractId = rng.gen();
+        assert_eq!(state_root(&mut db, &contract), EMPTY_ROOT);
+
+        let mut slots: Vec<Slot> = (0..100).map(|_| (rng.gen(), rng.gen())).collect();
+        for slot in &slots {
+            insert(&mut db, &contract, slot);
+            // the same slots of another contract are in another tree
+            insert(&mut db, &other, &(slot.0, rng.gen()));
+        }
+        assert_eq!(state_root(&mut db, &contract), expected_root(&slots));
+
+        // overwritten and removed slots
+        slots[0].1 = rng.gen();
+        insert(&mut db, &contract, &slots[0]);
+        slots.shuffle(&mut rng);
+        for slot in slots.drain(..50) {
+            remove(&mut db, &contract, &slot);
+        }
+        assert_eq!(state_root(&mut db, &contract), expected_root(&slots));
+
+        for slot in slots.drain(..) {
+            remove(&mut db, &contract, &slot);
+        }
+        assert_eq!(state_root(&mut db, &contract), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn migration_builds_the_existing_trees() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let contract: ContractId = rng.gen();
+        for _ in 0..10 {
+            insert(&mut db, &contract, &(rng.gen(), rng.gen()));
+        }
+        let asset_id: fuel_types::AssetId = rng.gen();
+        MerkleStorage::<ContractId, fuel_types::AssetId, Word>::insert(
+            &mut db, &contract, &asset_id, &100,
+        )
+        .unwrap();
+        let state_root = state_root(&mut db, &contract);
+        let balances_root = db
+            .contract_merkle_root(CONTRACTS_ASSETS_MERKLE, &contract)
+            .unwrap();
+
+        // like a database written before the trees were persisted
+        let old = Database::default();
+        for column in [CONTRACTS_STATE, BALANCES] {
+            for (key, value) in db.data.iter_all(column, None, None, IterDirection::Forward) {
+                old.data.put(key, column, value).unwrap();
+            }
+        }
+        ContractMerkleTrees.migrate(&old).unwrap();
+
+        assert_eq!(
+            old.contract_merkle_root(CONTRACTS_STATE_MERKLE, &contract)
+                .unwrap(),
+            state_root
+        );
+        assert_eq!(
+            old.contract_merkle_root(CONTRACTS_ASSETS_MERKLE, &contract)
+                .unwrap(),
+            balances_root
+        );
+    }
+}
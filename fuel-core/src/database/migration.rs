@@ -0,0 +1,364 @@
+//! Upgrades databases written by older versions of the node in place.
+//!
+//! Every change of the on-disk layout bumps [`VERSION`] and registers a [`Migration`] converting
+//! the data of the previous version. Pending migrations run in order when the node starts, each
+//! one in its own batch together with the new version, so an interrupted upgrade resumes from the
+//! last completed step. Migrations too large for a single batch commit their own batches
+//! instead, and are run again from the start if they are interrupted.
+
+use crate::{
+    database::{columns::METADATA, merkle::ContractMerkleTrees, Database, VERSION},
+    state::{Error, IterDirection},
+};
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, time::Instant};
+use tracing::info;
+
+/// Key of the layout version of the database in the `METADATA` column.
+const VERSION_KEY: &[u8] = b"version";
+/// Prefix of the records of applied migrations in the `METADATA` column, followed by the version.
+const APPLIED_MIGRATION_PREFIX: &[u8] = b"migration/";
+
+/// A step upgrading the database layout from `version() - 1` to `version()`.
+pub trait Migration {
+    fn version(&self) -> u32;
+
+    /// Short summary of the change, logged and recorded once the migration is applied.
+    fn description(&self) -> &'static str;
+
+    /// Converts the data in `db`. Nothing is written to disk if it fails, unless the migration
+    /// is resumable.
+    fn migrate(&self, db: &Database) -> Result<(), Error>;
+
+    /// Whether the migration is given the database itself rather than a batch, to commit its
+    /// changes in several batches of bounded size. It must then be idempotent, as it is run again
+    /// if it is interrupted: the version is only bumped once it completed.
+    fn is_resumable(&self) -> bool {
+        false
+    }
+}
+
+/// All the migrations, ordered by version. The last one upgrades to [`VERSION`].
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(ContractMerkleTrees)]
+}
+
+/// Record of a migration applied to the database.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub description: String,
+    /// Version of the node which applied the migration.
+    pub node_version: String,
+}
+
+impl Database {
+    /// The layout version of the database, `None` if nothing was ever written to it.
+    pub fn version(&self) -> Result<Option<u32>, Error> {
+        self.data
+            .get(VERSION_KEY, METADATA)?
+            .map(|version| {
+                <[u8; 4]>::try_from(version.as_slice())
+                    .map(u32::from_be_bytes)
+                    .map_err(|_| Error::InvalidDatabaseVersion)
+            })
+            .transpose()
+    }
+
+    fn set_version(&self, version: u32) -> Result<(), Error> {
+        self.data
+            .put(
+                VERSION_KEY.to_vec(),
+                METADATA,
+                version.to_be_bytes().to_vec(),
+            )
+            .map(|_| ())
+    }
+
+    /// The migrations applied to the database, ordered by version.
+    pub fn applied_migrations(&self) -> Result<Vec<AppliedMigration>, Error> {
+        self.iter_all::<Vec<u8>, AppliedMigration>(
+            METADATA,
+            Some(APPLIED_MIGRATION_PREFIX.to_vec()),
+            None,
+            Some(IterDirection::Forward),
+        )
+        .map(|entry| entry.map(|(_, migration)| migration))
+        .collect()
+    }
+
+    /// Upgrades the database to the current [`VERSION`] and returns the applied migrations.
+    ///
+    /// In dry-run mode the migrations are run, but their changes are discarded instead of
+    /// being committed.
+    pub fn migrate(&self, dry_run: bool) -> Result<Vec<AppliedMigration>, Error> {
+        self.migrate_to(&migrations(), VERSION, dry_run)
+    }
+
+    fn migrate_to(
+        &self,
+        migrations: &[Box<dyn Migration>],
+        target: u32,
+        dry_run: bool,
+    ) -> Result<Vec<AppliedMigration>, Error> {
+        let current = match self.version()? {
+            Some(version) if version > target => return Err(Error::InvalidDatabaseVersion),
+            Some(version) => version,
+            // a new database already has the current layout
+            None => {
+                if !dry_run {
+                    self.set_version(target)?;
+                }
+                return Ok(vec![]);
+            }
+        };
+
+        let pending: Vec<_> = migrations
+            .iter()
+            .filter(|migration| migration.version() > current && migration.version() <= target)
+            .collect();
+        let is_complete = pending
+            .iter()
+            .map(|migration| migration.version())
+            .eq(current + 1..=target);
+        if !is_complete {
+            return Err(Error::InvalidDatabaseVersion);
+        }
+        if pending.is_empty() {
+            return Ok(vec![]);
+        }
+
+        info!(
+            "Migrating database from version {} to {}{}",
+            current,
+            target,
+            if dry_run { " (dry-run)" } else { "" }
+        );
+        // the changes of a dry-run stay in memory, later migrations still see the earlier ones
+        let dry_run_changes;
+        let db = if dry_run {
+            dry_run_changes = self.transaction();
+            &*dry_run_changes
+        } else {
+            self
+        };
+
+        let mut applied = vec![];
+        for (step, migration) in pending.into_iter().enumerate() {
+            let version = migration.version();
+            info!(
+                "Applying migration {}/{} to version {}: {}",
+                step + 1,
+                target - current,
+                version,
+                migration.description()
+            );
+            let started = Instant::now();
+
+            let batch = db.transaction();
+            if migration.is_resumable() {
+                migration.migrate(db)?;
+            } else {
+                migration.migrate(&batch)?;
+            }
+            let record = AppliedMigration {
+                version,
+                description: migration.description().to_string(),
+                node_version: env!("CARGO_PKG_VERSION").to_string(),
+            };
+            batch.insert(
+                [APPLIED_MIGRATION_PREFIX, &version.to_be_bytes()].concat(),
+                METADATA,
+                record.clone(),
+            )?;
+            batch.set_version(version)?;
+            batch.commit()?;
+
+            info!(
+                "Migration to version {} completed in {:?}",
+                version,
+                started.elapsed()
+            );
+            applied.push(record);
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::columns::COIN;
+
+    struct SetKey(u32);
+
+    impl Migration for SetKey {
+        fn version(&self) -> u32 {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "set a key"
+        }
+
+        fn migrate(&self, db: &Database) -> Result<(), Error> {
+            // fails if the previous migration didn't run
+            if self.0 > 1 && !db.exists(&(self.0 - 1).to_be_bytes(), COIN)? {
+                return Err(Error::Codec);
+            }
+            db.insert(self.0.to_be_bytes(), COIN, self.0).map(|_| ())
+        }
+    }
+
+    struct Fail(u32);
+
+    impl Migration for Fail {
+        fn version(&self) -> u32 {
+            self.0
+        }
+
+        fn description(&self) -> &'static str {
+            "fail"
+        }
+
+        fn migrate(&self, db: &Database) -> Result<(), Error> {
+            db.insert(b"partial".to_vec(), COIN, true)?;
+            Err(Error::Codec)
+        }
+    }
+
+    /// Commits a batch for every key it sets, then fails the first time it runs.
+    struct Resumable {
+        failed: std::cell::Cell<bool>,
+    }
+
+    impl Migration for Resumable {
+        fn version(&self) -> u32 {
+            1
+        }
+
+        fn description(&self) -> &'static str {
+            "set keys in batches"
+        }
+
+        fn migrate(&self, db: &Database) -> Result<(), Error> {
+            for key in [b"first", b"other"] {
+                let batch = db.transaction();
+                batch.insert(key.to_vec(), COIN, true)?;
+                batch.commit()?;
+                if !self.failed.replace(true) {
+                    return Err(Error::Codec);
+                }
+            }
+            Ok(())
+        }
+
+        fn is_resumable(&self) -> bool {
+            true
+        }
+    }
+
+    fn registry() -> Vec<Box<dyn Migration>> {
+        vec![
+            Box::new(SetKey(1)),
+            Box::new(SetKey(2)),
+            Box::new(SetKey(3)),
+        ]
+    }
+
+    #[test]
+    fn registered_migrations_lead_to_current_version() {
+        let versions: Vec<_> = migrations().iter().map(|m| m.version()).collect();
+        assert_eq!(versions, (1..=VERSION).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn new_database_is_created_with_target_version() {
+        let db = Database::default();
+
+        assert!(db.migrate_to(&registry(), 3, false).unwrap().is_empty());
+        assert_eq!(db.version().unwrap(), Some(3));
+        assert!(!db.exists(&1u32.to_be_bytes(), COIN).unwrap());
+    }
+
+    #[test]
+    fn pending_migrations_are_applied_in_order_and_recorded() {
+        let db = Database::default();
+        // a database written at version 1
+        db.insert(1u32.to_be_bytes(), COIN, 1u32).unwrap();
+        db.set_version(1).unwrap();
+
+        let applied = db.migrate_to(&registry(), 3, false).unwrap();
+
+        assert_eq!(
+            applied.iter().map(|m| m.version).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(db.applied_migrations().unwrap(), applied);
+        assert_eq!(db.version().unwrap(), Some(3));
+        assert!(db.exists(&3u32.to_be_bytes(), COIN).unwrap());
+        // nothing left to do
+        assert!(db.migrate_to(&registry(), 3, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn dry_run_discards_changes() {
+        let db = Database::default();
+        db.set_version(0).unwrap();
+
+        let applied = db.migrate_to(&registry(), 3, true).unwrap();
+
+        assert_eq!(applied.len(), 3);
+        assert_eq!(db.version().unwrap(), Some(0));
+        assert!(db.applied_migrations().unwrap().is_empty());
+        assert!(!db.exists(&1u32.to_be_bytes(), COIN).unwrap());
+    }
+
+    #[test]
+    fn failed_migration_keeps_previous_steps() {
+        let db = Database::default();
+        db.set_version(0).unwrap();
+        let migrations: Vec<Box<dyn Migration>> = vec![Box::new(SetKey(1)), Box::new(Fail(2))];
+
+        assert!(db.migrate_to(&migrations, 2, false).is_err());
+        assert_eq!(db.version().unwrap(), Some(1));
+        assert!(db.exists(&1u32.to_be_bytes(), COIN).unwrap());
+        assert!(!db.exists(b"partial", COIN).unwrap());
+    }
+
+    #[test]
+    fn resumable_migration_keeps_its_batches_but_not_the_version() {
+        let db = Database::default();
+        db.set_version(0).unwrap();
+        let migrations: Vec<Box<dyn Migration>> = vec![Box::new(Resumable {
+            failed: Default::default(),
+        })];
+
+        assert!(db.migrate_to(&migrations, 1, false).is_err());
+        assert_eq!(db.version().unwrap(), Some(0));
+        assert!(db.exists(b"first", COIN).unwrap());
+        assert!(!db.exists(b"other", COIN).unwrap());
+
+        // run again from the start
+        assert_eq!(db.migrate_to(&migrations, 1, false).unwrap().len(), 1);
+        assert_eq!(db.version().unwrap(), Some(1));
+        assert!(db.exists(b"other", COIN).unwrap());
+    }
+
+    #[test]
+    fn newer_or_unknown_versions_are_rejected() {
+        let db = Database::default();
+        db.set_version(4).unwrap();
+        assert!(matches!(
+            db.migrate_to(&registry(), 3, false),
+            Err(Error::InvalidDatabaseVersion)
+        ));
+
+        // no migration from version 0 to 1
+        db.set_version(0).unwrap();
+        assert!(matches!(
+            db.migrate_to(&registry()[1..], 3, false),
+            Err(Error::InvalidDatabaseVersion)
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Deletes the history nodes which only follow the chain don't need: spent coins, and the
+//! transactions of old blocks with their receipts, statuses and owner indexes. Block headers are
+//! kept, so the chain itself stays verifiable, and so are the ids of the pruned transactions and
+//! of the pruned coins without one, so lookups by id can tell pruned data from unknown ones.
+
+use crate::{
+    database::{
+        coin::utxo_id_to_bytes,
+        columns::{
+            BLOCK_UNDO, METADATA, PRUNED_COINS, PRUNED_TRANSACTIONS, TRANSACTIONS,
+            TRANSACTION_STATUS,
+        },
+        transaction::{transaction_owners, TransactionIndex},
+        Database, KvStoreError,
+    },
+    model::{BlockHeight, Coin, FuelBlockDb},
+    state::{Error, WriteOperation},
+    tx_pool::TransactionStatus,
+};
+use fuel_core_interfaces::common::{
+    fuel_storage::Storage,
+    fuel_tx::{Bytes32, Input, Receipt, Transaction, UtxoId},
+};
+
+/// Key of the height of the most recent block whose history was pruned, in the `METADATA` column
+const PRUNED_HEIGHT_KEY: &[u8] = b"pruned_height";
+
+impl Database {
+    /// The height of the most recent block whose history was pruned
+    pub fn pruned_height(&self) -> Result<Option<BlockHeight>, Error> {
+        self.get(PRUNED_HEIGHT_KEY, METADATA)
+    }
+
+    /// The error for the history of the block at `height` which can't be found: it was pruned
+    /// if the block isn't more recent than the pruned ones.
+    pub fn missing_history(&self, height: BlockHeight) -> KvStoreError {
+        match self.pruned_height() {
+            Ok(Some(pruned)) if height <= pruned => KvStoreError::Pruned,
+            Ok(_) => KvStoreError::NotFound,
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Fails with [`KvStoreError::Pruned`] if the transaction was included in a pruned block,
+    /// for lookups by id which find nothing for unknown transactions.
+    pub fn ensure_not_pruned(&self, tx_id: &Bytes32) -> Result<(), KvStoreError> {
+        if self.exists(tx_id.as_ref(), PRUNED_TRANSACTIONS)? {
+            return Err(KvStoreError::Pruned);
+        }
+        Ok(())
+    }
+
+    /// Fails with [`KvStoreError::Pruned`] if the coin was spent in a pruned block, for lookups
+    /// by id which find nothing for unknown coins.
+    pub fn ensure_coin_not_pruned(&self, utxo_id: &UtxoId) -> Result<(), KvStoreError> {
+        // the spent coins are pruned with the transactions spending them, which come after the
+        // transactions creating them
+        self.ensure_not_pruned(utxo_id.tx_id())?;
+        if self.exists(&utxo_id_to_bytes(utxo_id), PRUNED_COINS)? {
+            return Err(KvStoreError::Pruned);
+        }
+        Ok(())
+    }
+
+    /// Prunes the history of the blocks older than the `keep_blocks` most recent ones and
+    /// returns the number of blocks pruned.
+    ///
+    /// Every block is pruned in its own batch, so an interrupted run resumes from the last
+    /// pruned block. Pruned blocks can't be rolled back anymore.
+    pub fn prune(&self, keep_blocks: u32) -> Result<u32, Error> {
+        let mut pruned = 0;
+        while self.prune_next(keep_blocks)?.is_some() {
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+
+    /// Prunes the history of the oldest block which isn't pruned yet, if it is older than the
+    /// `keep_blocks` most recent ones, and returns its height.
+    ///
+    /// The current height is read again for every block, no block must be committed or rolled
+    /// back while it is pruned.
+    pub fn prune_next(&self, keep_blocks: u32) -> Result<Option<BlockHeight>, Error> {
+        let current: u32 = self.get_block_height()?.unwrap_or_default().into();
+        let next = match self.pruned_height()? {
+            Some(pruned) => u32::from(pruned) + 1,
+            None => self.get_starting_chain_height()?.unwrap_or_default().into(),
+        };
+        match current.checked_sub(keep_blocks) {
+            Some(last) if last >= next => {
+                self.prune_block(next.into())?;
+                Ok(Some(next.into()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn prune_block(&self, height: BlockHeight) -> Result<(), Error> {
+        let mut batch = self.transaction();
+        let db: &mut Database = &mut batch;
+
+        let block = match db.get_block_id(height)? {
+            Some(block_id) => {
+                Storage::<Bytes32, FuelBlockDb>::get(db, &block_id)?.map(|block| block.into_owned())
+            }
+            None => None,
+        };
+        for (tx_idx, tx_id) in block
+            .iter()
+            .flat_map(|block| block.transactions.iter().enumerate())
+        {
+            if let Some(tx) = Storage::<Bytes32, Transaction>::remove(db, tx_id)? {
+                // the inputs of the transaction are spent
+                for input in tx.inputs() {
+                    if let Input::CoinSigned { utxo_id, .. }
+                    | Input::CoinPredicate { utxo_id, .. } = input
+                    {
+                        let _ = Storage::<UtxoId, Coin>::remove(db, utxo_id)?;
+                        // coins not created by a transaction, such as the genesis ones, have
+                        // no pruned transaction to tell that they were pruned
+                        let creator = utxo_id.tx_id().as_ref();
+                        if !db.exists(creator, TRANSACTIONS)?
+                            && !db.exists(creator, PRUNED_TRANSACTIONS)?
+                        {
+                            Database::insert(db, utxo_id_to_bytes(utxo_id), PRUNED_COINS, height)?;
+                        }
+                    }
+                }
+                for owner in transaction_owners(&tx) {
+                    db.remove_tx_id_owner(&owner, height, tx_idx as TransactionIndex)?;
+                }
+            }
+            let _ = Storage::<Bytes32, Vec<Receipt>>::remove(db, tx_id)?;
+            let _: Option<TransactionStatus> =
+                Database::remove(db, tx_id.as_ref(), TRANSACTION_STATUS)?;
+            Database::insert(db, tx_id.as_ref(), PRUNED_TRANSACTIONS, height)?;
+        }
+        // the undo data would restore the pruned history
+        let _: Option<Vec<WriteOperation>> = Database::remove(db, &height.to_bytes(), BLOCK_UNDO)?;
+        Database::insert(db, PRUNED_HEIGHT_KEY, METADATA, height)?;
+        batch.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, executor::Executor, model::CoinStatus};
+    use fuel_core_interfaces::common::{
+        fuel_asm::Opcode,
+        fuel_crypto::SecretKey,
+        fuel_tx::{Output, TransactionBuilder},
+        fuel_vm::consts::REG_ONE,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::sync::Arc;
+
+    /// Executes a block with a transaction spending a new coin
+    async fn execute(rng: &mut StdRng, db: &mut Database) -> Transaction {
+        let tx =
+            TransactionBuilder::script(vec![Opcode::RET(REG_ONE)].into_iter().collect(), vec![])
+                .add_unsigned_coin_input(
+                    rng.gen(),
+                    &SecretKey::random(rng),
+                    100,
+                    Default::default(),
+                    0,
+                )
+                .add_output(Output::Coin {
+                    to: rng.gen(),
+                    amount: 100,
+                    asset_id: Default::default(),
+                })
+                .finalize();
+        if let Input::CoinSigned { utxo_id, owner, .. } = tx.inputs()[0] {
+            let coin = Coin {
+                owner,
+                amount: 100,
+                asset_id: Default::default(),
+                maturity: Default::default(),
+                status: CoinStatus::Unspent,
+                block_created: Default::default(),
+            };
+            Storage::<UtxoId, Coin>::insert(db, &utxo_id, &coin).unwrap();
+        }
+        let executor = Executor {
+            database: db.clone(),
+            config: Config::local_node(),
+        };
+        let block = executor.submit_txs(vec![Arc::new(tx)]).await.unwrap();
+        block.transactions[0].clone()
+    }
+
+    fn is_pruned(db: &Database, tx: &Transaction) -> bool {
+        let tx_id = tx.id();
+        let owner = transaction_owners(tx)[0];
+        let has_history = Storage::<Bytes32, Transaction>::contains_key(db, &tx_id).unwrap()
+            || Storage::<Bytes32, Vec<Receipt>>::contains_key(db, &tx_id).unwrap()
+            || db.get_tx_status(&tx_id).unwrap().is_some()
+            || Storage::<UtxoId, Coin>::contains_key(db, tx.inputs()[0].utxo_id()).unwrap()
+            || db.owned_transactions(&owner, None, None).next().is_some();
+        !has_history
+    }
+
+    #[tokio::test]
+    async fn history_of_old_blocks_is_pruned() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let mut txs = vec![];
+        for _ in 0..3 {
+            txs.push(execute(&mut rng, &mut db).await);
+        }
+        assert!(matches!(
+            db.missing_history(0u32.into()),
+            KvStoreError::NotFound
+        ));
+
+        assert_eq!(db.prune(1).unwrap(), 3);
+
+        assert_eq!(db.pruned_height().unwrap(), Some(2u32.into()));
+        assert!(is_pruned(&db, &txs[0]));
+        assert!(is_pruned(&db, &txs[1]));
+        assert!(!is_pruned(&db, &txs[2]));
+        // the unspent outputs and the blocks are kept
+        let output = UtxoId::new(txs[0].id(), 0);
+        assert!(Storage::<UtxoId, Coin>::contains_key(&db, &output).unwrap());
+        assert!(db.get_block_id(1u32.into()).unwrap().is_some());
+        // pruned blocks can't be rolled back
+        assert!(matches!(
+            db.rollback_to(1u32.into()),
+            Err(Error::MissingUndoData(2))
+        ));
+        assert!(matches!(
+            db.missing_history(2u32.into()),
+            KvStoreError::Pruned
+        ));
+        assert!(matches!(
+            db.missing_history(3u32.into()),
+            KvStoreError::NotFound
+        ));
+        // pruned transactions are told apart from unknown ones
+        assert!(matches!(
+            db.ensure_not_pruned(&txs[0].id()),
+            Err(KvStoreError::Pruned)
+        ));
+        assert!(db.ensure_not_pruned(&txs[2].id()).is_ok());
+        assert!(db.ensure_not_pruned(&rng.gen()).is_ok());
+
+        // only new blocks are pruned by the next runs
+        assert_eq!(db.prune(1).unwrap(), 0);
+        txs.push(execute(&mut rng, &mut db).await);
+        assert_eq!(db.prune(1).unwrap(), 1);
+        assert!(is_pruned(&db, &txs[2]));
+    }
+}
@@ -0,0 +1,236 @@
+//! Reverts executed blocks, e.g. after a bad block was committed or the DA layer reorganized.
+//!
+//! When a block is executed, the previous values of all the keys it changes are stored as its
+//! undo data. Rolling back applies the undo data of the blocks from the most recent one down.
+//! Historical queries apply it the same way to an in-memory view which is never committed.
+
+use crate::{
+    database::{columns::BLOCK_UNDO, Database},
+    model::BlockHeight,
+    state::{Error, WriteOperation},
+};
+use fuel_core_interfaces::common::fuel_types::Bytes32;
+use tracing::info;
+
+impl Database {
+    /// Stores the operations reverting the execution of the block at `height`.
+    pub(crate) fn insert_block_undo(
+        &self,
+        height: BlockHeight,
+        operations: Vec<WriteOperation>,
+    ) -> Result<(), Error> {
+        self.insert(height, BLOCK_UNDO, operations).map(|_| ())
+    }
+
+    /// Reverts the blocks above `height` and returns their ids, the most recent first.
+    ///
+    /// The rollback is atomic: nothing is changed if any of the blocks can't be reverted, like
+    /// the genesis block or blocks executed before undo data was recorded.
+    pub fn rollback_to(&self, height: BlockHeight) -> Result<Vec<Bytes32>, Error> {
+        let current = match self.get_block_height()? {
+            Some(current) if current > height => current,
+            _ => return Ok(vec![]),
+        };
+
+        let rollback = self.transaction();
+        let reverted = rollback.revert_blocks(height, current)?;
+        rollback.commit()?;
+
+        info!(
+            "Rolled back {} blocks from height {} to {}",
+            reverted.len(),
+            current,
+            height
+        );
+        Ok(reverted)
+    }
+
+    /// A read-only view of the state at `height`, built by reverting the newer blocks in memory.
+    ///
+    /// The view is never committed, so the reverts don't affect the database. It reads the
+    /// unchanged keys through to the database, which must not commit new blocks while the view
+    /// is used. Like a rollback, it fails with [`Error::MissingUndoData`] if the history of a
+    /// newer block was pruned.
+    pub fn state_at_height(&self, height: BlockHeight) -> Result<Database, Error> {
+        let view = self.transaction();
+        if let Some(current) = self.get_block_height()? {
+            if current > height {
+                view.revert_blocks(height, current)?;
+            }
+        }
+        Ok(view.as_ref().clone())
+    }
+
+    /// Applies the undo data of the blocks from `current` down to `height + 1` and returns their
+    /// ids.
+    fn revert_blocks(
+        &self,
+        height: BlockHeight,
+        current: BlockHeight,
+    ) -> Result<Vec<Bytes32>, Error> {
+        let mut reverted = vec![];
+        for block_height in (u32::from(height) + 1..=u32::from(current)).rev() {
+            let block_height = BlockHeight::from(block_height);
+            let block_id = self.get_block_id(block_height)?.ok_or(Error::Codec)?;
+            let operations: Vec<WriteOperation> = self
+                .remove(&block_height.to_bytes(), BLOCK_UNDO)?
+                .ok_or_else(|| Error::MissingUndoData(block_height.into()))?;
+            for operation in operations {
+                match operation {
+                    WriteOperation::Insert(key, column, value) => {
+                        self.data.put(key, column, value)?;
+                    }
+                    WriteOperation::Remove(key, column) => {
+                        self.data.delete(&key, column)?;
+                    }
+                }
+            }
+            reverted.push(block_id);
+        }
+        Ok(reverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        executor::Executor,
+        model::{Coin, CoinStatus},
+    };
+    use fuel_core_interfaces::common::{
+        fuel_asm::Opcode,
+        fuel_crypto::SecretKey,
+        fuel_storage::Storage,
+        fuel_tx::{Input, Output, Receipt, Transaction, TransactionBuilder, UtxoId},
+        fuel_vm::consts::REG_ONE,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::sync::Arc;
+
+    /// A transaction spending a new coin
+    fn transfer(rng: &mut StdRng, db: &mut Database) -> Transaction {
+        let tx =
+            TransactionBuilder::script(vec![Opcode::RET(REG_ONE)].into_iter().collect(), vec![])
+                .add_unsigned_coin_input(
+                    rng.gen(),
+                    &SecretKey::random(rng),
+                    100,
+                    Default::default(),
+                    0,
+                )
+                .add_output(Output::Coin {
+                    to: rng.gen(),
+                    amount: 100,
+                    asset_id: Default::default(),
+                })
+                .finalize();
+        if let Input::CoinSigned { utxo_id, owner, .. } = tx.inputs()[0] {
+            let coin = Coin {
+                owner,
+                amount: 100,
+                asset_id: Default::default(),
+                maturity: Default::default(),
+                status: CoinStatus::Unspent,
+                block_created: Default::default(),
+            };
+            Storage::<UtxoId, Coin>::insert(db, &utxo_id, &coin).unwrap();
+        }
+        tx
+    }
+
+    async fn execute(db: &Database, tx: Transaction) -> Bytes32 {
+        let executor = Executor {
+            database: db.clone(),
+            config: Config::local_node(),
+        };
+        let block = executor.submit_txs(vec![Arc::new(tx)]).await.unwrap();
+        block.id()
+    }
+
+    #[tokio::test]
+    async fn rollback_reverts_executed_blocks() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let first = transfer(&mut rng, &mut db);
+        let first_block = execute(&db, first).await;
+        let second = transfer(&mut rng, &mut db);
+        let third = transfer(&mut rng, &mut db);
+        let state = db.get_state_config().unwrap();
+
+        let second_block = execute(&db, second.clone()).await;
+        let third_block = execute(&db, third).await;
+        assert_eq!(db.get_block_height().unwrap(), Some(3u32.into()));
+
+        let reverted = db.rollback_to(1u32.into()).unwrap();
+
+        assert_eq!(reverted, vec![third_block, second_block]);
+        assert_eq!(db.get_block_height().unwrap(), Some(1u32.into()));
+        assert_eq!(db.get_block_id(1u32.into()).unwrap(), Some(first_block));
+        assert!(db.get_block_id(2u32.into()).unwrap().is_none());
+        assert!(!db.exists(&2u32.to_be_bytes(), BLOCK_UNDO).unwrap());
+        // the spent coins are unspent again and the new ones are gone
+        assert_eq!(db.get_state_config().unwrap(), state);
+        let tx_id = second.id();
+        assert!(Storage::<Bytes32, Transaction>::get(&db, &tx_id)
+            .unwrap()
+            .is_none());
+        assert!(Storage::<Bytes32, Vec<Receipt>>::get(&db, &tx_id)
+            .unwrap()
+            .is_none());
+        if let Output::Coin { to, .. } = second.outputs()[0] {
+            assert!(db.owned_coins(to, None, None).next().is_none());
+        }
+        // the transaction is unknown again, as before the block
+        assert!(db.get_tx_status(&tx_id).unwrap().is_none());
+
+        // nothing to do at the current height
+        assert!(db.rollback_to(1u32.into()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rollback_is_atomic() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let first = transfer(&mut rng, &mut db);
+        execute(&db, first).await;
+        let second = transfer(&mut rng, &mut db);
+        execute(&db, second).await;
+        let state = db.get_state_config().unwrap();
+
+        // like a block executed before undo data was recorded
+        let _: Option<Vec<WriteOperation>> = db.remove(&1u32.to_be_bytes(), BLOCK_UNDO).unwrap();
+
+        assert!(matches!(
+            db.rollback_to(0u32.into()),
+            Err(Error::MissingUndoData(1))
+        ));
+        assert_eq!(db.get_block_height().unwrap(), Some(2u32.into()));
+        assert_eq!(db.get_state_config().unwrap(), state);
+    }
+
+    #[tokio::test]
+    async fn state_at_height_is_an_uncommitted_view() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut db = Database::default();
+        let first = transfer(&mut rng, &mut db);
+        execute(&db, first).await;
+        let second = transfer(&mut rng, &mut db);
+        let state = db.get_state_config().unwrap();
+        execute(&db, second).await;
+        let latest = db.get_state_config().unwrap();
+
+        let past = db.state_at_height(1u32.into()).unwrap();
+
+        assert_eq!(past.get_block_height().unwrap(), Some(1u32.into()));
+        assert_eq!(past.get_state_config().unwrap(), state);
+        // the database itself is unchanged
+        assert_eq!(db.get_block_height().unwrap(), Some(2u32.into()));
+        assert_eq!(db.get_state_config().unwrap(), latest);
+        assert!(db.exists(&2u32.to_be_bytes(), BLOCK_UNDO).unwrap());
+        // the latest state is the current one
+        let current = db.state_at_height(2u32.into()).unwrap();
+        assert_eq!(current.get_state_config().unwrap(), latest);
+    }
+}
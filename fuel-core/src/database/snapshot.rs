@@ -0,0 +1,212 @@
+use crate::{
+    config::{
+        chain_config::{CoinConfig, ContractConfig, StateConfig},
+        state_file::StateEntry,
+    },
+    database::{
+        columns::{COIN, CONTRACTS, CONTRACTS_STATE},
+        Database,
+    },
+    model::{Coin, CoinStatus},
+    state::{Error, IterDirection},
+};
+use fuel_core_interfaces::common::{
+    fuel_storage::Storage,
+    fuel_tx::UtxoId,
+    fuel_types::{Bytes32, ContractId, Salt},
+    fuel_vm::prelude::Contract,
+};
+use itertools::Itertools;
+use std::convert::TryFrom;
+
+impl Database {
+    /// Exports the current state as a [`StateConfig`], so a new chain can start from it.
+    ///
+    /// Only unspent coins are exported. Coins and contracts keep their ids, and the state
+    /// starts at the current block height.
+    pub fn get_state_config(&self) -> Result<StateConfig, Error> {
+        let mut entries = vec![];
+        self.export_state(|entry| {
+            entries.push(entry);
+            Ok::<_, Error>(())
+        })?;
+        let mut state = StateConfig::from_entries(entries)
+            .expect("Contract storage is exported after its contract");
+        state.coins.get_or_insert_with(Vec::new);
+        state.contracts.get_or_insert_with(Vec::new);
+        Ok(state)
+    }
+
+    /// Streams the same state as [`Database::get_state_config`] into `sink`, one entry at a
+    /// time, so states larger than the available memory can be exported.
+    ///
+    /// The storage slots and balances of a contract are separate entries following it.
+    pub fn export_state<E, F>(&self, mut sink: F) -> Result<(), E>
+    where
+        E: From<Error>,
+        F: FnMut(StateEntry) -> Result<(), E>,
+    {
+        if let Some(height) = self.get_block_height()? {
+            sink(StateEntry::Height(height))?;
+        }
+
+        let coins = self
+            .iter_all::<Vec<u8>, Coin>(COIN, None, None, Some(IterDirection::Forward))
+            .filter_ok(|(_, coin)| coin.status == CoinStatus::Unspent);
+        for entry in coins {
+            let (key, coin) = entry?;
+            let utxo_id = utxo_id_from_bytes(&key)?;
+            sink(StateEntry::Coin(CoinConfig {
+                tx_id: Some(*utxo_id.tx_id()),
+                output_index: Some(utxo_id.output_index() as u64),
+                block_created: Some(coin.block_created),
+                maturity: Some(coin.maturity),
+                owner: coin.owner,
+                amount: coin.amount,
+                asset_id: coin.asset_id,
+            }))?;
+        }
+
+        let contracts =
+            self.iter_all::<Vec<u8>, Contract>(CONTRACTS, None, None, Some(IterDirection::Forward));
+        for entry in contracts {
+            let (key, code) = entry?;
+            let contract_id = ContractId::try_from(key.as_slice()).map_err(|_| Error::Codec)?;
+            self.export_contract(contract_id, code, &mut sink)?;
+        }
+        Ok(())
+    }
+
+    fn export_contract<E, F>(
+        &self,
+        contract_id: ContractId,
+        code: Contract,
+        sink: &mut F,
+    ) -> Result<(), E>
+    where
+        E: From<Error>,
+        F: FnMut(StateEntry) -> Result<(), E>,
+    {
+        let (salt, _) = Storage::<ContractId, (Salt, Bytes32)>::get(self, &contract_id)?
+            .ok_or(Error::Codec)?
+            .into_owned();
+        let utxo_id = Storage::<ContractId, UtxoId>::get(self, &contract_id)?
+            .ok_or(Error::Codec)?
+            .into_owned();
+        sink(StateEntry::Contract(ContractConfig {
+            code: code.into(),
+            salt,
+            state: Some(vec![]),
+            balances: Some(vec![]),
+            contract_id: Some(contract_id),
+            tx_id: Some(*utxo_id.tx_id()),
+            output_index: Some(utxo_id.output_index() as u64),
+        }))?;
+
+        let state = self
+            .iter_all::<Vec<u8>, Bytes32>(
+                CONTRACTS_STATE,
+                Some(contract_id.as_ref().to_vec()),
+                None,
+                Some(IterDirection::Forward),
+            )
+            .filter_ok(|(key, _)| key.starts_with(contract_id.as_ref()));
+        for entry in state {
+            let (key, value) = entry?;
+            let key = Bytes32::try_from(&key[contract_id.len()..]).map_err(|_| Error::Codec)?;
+            sink(StateEntry::ContractState(key, value))?;
+        }
+        for entry in self.contract_balances(contract_id, None, Some(IterDirection::Forward)) {
+            let (asset_id, amount) = entry?;
+            sink(StateEntry::ContractBalance(asset_id, amount))?;
+        }
+        Ok(())
+    }
+}
+
+fn utxo_id_from_bytes(key: &[u8]) -> Result<UtxoId, Error> {
+    match key {
+        [tx_id @ .., output_index] => Ok(UtxoId::new(
+            Bytes32::try_from(tx_id).map_err(|_| Error::Codec)?,
+            *output_index,
+        )),
+        [] => Err(Error::Codec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{chain_config::ChainConfig, Config},
+        service::FuelService,
+    };
+    use fuel_core_interfaces::common::{
+        fuel_asm::Opcode,
+        fuel_types::{Address, AssetId},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[tokio::test]
+    async fn exported_state_can_be_imported_into_new_chain() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let state = StateConfig {
+            coins: Some(vec![CoinConfig {
+                tx_id: Some(rng.gen()),
+                output_index: Some(3),
+                block_created: Some(1u32.into()),
+                maturity: Some(2u32.into()),
+                owner: rng.gen::<Address>(),
+                amount: 100,
+                asset_id: rng.gen::<AssetId>(),
+            }]),
+            contracts: Some(vec![ContractConfig {
+                code: Opcode::RET(0x10).to_bytes().to_vec(),
+                salt: rng.gen(),
+                state: Some(vec![(rng.gen(), rng.gen())]),
+                balances: Some(vec![(rng.gen(), 50)]),
+                // an id which isn't derived from the code, like the ones of deployed contracts
+                contract_id: Some(rng.gen()),
+                tx_id: Some(rng.gen()),
+                output_index: Some(1),
+            }]),
+            height: Some(10u32.into()),
+        };
+        let config = |state| Config {
+            chain_conf: ChainConfig {
+                initial_state: Some(state),
+                ..ChainConfig::local_testnet()
+            },
+            ..Config::local_node()
+        };
+
+        let db = Database::default();
+        FuelService::from_database(db.clone(), config(state.clone()))
+            .await
+            .unwrap();
+        let exported = db.get_state_config().unwrap();
+        assert_eq!(exported, state);
+
+        let forked = Database::default();
+        FuelService::from_database(forked.clone(), config(exported))
+            .await
+            .unwrap();
+        assert_eq!(forked.get_state_config().unwrap(), state);
+    }
+
+    #[test]
+    fn spent_coins_are_not_exported() {
+        let mut db = Database::default();
+        let coin = Coin {
+            owner: Default::default(),
+            amount: 10,
+            asset_id: Default::default(),
+            maturity: Default::default(),
+            status: CoinStatus::Spent,
+            block_created: Default::default(),
+        };
+        Storage::<UtxoId, Coin>::insert(&mut db, &Default::default(), &coin).unwrap();
+
+        assert_eq!(db.get_state_config().unwrap().coins, Some(vec![]));
+    }
+}
@@ -1,15 +1,11 @@
 use crate::{
-    database::{columns::CONTRACTS_STATE, Database},
-    state::{Error, IterDirection, MultiKey},
+    database::{columns::CONTRACTS_STATE, merkle::CONTRACTS_STATE_MERKLE, Database},
+    state::{Error, MultiKey},
 };
 use fuel_core_interfaces::{
     common::fuel_vm::prelude::MerkleRoot,
-    common::fuel_vm::{
-        crypto,
-        prelude::{Bytes32, ContractId, MerkleStorage},
-    },
+    common::fuel_vm::prelude::{Bytes32, ContractId, MerkleStorage},
 };
-use itertools::Itertools;
 use std::borrow::Cow;
 
 impl MerkleStorage<ContractId, Bytes32, Bytes32> for Database {
@@ -21,13 +17,19 @@ impl MerkleStorage<ContractId, Bytes32, Bytes32> for Database {
         key: &Bytes32,
         value: &Bytes32,
     ) -> Result<Option<Bytes32>, Error> {
-        let key = MultiKey::new((parent, key));
-        Database::insert(self, key.as_ref().to_vec(), CONTRACTS_STATE, *value).map_err(Into::into)
+        let db_key = MultiKey::new((parent, key));
+        let previous = Database::insert(self, db_key.as_ref().to_vec(), CONTRACTS_STATE, *value)?;
+        self.update_contract_merkle(CONTRACTS_STATE_MERKLE, parent, key, Some(value.as_ref()))?;
+        Ok(previous)
     }
 
     fn remove(&mut self, parent: &ContractId, key: &Bytes32) -> Result<Option<Bytes32>, Error> {
-        let key = MultiKey::new((parent, key));
-        Database::remove(self, key.as_ref(), CONTRACTS_STATE).map_err(Into::into)
+        let db_key = MultiKey::new((parent, key));
+        let previous: Option<Bytes32> = Database::remove(self, db_key.as_ref(), CONTRACTS_STATE)?;
+        if previous.is_some() {
+            self.update_contract_merkle(CONTRACTS_STATE_MERKLE, parent, key, None)?;
+        }
+        Ok(previous)
     }
 
     fn get(&self, parent: &ContractId, key: &Bytes32) -> Result<Option<Cow<Bytes32>>, Error> {
@@ -42,24 +44,7 @@ impl MerkleStorage<ContractId, Bytes32, Bytes32> for Database {
     }
 
     fn root(&mut self, parent: &ContractId) -> Result<MerkleRoot, Error> {
-        let items: Vec<_> = Database::iter_all::<Vec<u8>, Bytes32>(
-            self,
-            CONTRACTS_STATE,
-            Some(parent.as_ref().to_vec()),
-            None,
-            Some(IterDirection::Forward),
-        )
-        .try_collect()?;
-
-        let root = items
-            .iter()
-            .filter_map(|(key, value)| {
-                (&key[..parent.len()] == parent.as_ref()).then(|| (key, value))
-            })
-            .sorted_by_key(|t| t.0)
-            .map(|(_, value)| value);
-
-        Ok(crypto::ephemeral_merkle_root(root).into())
+        self.contract_merkle_root(CONTRACTS_STATE_MERKLE, parent)
     }
 }
 
@@ -9,7 +9,7 @@ use crate::{
 };
 use fuel_core_interfaces::common::{
     fuel_storage::Storage,
-    fuel_tx::{Bytes32, Transaction},
+    fuel_tx::{Bytes32, Input, Output, Transaction},
     fuel_types::Address,
 };
 use std::{borrow::Cow, ops::Deref};
@@ -86,6 +86,18 @@ impl Database {
         )
     }
 
+    pub fn remove_tx_id_owner(
+        &self,
+        owner: &Address,
+        block_height: BlockHeight,
+        tx_idx: TransactionIndex,
+    ) -> Result<Option<Bytes32>, Error> {
+        self.remove(
+            &owned_tx_index_key(owner, block_height, tx_idx),
+            TRANSACTIONS_BY_OWNER_BLOCK_IDX,
+        )
+    }
+
     pub fn update_tx_status(
         &self,
         tx_id: &Bytes32,
@@ -99,6 +111,33 @@ impl Database {
     }
 }
 
+/// The owners of the inputs and outputs of the transaction, under which it is indexed
+pub(crate) fn transaction_owners(tx: &Transaction) -> Vec<Address> {
+    let mut owners = vec![];
+    for input in tx.inputs() {
+        if let Input::CoinSigned { owner, .. } | Input::CoinPredicate { owner, .. } = input {
+            owners.push(*owner);
+        }
+    }
+
+    for output in tx.outputs() {
+        match output {
+            Output::Coin { to, .. }
+            | Output::Withdrawal { to, .. }
+            | Output::Change { to, .. }
+            | Output::Variable { to, .. } => {
+                owners.push(*to);
+            }
+            Output::Contract { .. } | Output::ContractCreated { .. } => {}
+        }
+    }
+
+    // dedupe owners from inputs and outputs prior to indexing
+    owners.sort();
+    owners.dedup();
+    owners
+}
+
 struct OwnedTransactionIndexKey {
     block_height: BlockHeight,
     tx_idx: TransactionIndex,
@@ -1,5 +1,5 @@
 use crate::database::Database;
-use crate::state::in_memory::transaction::MemoryTransactionView;
+use crate::state::{in_memory::transaction::MemoryTransactionView, ColumnId, WriteOperation};
 use std::{
     fmt::Debug,
     ops::{Deref, DerefMut},
@@ -47,9 +47,19 @@ impl Default for DatabaseTransaction {
 }
 
 impl DatabaseTransaction {
-    /// Commit all the changes in this transaction to the data source
+    /// Keys of the column modified by this transaction which aren't committed yet
+    pub fn changed_keys(&self, column: ColumnId) -> Vec<Vec<u8>> {
+        self.changes.changed_keys(column)
+    }
+
+    /// Operations reverting the changes of this transaction once it is committed
+    pub fn revert_operations(&self) -> crate::state::Result<Vec<WriteOperation>> {
+        self.changes.revert_operations()
+    }
+
+    /// Commit all the changes in this transaction to the data source, atomically: if it fails,
+    /// nothing is written and the error is returned.
     pub fn commit(self) -> crate::state::Result<()> {
-        // TODO: should commit be fallible if this api is meant to be atomic?
         self.changes.commit()
     }
 }
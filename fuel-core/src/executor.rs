@@ -1,10 +1,13 @@
 use crate::{
     config::Config,
-    database::{transaction::TransactionIndex, Database, KvStoreError},
-    model::{BlockHeight, Coin, CoinStatus, FuelBlock, FuelBlockDb},
+    database::{
+        transaction::{transaction_owners, TransactionIndex},
+        Database, KvStoreError,
+    },
+    model::{BlockHeight, Coin, CoinStatus, FuelBlock, FuelBlockConsensus, FuelBlockDb},
     tx_pool::TransactionStatus,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use fuel_core_interfaces::{
     common::{
         fuel_asm::Word,
@@ -22,11 +25,7 @@ use fuel_core_interfaces::{
     },
     model::FuelBlockHeader,
 };
-use std::{
-    error::Error as StdError,
-    ops::{Deref, DerefMut},
-    sync::Arc,
-};
+use std::{error::Error as StdError, ops::DerefMut, sync::Arc};
 use thiserror::Error;
 use tracing::{debug, warn};
 
@@ -47,110 +46,136 @@ pub struct Executor {
     pub config: Config,
 }
 
+/// The id of the coin paying the fees of the block to its producer. The coin is derived from the
+/// block, so validation creates the same coin as production.
+pub fn coinbase_utxo_id(block_id: &Bytes32) -> UtxoId {
+    UtxoId::new(*block_id, 0)
+}
+
 impl Executor {
+    /// Produce and commit a new block containing the given transactions.
+    /// Returns the block as it was committed, with the transactions malleated by the VM.
+    pub async fn submit_txs(&self, txs: Vec<Arc<Transaction>>) -> Result<FuelBlock, Error> {
+        self.submit_txs_at(txs, Utc::now()).await
+    }
+
+    /// Like [`Executor::submit_txs`], with the block produced at `time`.
     #[tracing::instrument(skip(self))]
-    pub async fn submit_txs(&self, txs: Vec<Arc<Transaction>>) -> Result<(), Error> {
-        let db = self.database.clone();
+    pub async fn submit_txs_at(
+        &self,
+        txs: Vec<Arc<Transaction>>,
+        time: DateTime<Utc>,
+    ) -> Result<FuelBlock, Error> {
+        let mut block = self.new_block(txs, time)?;
+        // immediately execute block
+        self.execute(&mut block, ExecutionMode::Production).await?;
+        Ok(block)
+    }
 
-        for tx in txs.iter() {
-            // set status to submitted
-            db.update_tx_status(&tx.id(), TransactionStatus::Submitted { time: Utc::now() })?;
-        }
+    /// Produces and commits a new block at `time` with the given transactions, together with its
+    /// `consensus` data. Unlike [`Executor::submit_txs_at`], the transactions which can't be
+    /// executed don't fail the block, they are left out of it and returned with the reason.
+    #[tracing::instrument(skip(self))]
+    pub async fn produce_block(
+        &self,
+        txs: Vec<Arc<Transaction>>,
+        time: DateTime<Utc>,
+        consensus: &FuelBlockConsensus,
+    ) -> Result<(FuelBlock, Vec<(TxId, Error)>), Error> {
+        let mut block = self.new_block(txs, time)?;
+        let skipped =
+            self.execute_block(&mut block, ExecutionMode::Production, true, Some(consensus))?;
+        Ok((block, skipped))
+    }
 
-        // setup and execute block
-        let current_height = db.get_block_height()?.unwrap_or_default();
-        let current_hash = db.get_block_id(current_height)?.unwrap_or_default();
+    /// A new block at `time` on top of the latest one, which isn't executed yet.
+    fn new_block(
+        &self,
+        txs: Vec<Arc<Transaction>>,
+        time: DateTime<Utc>,
+    ) -> Result<FuelBlock, Error> {
+        let current_height = self.database.get_block_height()?.unwrap_or_default();
+        let current_hash = self
+            .database
+            .get_block_id(current_height)?
+            .unwrap_or_default();
         let new_block_height = current_height + 1u32.into();
 
-        let mut block = FuelBlock {
+        Ok(FuelBlock {
             header: FuelBlockHeader {
                 height: new_block_height,
                 parent_hash: current_hash,
-                time: Utc::now(),
+                time,
+                producer: self
+                    .config
+                    .block_producer
+                    .coinbase_recipient
+                    .unwrap_or_default(),
                 ..Default::default()
             },
             transactions: txs.into_iter().map(|t| t.as_ref().clone()).collect(),
-        };
-        // immediately execute block
-        self.execute(&mut block, ExecutionMode::Production).await?;
-        Ok(())
+        })
     }
 
     #[tracing::instrument(skip(self))]
     pub async fn execute(&self, block: &mut FuelBlock, mode: ExecutionMode) -> Result<(), Error> {
+        self.execute_block(block, mode, false, None)?;
+        Ok(())
+    }
+
+    /// Executes and commits `block`. If `skip_failing` is set, the transactions which fail are
+    /// removed from the block in production mode instead of failing it, and returned.
+    fn execute_block(
+        &self,
+        block: &mut FuelBlock,
+        mode: ExecutionMode,
+        skip_failing: bool,
+        consensus: Option<&FuelBlockConsensus>,
+    ) -> Result<Vec<(TxId, Error)>, Error> {
         // Compute the block id before execution, if mode is set to production just use zeroed id.
         let pre_exec_block_id = match mode {
             ExecutionMode::Production => Default::default(),
             ExecutionMode::Validation => block.id(),
         };
 
+        if mode == ExecutionMode::Validation {
+            self.verify_block_limits(block)?;
+        }
+
         let mut block_db_transaction = self.database.transaction();
         let mut storage = StorageMap::new();
         let mut txs_merkle = MerkleTree::new(&mut storage);
         let mut tx_status = vec![];
         let mut coinbase = 0u64;
+        let mut skipped = vec![];
 
-        for (idx, tx) in block.transactions.iter_mut().enumerate() {
-            let tx_id = tx.id();
-
-            // Throw a clear error if the transaction id is a duplicate
-            if Storage::<Bytes32, Transaction>::contains_key(
-                block_db_transaction.deref_mut(),
-                &tx_id,
-            )? {
-                return Err(Error::TransactionIdCollision(tx_id));
-            }
+        let mut executed = Vec::with_capacity(block.transactions.len());
 
-            self.verify_tx_predicates(tx)?;
-
-            if self.config.utxo_validation {
-                // validate transaction has at least one coin
-                self.verify_tx_has_at_least_one_coin(tx)?;
-                // validate utxos exist and maturity is properly set
-                self.verify_input_state(block_db_transaction.deref(), tx, block.header.height)?;
-                // validate transaction signature
-                tx.validate_input_signature()
-                    .map_err(TransactionValidityError::from)?;
-            }
-
-            self.compute_contract_input_utxo_ids(tx, &mode, block_db_transaction.deref())?;
-
-            // verify that the tx has enough gas to cover committed costs
-            self.verify_gas(tx)?;
-
-            // index owners of inputs and outputs with tx-id, regardless of validity (hence block_tx instead of tx_db)
-            self.persist_owners_index(
-                block.header.height,
+        for tx in block.transactions.iter_mut() {
+            let tx_id = tx.id();
+            // the changes of a transaction are only kept if it can be executed
+            let mut tx_db_transaction = block_db_transaction.transaction();
+            let idx = tx_status.len();
+            let (status, tx_fee) = match self.execute_transaction(
                 tx,
-                &tx_id,
                 idx,
-                block_db_transaction.deref_mut(),
-            )?;
-
-            // execute transaction
-            // setup database view that only lives for the duration of vm execution
-            let mut sub_block_db_commit = block_db_transaction.transaction();
-            let sub_db_view = sub_block_db_commit.deref_mut();
-            // execution vm
-            let mut vm = Interpreter::with_storage(
-                sub_db_view.clone(),
-                self.config.chain_conf.transaction_parameters,
-            );
-            let vm_result = vm
-                .transact(tx.clone())
-                .map_err(|error| Error::VmExecution {
-                    error,
-                    transaction_id: tx_id,
-                })?
-                .into_owned();
-
-            // only commit state changes if execution was a success
-            if !vm_result.should_revert() {
-                sub_block_db_commit.commit()?;
-            }
+                &block.header,
+                &mode,
+                tx_db_transaction.deref_mut(),
+            ) {
+                Ok(executed) => executed,
+                Err(e @ Error::CorruptedBlockState(_)) => return Err(e),
+                Err(e) if skip_failing && mode == ExecutionMode::Production => {
+                    warn!("Transaction {:#x} left out of the block: {}", tx_id, e);
+                    skipped.push((tx_id, e));
+                    executed.push(false);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            tx_db_transaction.commit()?;
 
             // update block commitment
-            let tx_fee = self.total_fee_paid(tx, vm_result.receipts())?;
             coinbase = coinbase.checked_add(tx_fee).ok_or(Error::FeeOverflow)?;
 
             // include the canonical serialization of the malleated tx into the commitment,
@@ -159,83 +184,20 @@ impl Executor {
             // TODO: reference the bytes directly from VM memory to save serialization. This isn't
             //       possible atm because the change output values are set on the tx instance in the vm
             //       and not also on the in-memory representation of the tx.
-            let tx_bytes = vm_result.tx().clone().to_bytes();
+            let tx_bytes = tx.clone().to_bytes();
             txs_merkle
                 .push(&tx_bytes)
                 .expect("In-memory impl should be infallible");
 
-            match mode {
-                ExecutionMode::Validation => {
-                    // ensure tx matches vm output exactly
-                    if vm_result.tx() != tx {
-                        return Err(Error::InvalidTransactionOutcome {
-                            transaction_id: tx_id,
-                        });
-                    }
-                }
-                ExecutionMode::Production => {
-                    // malleate the block with the resultant tx from the vm
-                    *tx = vm_result.tx().clone()
-                }
-            }
-
-            // Store tx into the block db transaction
-            Storage::<Bytes32, Transaction>::insert(
-                block_db_transaction.deref_mut(),
-                &tx_id,
-                vm_result.tx(),
-            )?;
-
-            // change the spent status of the tx inputs
-            self.spend_inputs(vm_result.tx(), block_db_transaction.deref_mut())?;
-
-            // persist any outputs
-            self.persist_outputs(
-                block.header.height,
-                vm_result.tx(),
-                &tx_id,
-                block_db_transaction.deref_mut(),
-            )?;
-
-            // persist receipts
-            self.persist_receipts(
-                &tx_id,
-                vm_result.receipts(),
-                block_db_transaction.deref_mut(),
-            )?;
-
-            let status = if vm_result.should_revert() {
-                self.log_backtrace(&vm, vm_result.receipts());
-                // get reason for revert
-                let reason = vm_result
-                    .receipts()
-                    .iter()
-                    .find_map(|receipt| match receipt {
-                        // Format as `Revert($rA)`
-                        Receipt::Revert { ra, .. } => Some(format!("Revert({})", ra)),
-                        // Display PanicReason e.g. `OutOfGas`
-                        Receipt::Panic { reason, .. } => Some(format!("{}", reason.reason())),
-                        _ => None,
-                    })
-                    .unwrap_or_else(|| format!("{:?}", vm_result.state()));
-
-                TransactionStatus::Failed {
-                    block_id: Default::default(),
-                    time: block.header.time,
-                    reason,
-                    result: Some(*vm_result.state()),
-                }
-            } else {
-                // else tx was a success
-                TransactionStatus::Success {
-                    block_id: Default::default(),
-                    time: block.header.time,
-                    result: *vm_result.state(),
-                }
-            };
-
             // queue up status for this tx to be stored once block id is finalized.
             tx_status.push((tx_id, status));
+            executed.push(true);
+        }
+        if !skipped.is_empty() {
+            let mut executed = executed.into_iter();
+            block
+                .transactions
+                .retain(|_| executed.next().unwrap_or_default());
         }
 
         // check or set transaction commitment
@@ -256,7 +218,7 @@ impl Executor {
 
         let finalized_block_id = block.id();
 
-        debug!("Block {:#x} fees: {}", pre_exec_block_id, coinbase);
+        debug!("Block {:#x} fees: {}", finalized_block_id, coinbase);
 
         // check if block id doesn't match proposed block id
         if mode == ExecutionMode::Validation && pre_exec_block_id != finalized_block_id {
@@ -265,6 +227,18 @@ impl Executor {
             return Err(Error::InvalidBlockId);
         }
 
+        // pay the fees to the block producer, they are burned if there is none
+        if block.header.producer != Address::default() {
+            Executor::insert_coin(
+                block.header.height.into(),
+                coinbase_utxo_id(&finalized_block_id),
+                &coinbase,
+                &AssetId::default(),
+                &block.header.producer,
+                block_db_transaction.deref_mut(),
+            )?;
+        }
+
         // save the status for every transaction using the finalized block id
         self.persist_transaction_status(
             finalized_block_id,
@@ -278,8 +252,139 @@ impl Executor {
             &finalized_block_id,
             &block.to_db_block(),
         )?;
+        if let Some(consensus) = consensus {
+            Storage::<Bytes32, FuelBlockConsensus>::insert(
+                block_db_transaction.deref_mut(),
+                &finalized_block_id,
+                consensus,
+            )?;
+        }
+        // keep what's needed to revert the block
+        let undo = block_db_transaction.revert_operations()?;
+        block_db_transaction.insert_block_undo(block.header.height, undo)?;
         block_db_transaction.commit()?;
-        Ok(())
+        Ok(skipped)
+    }
+
+    /// Executes `tx` as the `idx`-th transaction of the block with `header` on top of `db`.
+    /// Returns the status of the transaction and the fee it paid. In production mode, `tx` is
+    /// malleated with the result of the VM.
+    fn execute_transaction(
+        &self,
+        tx: &mut Transaction,
+        idx: usize,
+        header: &FuelBlockHeader,
+        mode: &ExecutionMode,
+        db: &mut Database,
+    ) -> Result<(TransactionStatus, Word), Error> {
+        let tx_id = tx.id();
+
+        // Throw a clear error if the transaction id is a duplicate
+        if Storage::<Bytes32, Transaction>::contains_key(db, &tx_id)? {
+            return Err(Error::TransactionIdCollision(tx_id));
+        }
+
+        self.verify_tx_predicates(tx)?;
+
+        if self.config.utxo_validation {
+            // validate transaction has at least one coin
+            self.verify_tx_has_at_least_one_coin(tx)?;
+            // validate utxos exist and maturity is properly set
+            self.verify_input_state(db, tx, header.height)?;
+            // validate transaction signature
+            tx.validate_input_signature()
+                .map_err(TransactionValidityError::from)?;
+        }
+
+        self.compute_contract_input_utxo_ids(tx, mode, db)?;
+
+        // verify that the tx has enough gas to cover committed costs
+        self.verify_gas(tx)?;
+
+        // index owners of inputs and outputs with tx-id, regardless of validity (hence db instead of the vm sub transaction)
+        self.persist_owners_index(header.height, tx, &tx_id, idx, db)?;
+
+        // execute transaction
+        // setup database view that only lives for the duration of vm execution
+        let mut sub_block_db_commit = db.transaction();
+        let sub_db_view = sub_block_db_commit.deref_mut();
+        // execution vm
+        let mut vm = Interpreter::with_storage(
+            sub_db_view.clone(),
+            self.config.chain_conf.transaction_parameters,
+        );
+        let vm_result = vm
+            .transact(tx.clone())
+            .map_err(|error| Error::VmExecution {
+                error,
+                transaction_id: tx_id,
+            })?
+            .into_owned();
+
+        // only commit state changes if execution was a success
+        if !vm_result.should_revert() {
+            sub_block_db_commit.commit()?;
+        }
+
+        let tx_fee = self.total_fee_paid(tx, vm_result.receipts())?;
+
+        match mode {
+            ExecutionMode::Validation => {
+                // ensure tx matches vm output exactly
+                if vm_result.tx() != tx {
+                    return Err(Error::InvalidTransactionOutcome {
+                        transaction_id: tx_id,
+                    });
+                }
+            }
+            ExecutionMode::Production => {
+                // malleate the block with the resultant tx from the vm
+                *tx = vm_result.tx().clone()
+            }
+        }
+
+        // Store tx into the block db transaction
+        Storage::<Bytes32, Transaction>::insert(db, &tx_id, vm_result.tx())?;
+
+        // change the spent status of the tx inputs
+        self.spend_inputs(vm_result.tx(), db)?;
+
+        // persist any outputs
+        self.persist_outputs(header.height, vm_result.tx(), &tx_id, db)?;
+
+        // persist receipts
+        self.persist_receipts(&tx_id, vm_result.receipts(), db)?;
+
+        let status = if vm_result.should_revert() {
+            self.log_backtrace(&vm, vm_result.receipts());
+            // get reason for revert
+            let reason = vm_result
+                .receipts()
+                .iter()
+                .find_map(|receipt| match receipt {
+                    // Format as `Revert($rA)`
+                    Receipt::Revert { ra, .. } => Some(format!("Revert({})", ra)),
+                    // Display PanicReason e.g. `OutOfGas`
+                    Receipt::Panic { reason, .. } => Some(format!("{}", reason.reason())),
+                    _ => None,
+                })
+                .unwrap_or_else(|| format!("{:?}", vm_result.state()));
+
+            TransactionStatus::Failed {
+                block_id: Default::default(),
+                time: header.time,
+                reason,
+                result: Some(*vm_result.state()),
+            }
+        } else {
+            // else tx was a success
+            TransactionStatus::Success {
+                block_id: Default::default(),
+                time: header.time,
+                result: *vm_result.state(),
+            }
+        };
+        Ok((status, tx_fee))
     }
 
     // Waiting until accounts and genesis block setup is working
@@ -346,6 +451,34 @@ impl Executor {
         }
     }
 
+    /// Verify that the transactions of the block fit into the block limits of the chain
+    fn verify_block_limits(&self, block: &FuelBlock) -> Result<(), Error> {
+        let chain_conf = &self.config.chain_conf;
+        let used = block
+            .transactions
+            .iter()
+            .map(Transaction::gas_limit)
+            .fold(0, Word::saturating_add);
+        if used > chain_conf.max_gas_per_block {
+            return Err(Error::BlockGasLimitExceeded {
+                used,
+                limit: chain_conf.max_gas_per_block,
+            });
+        }
+        let size = block
+            .transactions
+            .iter()
+            .map(|tx| tx.metered_bytes_size() as u64)
+            .fold(0, u64::saturating_add);
+        if size > chain_conf.max_block_size {
+            return Err(Error::BlockSizeLimitExceeded {
+                size,
+                limit: chain_conf.max_block_size,
+            });
+        }
+        Ok(())
+    }
+
     /// Mark inputs as spent
     fn spend_inputs(&self, tx: &Transaction, db: &mut Database) -> Result<(), Error> {
         for input in tx.inputs() {
@@ -428,8 +561,7 @@ impl Executor {
                 .chain_conf
                 .transaction_parameters
                 .gas_price_factor as f64;
-            let byte_fees =
-                ((tx.metered_bytes_size() as Word * tx.byte_price()) as f64 / factor).ceil() as u64;
+            let byte_fees = self.byte_fee_paid(tx);
             let gas_fees = ((tx.gas_limit() * tx.gas_price()) as f64 / factor).ceil() as u64;
             let total_gas_required = spent_gas
                 .checked_add(byte_fees)
@@ -446,16 +578,48 @@ impl Executor {
         Ok(())
     }
 
-    fn total_fee_paid(&self, tx: &Transaction, receipts: &[Receipt]) -> Result<Word, Error> {
-        let mut fee = tx.metered_bytes_size() as Word * tx.byte_price();
-
-        for r in receipts {
-            if let Receipt::ScriptResult { gas_used, .. } = r {
-                fee = fee.checked_add(*gas_used).ok_or(Error::FeeOverflow)?;
-            }
-        }
+    /// The part of the fee paid for the size of the transaction
+    pub fn byte_fee_paid(&self, tx: &Transaction) -> Word {
+        let factor = self
+            .config
+            .chain_conf
+            .transaction_parameters
+            .gas_price_factor as f64;
+        ((tx.metered_bytes_size() as Word * tx.byte_price()) as f64 / factor).ceil() as Word
+    }
 
-        Ok(fee)
+    /// The fee the VM charged the transaction: the fee for its size and its gas limit, minus the
+    /// refund of the gas its execution didn't use.
+    pub fn total_fee_paid(&self, tx: &Transaction, receipts: &[Receipt]) -> Result<Word, Error> {
+        let factor = self
+            .config
+            .chain_conf
+            .transaction_parameters
+            .gas_price_factor as f64;
+        let gas_used = receipts
+            .iter()
+            .find_map(|receipt| match receipt {
+                Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let gas_fee = tx
+            .gas_limit()
+            .checked_mul(tx.gas_price())
+            .ok_or(Error::FeeOverflow)?;
+        let gas_fee = (gas_fee as f64 / factor).ceil() as Word;
+        // the VM rounds the refund down, in favor of the fee
+        let refund = tx
+            .gas_limit()
+            .saturating_sub(gas_used)
+            .checked_mul(tx.gas_price())
+            .ok_or(Error::FeeOverflow)?;
+        let refund = (refund as f64 / factor).floor() as Word;
+
+        self.byte_fee_paid(tx)
+            .checked_add(gas_fee)
+            .and_then(|fee| fee.checked_sub(refund))
+            .ok_or(Error::FeeOverflow)
     }
 
     /// In production mode, lookup and set the proper utxo ids for contract inputs
@@ -620,31 +784,8 @@ impl Executor {
         tx_idx: usize,
         db: &mut Database,
     ) -> Result<(), Error> {
-        let mut owners = vec![];
-        for input in tx.inputs() {
-            if let Input::CoinSigned { owner, .. } | Input::CoinPredicate { owner, .. } = input {
-                owners.push(owner);
-            }
-        }
-
-        for output in tx.outputs() {
-            match output {
-                Output::Coin { to, .. }
-                | Output::Withdrawal { to, .. }
-                | Output::Change { to, .. }
-                | Output::Variable { to, .. } => {
-                    owners.push(to);
-                }
-                Output::Contract { .. } | Output::ContractCreated { .. } => {}
-            }
-        }
-
-        // dedupe owners from inputs and outputs prior to indexing
-        owners.sort();
-        owners.dedup();
-
-        for owner in owners {
-            db.record_tx_id_owner(owner, block_height, tx_idx as TransactionIndex, tx_id)?;
+        for owner in transaction_owners(tx) {
+            db.record_tx_id_owner(&owner, block_height, tx_idx as TransactionIndex, tx_id)?;
         }
 
         Ok(())
@@ -740,6 +881,10 @@ pub enum Error {
     InvalidBlockId,
     #[error("No matching utxo for contract id ${0:#x}")]
     ContractUtxoMissing(ContractId),
+    #[error("The gas limit of the block transactions exceeds the block limit: {used} > {limit}")]
+    BlockGasLimitExceeded { used: Word, limit: Word },
+    #[error("The size of the block transactions exceeds the block limit: {size} > {limit}")]
+    BlockSizeLimitExceeded { size: u64, limit: u64 },
 }
 
 impl From<FuelBacktrace> for Error {
@@ -763,7 +908,7 @@ impl From<crate::state::Error> for Error {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::FuelBlockHeader;
+    use crate::{model::FuelBlockHeader, state::in_memory::faulty_store::FaultyStore};
     use fuel_core_interfaces::common::fuel_tx::ConsensusParameters;
     use fuel_core_interfaces::common::{
         fuel_asm::Opcode,
@@ -850,6 +995,96 @@ mod tests {
         assert!(validation_result.is_ok());
     }
 
+    #[tokio::test]
+    async fn executor_pays_the_fees_to_the_block_producer() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let recipient: Address = rng.gen();
+        let mut config = Config::local_node();
+        config.block_producer.coinbase_recipient = Some(recipient);
+        let producer = Executor {
+            database: Default::default(),
+            config,
+        };
+        let verifier = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        let tx = TxBuilder::new(2322u64)
+            .gas_limit(1000)
+            .gas_price(1)
+            .byte_price(1)
+            .coin_input(AssetId::default(), 1000)
+            .change_output(AssetId::default())
+            .build();
+        // a transaction without fees doesn't pay the producer
+        let free_tx = TxBuilder::new(2323u64)
+            .gas_limit(1000)
+            .coin_input(AssetId::default(), 1000)
+            .change_output(AssetId::default())
+            .build();
+
+        let block = producer
+            .submit_txs(vec![Arc::new(tx), Arc::new(free_tx)])
+            .await
+            .unwrap();
+        assert_eq!(block.header.producer, recipient);
+        let coinbase =
+            Storage::<UtxoId, Coin>::get(&producer.database, &coinbase_utxo_id(&block.id()))
+                .unwrap()
+                .expect("the fees should be paid to the producer")
+                .into_owned();
+        assert_eq!(coinbase.owner, recipient);
+        assert_eq!(coinbase.asset_id, AssetId::default());
+        // the producer is paid exactly what the VM charged the transactions
+        let charged = block
+            .transactions
+            .iter()
+            .map(|tx| match tx.outputs()[0] {
+                Output::Change { amount, .. } => 1000 - amount,
+                _ => panic!("the change output is missing"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(charged[1], 0);
+        assert!(charged[0] > 0);
+        assert_eq!(coinbase.amount, charged[0]);
+
+        // the validator pays the same coin to the producer of the block
+        verifier
+            .execute(&mut block.clone(), ExecutionMode::Validation)
+            .await
+            .unwrap();
+        let validated_coinbase =
+            Storage::<UtxoId, Coin>::get(&verifier.database, &coinbase_utxo_id(&block.id()))
+                .unwrap()
+                .unwrap();
+        assert_eq!(validated_coinbase.owner, coinbase.owner);
+        assert_eq!(validated_coinbase.amount, coinbase.amount);
+    }
+
+    #[tokio::test]
+    async fn executor_burns_the_fees_without_block_producer() {
+        let producer = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        let tx = TxBuilder::new(2322u64)
+            .gas_limit(1000)
+            .gas_price(1)
+            .byte_price(1)
+            .coin_input(AssetId::default(), 1000)
+            .change_output(AssetId::default())
+            .build();
+
+        let block = producer.submit_txs(vec![Arc::new(tx)]).await.unwrap();
+
+        assert_eq!(block.header.producer, Address::default());
+        assert!(
+            Storage::<UtxoId, Coin>::get(&producer.database, &coinbase_utxo_id(&block.id()))
+                .unwrap()
+                .is_none()
+        );
+    }
+
     // Ensure transaction commitment != default after execution
     #[tokio::test]
     async fn executor_commits_transactions_to_block() {
@@ -871,6 +1106,69 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn executor_invalidates_blocks_over_the_gas_limit() {
+        let producer = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        let mut verifier = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        // every transaction of the test block has a gas limit of 10
+        verifier.config.chain_conf.max_gas_per_block = 10 * 2 - 1;
+        let mut block = test_block(2);
+
+        producer
+            .execute(&mut block, ExecutionMode::Production)
+            .await
+            .unwrap();
+
+        let verify_result = verifier
+            .execute(&mut block, ExecutionMode::Validation)
+            .await;
+        assert!(matches!(
+            verify_result,
+            Err(Error::BlockGasLimitExceeded {
+                used: 20,
+                limit: 19
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn executor_invalidates_blocks_over_the_size_limit() {
+        let producer = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        let mut verifier = Executor {
+            database: Default::default(),
+            config: Config::local_node(),
+        };
+        let mut block = test_block(2);
+        let size = block
+            .transactions
+            .iter()
+            .map(|tx| tx.metered_bytes_size() as u64)
+            .sum::<u64>();
+        verifier.config.chain_conf.max_block_size = size - 1;
+
+        producer
+            .execute(&mut block, ExecutionMode::Production)
+            .await
+            .unwrap();
+
+        let verify_result = verifier
+            .execute(&mut block, ExecutionMode::Validation)
+            .await;
+        assert!(matches!(
+            verify_result,
+            Err(Error::BlockSizeLimitExceeded { limit, .. }) if limit == size - 1
+        ));
+    }
+
     // Ensure tx has at least one input to cover gas
     #[tokio::test]
     async fn executor_invalidates_missing_gas_input() {
@@ -1253,6 +1551,68 @@ mod tests {
         assert_eq!(coin.status, CoinStatus::Spent);
     }
 
+    #[tokio::test]
+    async fn failed_commit_leaves_the_store_untouched() {
+        let tx = TxBuilder::new(2322u64)
+            .coin_input(AssetId::default(), 100)
+            .change_output(AssetId::default())
+            .build();
+        let utxo_id = *tx.inputs()[0].utxo_id();
+        let store = Arc::new(FaultyStore::default());
+        let db = Database::with_data_source(store.clone());
+        let coin = Coin {
+            owner: Default::default(),
+            amount: 100,
+            asset_id: Default::default(),
+            maturity: Default::default(),
+            status: CoinStatus::Unspent,
+            block_created: Default::default(),
+        };
+        Storage::<UtxoId, Coin>::insert(&mut db.clone(), &utxo_id, &coin).unwrap();
+        let state = db.get_state_config().unwrap();
+
+        let executor = Executor {
+            database: db.clone(),
+            config: Config::local_node(),
+        };
+        let mut block = FuelBlock {
+            header: Default::default(),
+            transactions: vec![tx],
+        };
+
+        store.fail_batches(true);
+        let result = executor
+            .execute(&mut block.clone(), ExecutionMode::Production)
+            .await;
+
+        assert!(matches!(result, Err(Error::CorruptedBlockState(_))));
+        assert_eq!(db.get_state_config().unwrap(), state);
+        assert!(db.get_block_height().unwrap().is_none());
+        let tx_id = block.transactions[0].id();
+        assert!(!Storage::<Bytes32, Transaction>::contains_key(&db, &tx_id).unwrap());
+        assert_eq!(
+            Storage::<UtxoId, Coin>::get(&db, &utxo_id)
+                .unwrap()
+                .unwrap()
+                .status,
+            CoinStatus::Unspent
+        );
+
+        // the block executes once the store accepts writes again
+        store.fail_batches(false);
+        executor
+            .execute(&mut block, ExecutionMode::Production)
+            .await
+            .unwrap();
+        assert_eq!(
+            Storage::<UtxoId, Coin>::get(&db, &utxo_id)
+                .unwrap()
+                .unwrap()
+                .status,
+            CoinStatus::Spent
+        );
+    }
+
     #[tokio::test]
     async fn input_coins_are_marked_as_spent_with_utxo_validation_enabled() {
         // ensure coins are marked as spent after tx is processed
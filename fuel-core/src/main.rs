@@ -1,5 +1,14 @@
+use args::{Command, SnapshotFormat};
 use clap::Parser;
-use fuel_core::service::FuelService;
+use fuel_core::{
+    config::{chain_config::ChainConfig, state_file::BinaryStateWriter, Config},
+    service::FuelService,
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
 use tracing::{info, trace};
 
 mod args;
@@ -7,13 +16,74 @@ mod args;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // load configuration
-    let config = args::Opt::parse().exec()?;
+    let mut opt = args::Opt::parse();
+    let migrate_dry_run = opt.migrate_dry_run;
+    let command = opt.command.take();
+    let config = opt.exec()?;
     // log fuel-core version
     info!("Fuel Core version v{}", env!("CARGO_PKG_VERSION"));
     trace!("Initializing in TRACE mode.");
+    if migrate_dry_run {
+        let migrations = FuelService::pending_migrations(&config)?;
+        info!("{} database migrations are pending", migrations.len());
+        return Ok(());
+    }
+    match command {
+        Some(Command::Snapshot { output, format }) => return snapshot(&config, output, format),
+        Some(Command::Rollback { height }) => {
+            let database = FuelService::open_database(&config)?;
+            database.rollback_to(height.into())?;
+            return Ok(());
+        }
+        Some(Command::Backup { output }) => {
+            let database = FuelService::open_database(&config)?;
+            database.create_checkpoint(&output)?;
+            info!("Created a database checkpoint at {}", output.display());
+            return Ok(());
+        }
+        Some(Command::Restore { checkpoint }) => {
+            return FuelService::restore_database(&config, &checkpoint)
+        }
+        None => {}
+    }
     // initialize the server
     let server = FuelService::new_node(config).await?;
     // pause the main task while service is running
     server.run().await;
     Ok(())
 }
+
+/// Writes the state of the node's database as a chain config or as a binary state file
+fn snapshot(
+    config: &Config,
+    output: Option<PathBuf>,
+    format: SnapshotFormat,
+) -> anyhow::Result<()> {
+    let database = FuelService::open_database(config)?;
+    info!(
+        "Exporting the state at height {}",
+        database.get_block_height()?.unwrap_or_default()
+    );
+    let writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = BufWriter::new(writer);
+    match format {
+        SnapshotFormat::Json => {
+            let chain_config = ChainConfig {
+                initial_state: Some(database.get_state_config()?),
+                ..config.chain_conf.clone()
+            };
+            serde_json::to_writer_pretty(&mut writer, &chain_config)?;
+            writeln!(writer)?;
+        }
+        SnapshotFormat::Binary => {
+            let mut state_writer = BinaryStateWriter::new(writer)?;
+            database.export_state(|entry| state_writer.write(entry))?;
+            writer = state_writer.finish()?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
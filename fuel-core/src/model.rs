@@ -1,5 +1,6 @@
 pub use fuel_core_interfaces::model::{
-    BlockHeight, Coin, CoinStatus, FuelBlock, FuelBlockDb, FuelBlockHeader,
+    BlockHeight, Coin, CoinStatus, FuelBlock, FuelBlockConsensus, FuelBlockDb, FuelBlockHeader,
+    SealedFuelBlock,
 };
 
 pub type Hash = [u8; 32];
@@ -1,5 +1,7 @@
-use async_graphql::{EmptySubscription, MergedObject, Schema, SchemaBuilder};
+use async_graphql::{MergedObject, MergedSubscription, Schema, SchemaBuilder};
 
+pub mod admin;
+pub mod backup;
 pub mod balance;
 pub mod block;
 pub mod chain;
@@ -7,7 +9,10 @@ pub mod coin;
 pub mod contract;
 pub mod dap;
 pub mod health;
+pub mod history;
+pub mod limits;
 pub mod node_info;
+pub mod replica;
 pub mod scalars;
 pub mod tx;
 
@@ -26,19 +31,23 @@ pub struct Query(
 );
 
 #[derive(MergedObject, Default)]
-pub struct Mutation(dap::DapMutation, tx::TxMutation);
+pub struct Mutation(
+    dap::DapMutation,
+    tx::TxMutation,
+    block::BlockMutation,
+    backup::BackupMutation,
+);
 
-// Placeholder for when we need to add subscriptions
-// #[derive(MergedSubscription, Default)]
-// pub struct Subscription();
+#[derive(MergedSubscription, Default)]
+pub struct Subscription(block::BlockSubscription, tx::TxStatusSubscription);
 
-pub type CoreSchema = Schema<Query, Mutation, EmptySubscription>;
+pub type CoreSchema = Schema<Query, Mutation, Subscription>;
 
-pub fn build_schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
+pub fn build_schema() -> SchemaBuilder<Query, Mutation, Subscription> {
     Schema::build_with_ignore_name_conflicts(
         Query::default(),
         Mutation::default(),
-        EmptySubscription::default(),
+        Subscription::default(),
         ["TransactionConnection"],
     )
 }
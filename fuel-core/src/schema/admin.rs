@@ -0,0 +1,87 @@
+//! Access control of the operations reserved to the node operator, like the debugger.
+
+use crate::config::Config;
+use async_graphql::{Context, ErrorExtensions, Guard};
+
+/// Error code of admin operations requested without a valid token.
+pub const UNAUTHORIZED: &str = "UNAUTHORIZED";
+
+/// The bearer token sent with the `Authorization` header of a request.
+pub struct BearerToken(pub String);
+
+impl BearerToken {
+    /// Extracts the token from the value of an `Authorization` header.
+    pub fn from_header(authorization: &str) -> Option<Self> {
+        let (scheme, token) = authorization.trim().split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| BearerToken(token.trim().to_string()))
+    }
+}
+
+/// Restricts a field to requests authenticated with the configured admin token. Every request
+/// is allowed if the node has no admin token.
+pub struct AdminGuard;
+
+#[async_trait::async_trait]
+impl Guard for AdminGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let expected = match &ctx.data_unchecked::<Config>().graphql.admin_token {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        match ctx.data_opt::<BearerToken>() {
+            Some(BearerToken(token)) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                Ok(())
+            }
+            _ => Err(
+                async_graphql::Error::new("This operation requires a valid admin token")
+                    .extend_with(|_, e| e.set("code", UNAUTHORIZED)),
+            ),
+        }
+    }
+}
+
+/// Like [`AdminGuard`], but every request is refused if the node has no admin token, for the
+/// operations which must never be open to everyone.
+pub struct RequiredAdminGuard;
+
+#[async_trait::async_trait]
+impl Guard for RequiredAdminGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        if ctx.data_unchecked::<Config>().graphql.admin_token.is_none() {
+            return Err(async_graphql::Error::new(
+                "This operation is disabled on nodes without an admin token",
+            )
+            .extend_with(|_, e| e.set("code", UNAUTHORIZED)));
+        }
+        AdminGuard.check(ctx).await
+    }
+}
+
+/// Compares the tokens without leaking the length of their common prefix through timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_token_is_parsed_from_authorization_header() {
+        let token = |header| BearerToken::from_header(header).map(|BearerToken(token)| token);
+
+        assert_eq!(token("Bearer secret"), Some("secret".to_string()));
+        assert_eq!(token("bearer  secret "), Some("secret".to_string()));
+        assert_eq!(token("Basic c2VjcmV0"), None);
+        assert_eq!(token("secret"), None);
+    }
+
+    #[test]
+    fn tokens_are_compared_by_content_and_length() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    config::Config,
+    database::Database,
+    schema::{admin::RequiredAdminGuard, replica::PrimaryGuard},
+};
+use anyhow::anyhow;
+use async_graphql::{Context, Object};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Default)]
+pub struct BackupMutation;
+
+#[Object]
+impl BackupMutation {
+    /// Writes a consistent copy of the database of the running node to `path`, a new directory
+    /// in the checkpoint directory of the node, which a node can be restored from.
+    #[graphql(guard = "RequiredAdminGuard.and(PrimaryGuard)")]
+    async fn create_checkpoint(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "path of the checkpoint, relative to the checkpoint directory")]
+        path: String,
+    ) -> async_graphql::Result<bool> {
+        let dir = ctx
+            .data_unchecked::<Config>()
+            .graphql
+            .checkpoint_dir
+            .clone()
+            .ok_or_else(|| anyhow!("The node has no checkpoint directory configured"))?;
+        let db = ctx.data_unchecked::<Database>().clone();
+        let path = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            let path = checkpoint_path(&dir, &path)?;
+            db.create_checkpoint(&path)?;
+            Ok(path)
+        })
+        .await??;
+        info!("Created a database checkpoint at {}", path.display());
+        Ok(true)
+    }
+}
+
+/// Resolves the `path` of a new checkpoint in `dir`. Fails if it would be written outside of
+/// `dir`, e.g. through `..` or a symbolic link.
+fn checkpoint_path(dir: &Path, path: &str) -> anyhow::Result<PathBuf> {
+    let path = dir.join(path);
+    let outside = || anyhow!("Checkpoints can only be written to the checkpoint directory");
+    // the name of the checkpoint can't be `..`, and its parent must exist
+    let name = path.file_name().ok_or_else(outside)?;
+    let parent = path.parent().ok_or_else(outside)?.canonicalize()?;
+    if !parent.starts_with(dir.canonicalize()?) {
+        return Err(outside());
+    }
+    Ok(parent.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn checkpoints_are_only_written_to_the_checkpoint_directory() {
+        let tmp_dir = TempDir::new().unwrap();
+        let dir = tmp_dir.path().join("checkpoints");
+        std::fs::create_dir_all(dir.join("daily")).unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        assert_eq!(checkpoint_path(&dir, "first").unwrap(), dir.join("first"));
+        assert_eq!(
+            checkpoint_path(&dir, "daily/first").unwrap(),
+            dir.join("daily").join("first")
+        );
+        assert_eq!(
+            checkpoint_path(&dir, dir.join("first").to_str().unwrap()).unwrap(),
+            dir.join("first")
+        );
+        for path in ["../first", "daily/../../first", "daily/..", "/tmp/first"] {
+            assert!(checkpoint_path(&dir, path).is_err(), "{}", path);
+        }
+        // missing parents aren't created
+        assert!(checkpoint_path(&dir, "weekly/first").is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use crate::database::{Database, KvStoreError};
 use crate::model::{Coin as CoinModel, CoinStatus};
+use crate::schema::history::state_at;
+use crate::schema::limits::check_page_size;
 use crate::schema::scalars::{Address, AssetId, U64};
 use crate::state::{Error, IterDirection};
 use async_graphql::InputObject;
@@ -35,6 +37,8 @@ impl Balance {
 struct BalanceFilterInput {
     /// Filter coins based on the `owner` field
     owner: Address,
+    /// Height of the state to query, the latest one by default
+    at_height: Option<U64>,
 }
 
 #[derive(Default)]
@@ -47,8 +51,11 @@ impl BalanceQuery {
         ctx: &Context<'_>,
         #[graphql(desc = "address of the owner")] owner: Address,
         #[graphql(desc = "asset_id of the coin")] asset_id: AssetId,
+        #[graphql(desc = "height of the state to query, the latest one by default")]
+        at_height: Option<U64>,
     ) -> async_graphql::Result<Balance> {
-        let db = ctx.data_unchecked::<Database>();
+        let state = state_at(ctx, at_height).await?;
+        let db: &Database = &state;
 
         let balance = db
             .owned_coins(owner.into(), None, None)
@@ -90,7 +97,9 @@ impl BalanceQuery {
         last: Option<i32>,
         before: Option<String>,
     ) -> async_graphql::Result<Connection<AssetId, Balance, EmptyFields, EmptyFields>> {
-        let db = ctx.data_unchecked::<Database>();
+        check_page_size(ctx, first, last)?;
+        let state = state_at(ctx, filter.at_height).await?;
+        let db: &Database = &state;
 
         let balances = db
             .owned_coins(filter.owner.into(), None, None)
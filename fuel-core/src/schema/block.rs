@@ -1,22 +1,38 @@
+use crate::config::{chain_config::ProductionStrategy, Config};
 use crate::database::Database;
 use crate::schema::{
-    scalars::{BlockId, U64},
+    admin::AdminGuard,
+    limits::{check_list_complexity, check_page_size},
+    replica::PrimaryGuard,
+    scalars::{BlockId, Bytes32, U64},
     tx::types::Transaction,
 };
 use crate::{
     database::KvStoreError,
-    model::{BlockHeight, FuelBlockDb},
+    model::{BlockHeight, FuelBlockConsensus, FuelBlockDb, FuelBlockHeader},
     state::IterDirection,
 };
 use async_graphql::{
     connection::{query, Connection, Edge, EmptyFields},
-    Context, Object,
+    Context, Object, Subscription,
 };
 use chrono::{DateTime, Utc};
-use fuel_core_interfaces::common::{fuel_storage::Storage, fuel_tx, fuel_types};
+use fuel_block_importer::Service as BlockImporterService;
+use fuel_block_producer::Service as BlockProducerService;
+use fuel_core_interfaces::{
+    block_importer::ImportBlockBroadcast,
+    block_producer::BlockProducerMpsc,
+    common::{fuel_storage::Storage, fuel_tx, fuel_types},
+    model::ValidatorStake,
+};
+use futures::{future, Stream, StreamExt};
 use itertools::Itertools;
 use std::borrow::Cow;
 use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::BroadcastStream;
 
 use super::scalars::Address;
 
@@ -33,14 +49,15 @@ impl Block {
     }
 
     async fn transactions(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Transaction>> {
+        check_list_complexity(ctx, self.0.transactions.len())?;
         let db = ctx.data_unchecked::<Database>().clone();
         self.0
             .transactions
             .iter()
             .map(|tx_id| {
                 Ok(Transaction(
-                    Storage::<fuel_types::Bytes32, fuel_tx::Transaction>::get(&db, tx_id)
-                        .and_then(|v| v.ok_or(KvStoreError::NotFound))?
+                    Storage::<fuel_types::Bytes32, fuel_tx::Transaction>::get(&db, tx_id)?
+                        .ok_or_else(|| db.missing_history(self.0.headers.height))?
                         .into_owned(),
                 ))
             })
@@ -54,6 +71,108 @@ impl Block {
     async fn producer(&self) -> Address {
         self.0.headers.producer.into()
     }
+
+    async fn header(&self) -> Header {
+        Header(self.0.headers.clone())
+    }
+
+    /// The consensus data the block was sealed with, if it was sealed.
+    async fn consensus(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Consensus>> {
+        let db = ctx.data_unchecked::<Database>();
+        let consensus = Storage::<fuel_types::Bytes32, FuelBlockConsensus>::get(db, &self.0.id())?
+            .map(|consensus| Consensus(consensus.into_owned()));
+        Ok(consensus)
+    }
+}
+
+pub struct Header(pub(crate) FuelBlockHeader);
+
+#[Object]
+impl Header {
+    /// Hash of the header.
+    async fn id(&self) -> BlockId {
+        self.0.id().into()
+    }
+
+    /// Fuel block height.
+    async fn height(&self) -> U64 {
+        self.0.height.into()
+    }
+
+    /// The layer 1 height of deposits and events included since the previous block.
+    async fn da_height(&self) -> U64 {
+        self.0.number.into()
+    }
+
+    /// Hash of the previous block header.
+    async fn parent_hash(&self) -> Bytes32 {
+        self.0.parent_hash.into()
+    }
+
+    /// Merkle root of all previous block header hashes.
+    async fn prev_root(&self) -> Bytes32 {
+        self.0.prev_root.into()
+    }
+
+    /// Merkle root of the transactions in the block.
+    async fn transactions_root(&self) -> Bytes32 {
+        self.0.transactions_root.into()
+    }
+
+    /// The block producer time.
+    async fn time(&self) -> DateTime<Utc> {
+        self.0.time
+    }
+
+    /// The block producer public key.
+    async fn producer(&self) -> Address {
+        self.0.producer.into()
+    }
+}
+
+pub struct Consensus(pub(crate) FuelBlockConsensus);
+
+#[Object]
+impl Consensus {
+    /// Stake required to seal the next block.
+    async fn required_stake(&self) -> U64 {
+        self.0.required_stake.into()
+    }
+
+    /// Validators that signed the block, ordered by their consensus key.
+    async fn validators(&self) -> Vec<ValidatorSignature> {
+        self.0
+            .validators
+            .iter()
+            .map(|(consensus_key, (stake, signature))| ValidatorSignature {
+                consensus_key: *consensus_key,
+                stake: *stake,
+                signature: *signature,
+            })
+            .sorted_by_key(|validator| validator.consensus_key)
+            .collect()
+    }
+}
+
+pub struct ValidatorSignature {
+    consensus_key: fuel_types::Address,
+    stake: ValidatorStake,
+    signature: fuel_types::Address,
+}
+
+#[Object]
+impl ValidatorSignature {
+    async fn consensus_key(&self) -> Address {
+        self.consensus_key.into()
+    }
+
+    async fn stake(&self) -> U64 {
+        self.stake.into()
+    }
+
+    async fn signature(&self) -> Address {
+        self.signature.into()
+    }
 }
 
 #[derive(Default)]
@@ -77,14 +196,8 @@ impl BlockQuery {
             (Some(id), None) => id.into(),
             (None, Some(height)) => {
                 let height: u64 = height.into();
-                if height == 0 {
-                    return Err(async_graphql::Error::new(
-                        "Genesis block isn't implemented yet",
-                    ));
-                } else {
-                    db.get_block_id(height.try_into()?)?
-                        .ok_or("Block height non-existent")?
-                }
+                db.get_block_id(height.try_into()?)?
+                    .ok_or("Block height non-existent")?
             }
             (None, None) => return Err(async_graphql::Error::new("Missing either id or height")),
         };
@@ -102,6 +215,7 @@ impl BlockQuery {
         last: Option<i32>,
         before: Option<String>,
     ) -> async_graphql::Result<Connection<usize, Block, EmptyFields, EmptyFields>> {
+        check_page_size(ctx, first, last)?;
         let db = ctx.data_unchecked::<Database>().clone();
 
         query(
@@ -175,3 +289,107 @@ impl BlockQuery {
         .await
     }
 }
+
+#[derive(Default)]
+pub struct BlockMutation;
+
+#[Object]
+impl BlockMutation {
+    /// Produces `count` blocks from the pending transactions of the txpool, on nodes producing
+    /// blocks on demand with the `Manual` strategy. The first block is produced at `start_time`,
+    /// the current time by default, and the next ones `block_time_interval` seconds apart, 1 by
+    /// default. Returns the height of the latest block.
+    #[graphql(guard = "AdminGuard.and(PrimaryGuard)")]
+    async fn produce_blocks(
+        &self,
+        ctx: &Context<'_>,
+        count: U64,
+        start_time: Option<DateTime<Utc>>,
+        block_time_interval: Option<U64>,
+    ) -> async_graphql::Result<U64> {
+        let config = ctx.data_unchecked::<Config>();
+        if config.chain_conf.block_production != ProductionStrategy::Manual {
+            return Err(async_graphql::Error::new(
+                "Blocks are only produced on demand with the manual block production strategy",
+            ));
+        }
+        let block_producer = ctx.data_unchecked::<Arc<BlockProducerService>>();
+
+        let (response, receiver) = oneshot::channel();
+        block_producer
+            .sender()
+            .send(BlockProducerMpsc::ProduceBlocks {
+                count: u64::from(count).try_into()?,
+                start_time: start_time.unwrap_or_else(Utc::now),
+                block_time_interval: Duration::from_secs(block_time_interval.map_or(1, Into::into)),
+                response,
+            })
+            .await
+            .map_err(|_| "The block producer is stopped")?;
+        receiver.await??;
+
+        let db = ctx.data_unchecked::<Database>();
+        Ok(db.get_block_height()?.unwrap_or_default().into())
+    }
+}
+
+#[derive(Default)]
+pub struct BlockSubscription;
+
+#[Subscription]
+impl BlockSubscription {
+    /// Emits every block as soon as it is committed by the node.
+    async fn new_blocks(&self, ctx: &Context<'_>) -> impl Stream<Item = Block> {
+        let block_importer = ctx.data_unchecked::<Arc<BlockImporterService>>();
+
+        BroadcastStream::new(block_importer.subscribe()).filter_map(|event| {
+            future::ready(match event {
+                Ok(ImportBlockBroadcast::SealedFuelBlockImported { block, .. }) => {
+                    Some(Block(block.block.to_db_block()))
+                }
+                // Pending blocks aren't committed yet. A lagging subscriber skips the blocks
+                // it missed rather than terminating the stream.
+                Ok(ImportBlockBroadcast::PendingBlockImported { .. }) | Err(_) => None,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::test_utils::start_schema;
+    use async_graphql::{value, Request};
+    use fuel_core_interfaces::common::{fuel_tx, fuel_types::bytes::SerializableVec};
+    use futures::{poll, StreamExt};
+
+    #[tokio::test]
+    async fn new_blocks_emits_committed_blocks() {
+        let (schema, _modules) = start_schema(Config::local_node(), Default::default()).await;
+        let mut blocks = schema.execute_stream(Request::new(
+            "subscription { newBlocks { height transactions { id } } }",
+        ));
+        // the first poll subscribes to the committed blocks
+        assert!(poll!(blocks.next()).is_pending());
+
+        let tx = fuel_tx::Transaction::default();
+        let submitted = schema
+            .execute(format!(
+                r#"mutation {{ submit(tx: "0x{}") {{ id }} }}"#,
+                hex::encode(tx.clone().to_bytes())
+            ))
+            .await;
+        assert!(submitted.errors.is_empty(), "{:?}", submitted.errors);
+
+        let block = blocks.next().await.unwrap();
+        assert_eq!(
+            block.data,
+            value!({
+                "newBlocks": {
+                    "height": "1",
+                    "transactions": [{ "id": format!("{:#x}", tx.id()) }],
+                }
+            })
+        );
+    }
+}
@@ -2,7 +2,11 @@ use crate::{
     coin_query::{random_improve, SpendQueryElement},
     config::Config,
     database::{Database, KvStoreError},
-    schema::scalars::{Address, AssetId, UtxoId, U64},
+    schema::{
+        history::state_at,
+        limits::check_page_size,
+        scalars::{Address, AssetId, UtxoId, U64},
+    },
     state::IterDirection,
 };
 use async_graphql::{
@@ -22,7 +26,7 @@ pub enum CoinStatus {
     Spent,
 }
 
-pub struct Coin(fuel_tx::UtxoId, CoinModel);
+pub struct Coin(pub(crate) fuel_tx::UtxoId, pub(crate) CoinModel);
 
 #[Object]
 impl Coin {
@@ -61,6 +65,8 @@ struct CoinFilterInput {
     owner: Address,
     /// Asset ID of the coins
     asset_id: Option<AssetId>,
+    /// Height of the state to query, the latest one by default
+    at_height: Option<U64>,
 }
 
 #[derive(InputObject)]
@@ -80,12 +86,18 @@ impl CoinQuery {
         &self,
         ctx: &Context<'_>,
         #[graphql(desc = "The ID of the coin")] utxo_id: UtxoId,
+        #[graphql(desc = "height of the state to query, the latest one by default")]
+        at_height: Option<U64>,
     ) -> async_graphql::Result<Option<Coin>> {
         let utxo_id = utxo_id.0;
-        let db = ctx.data_unchecked::<Database>().clone();
-        let block = Storage::<fuel_tx::UtxoId, CoinModel>::get(&db, &utxo_id)?
+        let state = state_at(ctx, at_height).await?;
+        let db: &Database = &state;
+        let coin = Storage::<fuel_tx::UtxoId, CoinModel>::get(db, &utxo_id)?
             .map(|coin| Coin(utxo_id, coin.into_owned()));
-        Ok(block)
+        if coin.is_none() {
+            db.ensure_coin_not_pruned(&utxo_id)?;
+        }
+        Ok(coin)
     }
 
     async fn coins(
@@ -97,7 +109,9 @@ impl CoinQuery {
         last: Option<i32>,
         before: Option<String>,
     ) -> async_graphql::Result<Connection<UtxoId, Coin, EmptyFields, EmptyFields>> {
-        let db = ctx.data_unchecked::<Database>();
+        check_page_size(ctx, first, last)?;
+        let state = state_at(ctx, filter.at_height).await?;
+        let db: &Database = &state;
 
         query(
             after,
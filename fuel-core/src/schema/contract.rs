@@ -1,5 +1,7 @@
 use crate::database::{Database, KvStoreError};
-use crate::schema::scalars::{AssetId, ContractId, HexString, Salt, U64};
+use crate::schema::history::state_at;
+use crate::schema::limits::check_page_size;
+use crate::schema::scalars::{AssetId, Bytes32, ContractId, HexString, Salt, U64};
 use crate::state::IterDirection;
 use anyhow::anyhow;
 use async_graphql::{
@@ -7,7 +9,8 @@ use async_graphql::{
     Context, InputObject, Object,
 };
 use fuel_core_interfaces::common::{
-    fuel_storage::Storage, fuel_tx, fuel_types, fuel_vm,
+    fuel_storage::{MerkleStorage, Storage},
+    fuel_tx, fuel_types, fuel_vm,
     fuel_vm::prelude::Contract as FuelVmContract,
 };
 use std::iter::IntoIterator;
@@ -67,6 +70,53 @@ impl ContractQuery {
         let contract = Contract(id);
         Ok(Some(contract))
     }
+
+    async fn contract_storage_slot(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "ID of the Contract")] contract: ContractId,
+        #[graphql(desc = "key of the storage slot")] key: Bytes32,
+        #[graphql(desc = "height of the state to query, the latest one by default")]
+        at_height: Option<U64>,
+    ) -> async_graphql::Result<ContractStorageSlot> {
+        let state = state_at(ctx, at_height).await?;
+        let db: &Database = &state;
+        // slots which were never written read as zero
+        let value = MerkleStorage::<
+            fuel_types::ContractId,
+            fuel_types::Bytes32,
+            fuel_types::Bytes32,
+        >::get(db, &contract.0, &key.0)?
+        .map(|value| value.into_owned())
+        .unwrap_or_default();
+
+        Ok(ContractStorageSlot {
+            contract: contract.0,
+            key: key.0,
+            value,
+        })
+    }
+}
+
+pub struct ContractStorageSlot {
+    contract: fuel_types::ContractId,
+    key: fuel_types::Bytes32,
+    value: fuel_types::Bytes32,
+}
+
+#[Object]
+impl ContractStorageSlot {
+    async fn contract(&self) -> ContractId {
+        self.contract.into()
+    }
+
+    async fn key(&self) -> Bytes32 {
+        self.key.into()
+    }
+
+    async fn value(&self) -> Bytes32 {
+        self.value.into()
+    }
 }
 
 pub struct ContractBalance {
@@ -94,6 +144,8 @@ impl ContractBalance {
 struct ContractBalanceFilterInput {
     /// Filter assets based on the `contractId` field
     contract: ContractId,
+    /// Height of the state to query, the latest one by default
+    at_height: Option<U64>,
 }
 
 #[derive(Default)]
@@ -106,15 +158,18 @@ impl ContractBalanceQuery {
         ctx: &Context<'_>,
         contract: ContractId,
         asset: AssetId,
+        #[graphql(desc = "height of the state to query, the latest one by default")]
+        at_height: Option<U64>,
     ) -> async_graphql::Result<ContractBalance> {
         let contract_id: fuel_types::ContractId = contract.0;
 
-        let db = ctx.data_unchecked::<Database>().clone();
+        let state = state_at(ctx, at_height).await?;
+        let db: &Database = &state;
 
         let asset_id: fuel_types::AssetId = asset.into();
 
         let result = fuel_vm::storage::InterpreterStorage::merkle_contract_asset_id_balance(
-            &db,
+            db,
             &contract_id,
             &asset_id,
         );
@@ -136,7 +191,9 @@ impl ContractBalanceQuery {
         last: Option<i32>,
         before: Option<String>,
     ) -> async_graphql::Result<Connection<AssetId, ContractBalance, EmptyFields, EmptyFields>> {
-        let db = ctx.data_unchecked::<Database>().clone();
+        check_page_size(ctx, first, last)?;
+        let state = state_at(ctx, filter.at_height).await?;
+        let db: &Database = &state;
 
         query(
             after,
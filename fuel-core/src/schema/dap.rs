@@ -1,6 +1,6 @@
 use crate::database::transactional::DatabaseTransaction;
 use crate::database::Database;
-use crate::schema::scalars::U64;
+use crate::schema::{admin::AdminGuard, scalars::U64};
 use async_graphql::{Context, Object, SchemaBuilder, ID};
 use fuel_core_interfaces::common::{
     fuel_tx::ConsensusParameters,
@@ -123,6 +123,7 @@ pub fn init<Q, M, S>(
 
 #[Object]
 impl DapQuery {
+    #[graphql(guard = "AdminGuard")]
     async fn register(
         &self,
         ctx: &Context<'_>,
@@ -137,6 +138,7 @@ impl DapQuery {
             .map(|val| val.into())
     }
 
+    #[graphql(guard = "AdminGuard")]
     async fn memory(
         &self,
         ctx: &Context<'_>,
@@ -155,6 +157,7 @@ impl DapQuery {
 
 #[Object]
 impl DapMutation {
+    #[graphql(guard = "AdminGuard")]
     async fn start_session(&self, ctx: &Context<'_>) -> async_graphql::Result<ID> {
         trace!("Initializing new interpreter");
 
@@ -171,6 +174,7 @@ impl DapMutation {
         Ok(id)
     }
 
+    #[graphql(guard = "AdminGuard")]
     async fn end_session(&self, ctx: &Context<'_>, id: ID) -> bool {
         let existed = ctx.data_unchecked::<GraphStorage>().lock().await.kill(&id);
 
@@ -179,6 +183,7 @@ impl DapMutation {
         existed
     }
 
+    #[graphql(guard = "AdminGuard")]
     async fn reset(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<bool> {
         let db = ctx.data_unchecked::<Database>();
 
@@ -192,6 +197,7 @@ impl DapMutation {
         Ok(true)
     }
 
+    #[graphql(guard = "AdminGuard")]
     async fn execute(&self, ctx: &Context<'_>, id: ID, op: String) -> async_graphql::Result<bool> {
         trace!("Execute encoded op {}", op);
 
@@ -208,6 +214,7 @@ impl DapMutation {
     }
 
     #[cfg(not(feature = "debug"))]
+    #[graphql(guard = "AdminGuard")]
     async fn set_single_stepping(
         &self,
         _ctx: &Context<'_>,
@@ -220,6 +227,7 @@ impl DapMutation {
     }
 
     #[cfg(feature = "debug")]
+    #[graphql(guard = "AdminGuard")]
     async fn set_single_stepping(
         &self,
         ctx: &Context<'_>,
@@ -239,6 +247,7 @@ impl DapMutation {
     }
 
     #[cfg(not(feature = "debug"))]
+    #[graphql(guard = "AdminGuard")]
     async fn set_breakpoint(
         &self,
         _ctx: &Context<'_>,
@@ -251,6 +260,7 @@ impl DapMutation {
     }
 
     #[cfg(feature = "debug")]
+    #[graphql(guard = "AdminGuard")]
     async fn set_breakpoint(
         &self,
         ctx: &Context<'_>,
@@ -269,6 +279,7 @@ impl DapMutation {
         Ok(true)
     }
 
+    #[graphql(guard = "AdminGuard")]
     async fn start_tx(
         &self,
         ctx: &Context<'_>,
@@ -324,6 +335,7 @@ impl DapMutation {
     }
 
     #[cfg(not(feature = "debug"))]
+    #[graphql(guard = "AdminGuard")]
     async fn continue_tx(
         &self,
         _ctx: &Context<'_>,
@@ -335,6 +347,7 @@ impl DapMutation {
     }
 
     #[cfg(feature = "debug")]
+    #[graphql(guard = "AdminGuard")]
     async fn continue_tx(
         &self,
         ctx: &Context<'_>,
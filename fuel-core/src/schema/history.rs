@@ -0,0 +1,68 @@
+//! Resolves queries taking an `atHeight` argument against the state at a past block height.
+
+use crate::{
+    database::{Database, KvStoreError},
+    schema::{limits::check_history_depth, scalars::U64},
+    service::block_production::BlockCommits,
+    state::Error,
+};
+use anyhow::anyhow;
+use async_graphql::Context;
+use std::ops::Deref;
+use tokio::sync::RwLockReadGuard;
+
+/// The state a query is answered from.
+pub(crate) struct State<'a> {
+    database: Database,
+    // a past state is a view reverting the newer blocks on top of the database, no block is
+    // committed or pruned while it is read
+    _commits: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl Deref for State<'_> {
+    type Target = Database;
+
+    fn deref(&self) -> &Self::Target {
+        &self.database
+    }
+}
+
+/// The state to answer a query from: the latest one, or the one at `at_height`.
+///
+/// Fails if `at_height` is above the current height, more than `max_history_depth` blocks below
+/// it, or if the history of a newer block isn't available anymore.
+pub(crate) async fn state_at<'a>(
+    ctx: &Context<'a>,
+    at_height: Option<U64>,
+) -> async_graphql::Result<State<'a>> {
+    let db = ctx.data_unchecked::<Database>();
+    let height = match at_height {
+        Some(height) => height.0,
+        None => {
+            return Ok(State {
+                database: db.clone(),
+                _commits: None,
+            })
+        }
+    };
+    let commits = ctx.data_unchecked::<BlockCommits>().read().await;
+    let current = db.get_block_height()?.unwrap_or_default();
+    let height = u32::try_from(height)
+        .ok()
+        .filter(|height| *height <= u32::from(current))
+        .ok_or_else(|| anyhow!("Height {} is above the current height {}", height, current))?;
+    check_history_depth(ctx, u32::from(current) - height)?;
+
+    match db.state_at_height(height.into()) {
+        Ok(database) => Ok(State {
+            database,
+            _commits: Some(commits),
+        }),
+        Err(Error::MissingUndoData(_))
+            if matches!(db.missing_history(height.into()), KvStoreError::Pruned) =>
+        {
+            Err(KvStoreError::Pruned.into())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
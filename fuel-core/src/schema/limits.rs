@@ -0,0 +1,371 @@
+//! Enforces the query limits of [`GraphQLConfig`], so a single request can't make the node
+//! do an unbounded amount of work.
+
+use crate::config::{Config, GraphQLConfig};
+use async_graphql::{
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery, NextValidation,
+    },
+    parser::types::{
+        DocumentOperations, ExecutableDocument, OperationType, Selection, SelectionSet,
+    },
+    Context, ErrorExtensionValues, ErrorExtensions, Response, SelectionField, ServerError,
+    ServerResult, ValidationResult, Value, Variables,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// Error code of queries nested deeper than `max_depth`.
+pub const QUERY_TOO_DEEP: &str = "QUERY_TOO_DEEP";
+/// Error code of queries more complex than `max_complexity`.
+pub const QUERY_TOO_COMPLEX: &str = "QUERY_TOO_COMPLEX";
+/// Error code of connections asked for more than `max_page_size` items.
+pub const PAGE_SIZE_TOO_LARGE: &str = "PAGE_SIZE_TOO_LARGE";
+/// Error code of dry-runs of more than `max_dry_run_transactions` transactions.
+pub const TOO_MANY_TRANSACTIONS: &str = "TOO_MANY_TRANSACTIONS";
+/// Error code of queries at heights more than `max_history_depth` blocks below the latest one.
+pub const HISTORY_TOO_DEEP: &str = "HISTORY_TOO_DEEP";
+/// Error code of queries and mutations sent over a websocket connection.
+pub const NOT_A_SUBSCRIPTION: &str = "NOT_A_SUBSCRIPTION";
+
+/// Data of websocket connections. They only run subscriptions, queries and mutations have to
+/// be sent over http, where the rate limit, the request timeout and the body size limit apply.
+pub struct SubscriptionsOnly;
+
+/// Rejects queries exceeding the configured depth or complexity during validation, before
+/// anything is resolved.
+///
+/// Every field costs one, fields paginated with `first` or `last` multiply the cost of their
+/// selection by the requested page size. Unpaginated lists are counted once during validation,
+/// their resolvers add the cost of the other items with [`check_list_complexity`]. Queries at a
+/// past height add the number of blocks they revert with [`check_history_depth`].
+pub struct QueryLimits {
+    max_depth: usize,
+    max_complexity: usize,
+}
+
+impl QueryLimits {
+    pub fn new(config: &GraphQLConfig) -> Self {
+        Self {
+            max_depth: config.max_depth,
+            max_complexity: config.max_complexity,
+        }
+    }
+}
+
+impl ExtensionFactory for QueryLimits {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryLimitsExtension {
+            max_depth: self.max_depth,
+            max_complexity: self.max_complexity,
+            query: Default::default(),
+            complexity: Default::default(),
+        })
+    }
+}
+
+struct QueryLimitsExtension {
+    max_depth: usize,
+    max_complexity: usize,
+    // the parsed query of the request, kept to compute its complexity once it is validated
+    query: Mutex<Option<(ExecutableDocument, Variables)>>,
+    complexity: Arc<AtomicUsize>,
+}
+
+/// The complexity of the query being executed, grown by the resolvers of unpaginated lists.
+struct QueryComplexity {
+    value: Arc<AtomicUsize>,
+    limit: usize,
+}
+
+tokio::task_local! {
+    // set while queries and mutations are executed, the items emitted by subscriptions are only
+    // checked during validation
+    static QUERY_COMPLEXITY: QueryComplexity;
+}
+
+#[async_trait::async_trait]
+impl Extension for QueryLimitsExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        if ctx.data_opt::<SubscriptionsOnly>().is_some() && !only_subscriptions(&document) {
+            let mut extensions = ErrorExtensionValues::default();
+            extensions.set("code", NOT_A_SUBSCRIPTION);
+            let mut error = ServerError::new(
+                "Only subscriptions are served over websockets, queries and mutations have to \
+                 be sent over http",
+                None,
+            );
+            error.extensions = Some(extensions);
+            return Err(error);
+        }
+        *self.query.lock().expect("poisoned lock") = Some((document.clone(), variables.clone()));
+        Ok(document)
+    }
+
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+        if result.depth > self.max_depth {
+            return Err(vec![limit_exceeded(
+                format!(
+                    "Query depth of {} exceeds the maximum of {}",
+                    result.depth, self.max_depth
+                ),
+                QUERY_TOO_DEEP,
+                result.depth,
+                self.max_depth,
+            )]);
+        }
+        // the complexity computed by async-graphql ignores the page size of connections
+        let complexity = self
+            .query
+            .lock()
+            .expect("poisoned lock")
+            .take()
+            .map_or(0, |(document, variables)| {
+                document_complexity(&document, &variables)
+            });
+        if complexity > self.max_complexity {
+            return Err(vec![limit_exceeded(
+                format!(
+                    "Query complexity of {} exceeds the maximum of {}",
+                    complexity, self.max_complexity
+                ),
+                QUERY_TOO_COMPLEX,
+                complexity,
+                self.max_complexity,
+            )]);
+        }
+        self.complexity.store(complexity, Ordering::Relaxed);
+        Ok(result)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let complexity = QueryComplexity {
+            value: self.complexity.clone(),
+            limit: self.max_complexity,
+        };
+        QUERY_COMPLEXITY
+            .scope(complexity, next.run(ctx, operation_name))
+            .await
+    }
+}
+
+fn limit_exceeded(message: String, code: &str, value: usize, limit: usize) -> ServerError {
+    let mut extensions = ErrorExtensionValues::default();
+    extensions.set("code", code);
+    extensions.set("value", value);
+    extensions.set("limit", limit);
+
+    let mut error = ServerError::new(message, None);
+    error.extensions = Some(extensions);
+    error
+}
+
+/// Fails if a connection is asked for more items than `max_page_size`.
+pub fn check_page_size(
+    ctx: &Context<'_>,
+    first: Option<i32>,
+    last: Option<i32>,
+) -> async_graphql::Result<()> {
+    let limit = ctx.data_unchecked::<Config>().graphql.max_page_size;
+    match first.into_iter().chain(last).max() {
+        Some(size) if size as i64 > limit as i64 => Err(async_graphql::Error::new(format!(
+            "Requested page size of {} exceeds the maximum of {}",
+            size, limit
+        ))
+        .extend_with(|_, e| {
+            e.set("code", PAGE_SIZE_TOO_LARGE);
+            e.set("value", size);
+            e.set("limit", limit);
+        })),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if a dry-run is asked to execute more transactions than `max_dry_run_transactions`.
+pub fn check_dry_run_size(ctx: &Context<'_>, count: usize) -> async_graphql::Result<()> {
+    let limit = ctx
+        .data_unchecked::<Config>()
+        .graphql
+        .max_dry_run_transactions;
+    if count > limit {
+        return Err(async_graphql::Error::new(format!(
+            "Dry-run of {} transactions exceeds the maximum of {}",
+            count, limit
+        ))
+        .extend_with(|_, e| {
+            e.set("code", TOO_MANY_TRANSACTIONS);
+            e.set("value", count);
+            e.set("limit", limit);
+        }));
+    }
+    Ok(())
+}
+
+/// Fails if a query at a past height has to revert more than `max_history_depth` blocks, and
+/// otherwise adds the reverted blocks to the complexity of the request.
+pub fn check_history_depth(ctx: &Context<'_>, depth: u32) -> async_graphql::Result<()> {
+    let limit = ctx.data_unchecked::<Config>().graphql.max_history_depth;
+    if depth > limit {
+        return Err(async_graphql::Error::new(format!(
+            "Querying the state {} blocks below the latest one exceeds the maximum of {}",
+            depth, limit
+        ))
+        .extend_with(|_, e| {
+            e.set("code", HISTORY_TOO_DEEP);
+            e.set("value", depth);
+            e.set("limit", limit);
+        }));
+    }
+    add_complexity(depth as usize)
+}
+
+/// Adds the cost of the items of an unpaginated list of `len` items, besides the first one
+/// counted during validation, to the complexity of the request. Fails if it then exceeds
+/// `max_complexity`, before the items are resolved.
+pub fn check_list_complexity(ctx: &Context<'_>, len: usize) -> async_graphql::Result<()> {
+    let item = ctx
+        .field()
+        .selection_set()
+        .map(|field| selection_field_complexity(&field))
+        .fold(0, usize::saturating_add);
+    add_complexity(item.saturating_mul(len.saturating_sub(1)))
+}
+
+/// Adds `cost` to the complexity of the query being executed and fails if it then exceeds
+/// `max_complexity`.
+fn add_complexity(cost: usize) -> async_graphql::Result<()> {
+    let (value, limit) = match QUERY_COMPLEXITY.try_with(|complexity| {
+        let value = complexity
+            .value
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| {
+                Some(value.saturating_add(cost))
+            })
+            .unwrap_or_default()
+            .saturating_add(cost);
+        (value, complexity.limit)
+    }) {
+        Ok(complexity) => complexity,
+        Err(_) => return Ok(()),
+    };
+    if value > limit {
+        return Err(async_graphql::Error::new(format!(
+            "Query complexity of {} exceeds the maximum of {}",
+            value, limit
+        ))
+        .extend_with(|_, e| {
+            e.set("code", QUERY_TOO_COMPLEX);
+            e.set("value", value);
+            e.set("limit", limit);
+        }));
+    }
+    Ok(())
+}
+
+fn only_subscriptions(document: &ExecutableDocument) -> bool {
+    document
+        .operations
+        .iter()
+        .all(|(_, operation)| operation.node.ty == OperationType::Subscription)
+}
+
+/// The complexity of the most complex operation of the document.
+fn document_complexity(document: &ExecutableDocument, variables: &Variables) -> usize {
+    let complexity =
+        |selection_set: &SelectionSet| selection_set_complexity(selection_set, document, variables);
+    match &document.operations {
+        DocumentOperations::Single(operation) => complexity(&operation.node.selection_set.node),
+        DocumentOperations::Multiple(operations) => operations
+            .values()
+            .map(|operation| complexity(&operation.node.selection_set.node))
+            .max()
+            .unwrap_or_default(),
+    }
+}
+
+fn selection_set_complexity(
+    selection_set: &SelectionSet,
+    document: &ExecutableDocument,
+    variables: &Variables,
+) -> usize {
+    selection_set
+        .items
+        .iter()
+        .map(|selection| match &selection.node {
+            Selection::Field(field) => {
+                let children =
+                    selection_set_complexity(&field.node.selection_set.node, document, variables);
+                let arguments = ["first", "last"]
+                    .iter()
+                    .filter_map(|name| field.node.get_argument(name))
+                    .filter_map(|value| {
+                        value
+                            .node
+                            .clone()
+                            .into_const_with(|name| variables.get(&name).cloned().ok_or(()))
+                            .ok()
+                    });
+                page_size(arguments)
+                    .saturating_mul(children)
+                    .saturating_add(1)
+            }
+            // validation rejects cyclic and unknown fragments
+            Selection::FragmentSpread(spread) => document
+                .fragments
+                .get(&spread.node.fragment_name.node)
+                .map_or(0, |fragment| {
+                    selection_set_complexity(&fragment.node.selection_set.node, document, variables)
+                }),
+            Selection::InlineFragment(fragment) => {
+                selection_set_complexity(&fragment.node.selection_set.node, document, variables)
+            }
+        })
+        .fold(0, usize::saturating_add)
+}
+
+/// The complexity of a field being resolved, computed as during validation.
+fn selection_field_complexity(field: &SelectionField<'_>) -> usize {
+    let children = field
+        .selection_set()
+        .map(|field| selection_field_complexity(&field))
+        .fold(0, usize::saturating_add);
+    let arguments = field
+        .arguments()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(name, _)| name.as_str() == "first" || name.as_str() == "last")
+        .map(|(_, value)| value);
+    page_size(arguments)
+        .saturating_mul(children)
+        .saturating_add(1)
+}
+
+/// The number of items requested from a field by the values of its `first` and `last`
+/// arguments, one for any other field.
+fn page_size(arguments: impl Iterator<Item = Value>) -> usize {
+    arguments
+        .filter_map(|value| match value {
+            Value::Number(n) => n.as_u64(),
+            _ => None,
+        })
+        .max()
+        .map_or(1, |n| n.max(1) as usize)
+}
@@ -0,0 +1,25 @@
+//! Restrictions of the API of read-only replicas, which serve it from the database of a primary
+//! node without writing to it.
+
+use crate::config::{Config, DbMode};
+use async_graphql::{Context, ErrorExtensions, Guard};
+
+/// Error code of the operations requested from a read-only replica which only a primary serves.
+pub const READ_ONLY_REPLICA: &str = "READ_ONLY_REPLICA";
+
+/// Restricts a field to primary nodes. Replicas forward the mutations to their primary before
+/// they reach the field, if one is configured.
+pub struct PrimaryGuard;
+
+#[async_trait::async_trait]
+impl Guard for PrimaryGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        match ctx.data_unchecked::<Config>().database_mode {
+            DbMode::Primary => Ok(()),
+            DbMode::ReadOnlyReplica => Err(async_graphql::Error::new(
+                "This node is a read-only replica, the operation is only served by the primary",
+            )
+            .extend_with(|_, e| e.set("code", READ_ONLY_REPLICA))),
+        }
+    }
+}
@@ -2,28 +2,40 @@ use crate::config::Config;
 use crate::database::{transaction::OwnedTransactionIndexCursor, Database, KvStoreError};
 use crate::executor::Executor;
 use crate::model::{BlockHeight, FuelBlockDb};
+use crate::schema::limits::{check_dry_run_size, check_page_size};
+use crate::schema::replica::PrimaryGuard;
 use crate::schema::scalars::{Address, Bytes32, HexString, SortedTxCursor, TransactionId};
 use crate::state::IterDirection;
 use async_graphql::{
     connection::{query, Connection, Edge, EmptyFields},
-    Context, Object,
+    Context, Object, Subscription,
 };
+use dry_run::{ContractChanges, DryRunFee, DryRunTransactionResult, DryRunTransactionStatus};
+use fuel_block_importer::Service as BlockImporterService;
 use fuel_core_interfaces::common::{
     fuel_storage::Storage,
     fuel_tx::{Bytes32 as FuelBytes32, Receipt as FuelReceipt, Transaction as FuelTx},
     fuel_types,
     fuel_vm::prelude::Deserializable,
 };
-use fuel_core_interfaces::txpool::TxPoolMpsc;
+use fuel_core_interfaces::{
+    block_importer::ImportBlockBroadcast,
+    txpool::{Sender as TxPoolSender, TxPoolMpsc, TxStatus, TxStatusBroadcast},
+};
 use fuel_txpool::Service as TxPoolService;
+use futures::{Stream, StreamExt};
 use itertools::Itertools;
 use std::borrow::Cow;
 use std::iter;
 use std::ops::Deref;
 use std::sync::Arc;
-use tokio::sync::{oneshot, Mutex};
-use types::Transaction;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    oneshot,
+};
+use types::{Transaction, TransactionStatus};
 
+pub mod dry_run;
 pub mod input;
 pub mod output;
 pub mod receipt;
@@ -52,8 +64,13 @@ impl TxQuery {
         if let Ok(Some(transaction)) = receiver.await {
             Ok(Some(Transaction((transaction.tx().deref()).clone())))
         } else {
-            Ok(Storage::<fuel_types::Bytes32, FuelTx>::get(db, &id)?
-                .map(|tx| Transaction(tx.into_owned())))
+            match Storage::<fuel_types::Bytes32, FuelTx>::get(db, &id)? {
+                Some(tx) => Ok(Some(Transaction(tx.into_owned()))),
+                None => {
+                    db.ensure_not_pruned(&id)?;
+                    Ok(None)
+                }
+            }
         }
     }
 
@@ -66,6 +83,7 @@ impl TxQuery {
         before: Option<String>,
     ) -> async_graphql::Result<Connection<SortedTxCursor, Transaction, EmptyFields, EmptyFields>>
     {
+        check_page_size(ctx, first, last)?;
         let db = ctx.data_unchecked::<Database>();
 
         query(
@@ -141,7 +159,7 @@ impl TxQuery {
                     .map(|(tx_id, block_height)| -> Result<(Cow<FuelTx>, &BlockHeight), KvStoreError> {
                         let tx = Storage::<fuel_types::Bytes32, FuelTx>::get(db, tx_id)
                             .transpose()
-                            .ok_or(KvStoreError::NotFound)?;
+                            .ok_or_else(|| db.missing_history(*block_height))?;
 
                         Ok((tx?, block_height))
                     })
@@ -176,6 +194,7 @@ impl TxQuery {
         last: Option<i32>,
         before: Option<String>,
     ) -> async_graphql::Result<Connection<HexString, Transaction, EmptyFields, EmptyFields>> {
+        check_page_size(ctx, first, last)?;
         let db = ctx.data_unchecked::<Database>();
         let owner = fuel_types::Address::from(owner);
 
@@ -229,7 +248,7 @@ impl TxQuery {
                     .map(|res| {
                         res.and_then(|(cursor, tx_id)| {
                             let tx = Storage::<fuel_types::Bytes32, FuelTx>::get(db, &tx_id)?
-                                .ok_or(KvStoreError::NotFound)?
+                                .ok_or_else(|| db.missing_history(cursor.block_height))?
                                 .into_owned();
                             Ok((cursor, tx))
                         })
@@ -254,83 +273,352 @@ impl TxQuery {
 }
 
 #[derive(Default)]
-pub struct TxMutation {
-    block_production_lock: Mutex<()>,
-}
+pub struct TxMutation;
 
 #[Object]
 impl TxMutation {
-    /// Execute a dry-run of the transaction using a fork of current state, no changes are committed.
+    /// Execute a dry-run of the transactions in order using a single fork of current state,
+    /// so every transaction sees the changes made by the previous ones. No changes are committed.
+    #[graphql(guard = "PrimaryGuard")]
     async fn dry_run(
         &self,
         ctx: &Context<'_>,
-        tx: HexString,
+        txs: Vec<HexString>,
         // If set to false, disable input utxo validation, overriding the configuration of the node.
         // This allows for non-existent inputs to be used without signature validation
         // for read-only calls.
         utxo_validation: Option<bool>,
-    ) -> async_graphql::Result<Vec<receipt::Receipt>> {
-        let transaction = ctx.data_unchecked::<Database>().transaction();
-        let mut cfg = ctx.data_unchecked::<Config>().clone();
-        // override utxo_validation if set
-        if let Some(utxo_validation) = utxo_validation {
-            cfg.utxo_validation = utxo_validation;
-        }
+    ) -> async_graphql::Result<Vec<DryRunTransactionResult>> {
+        dry_run_txs(ctx, txs, utxo_validation).await
+    }
+
+    /// Submits transaction to the txpool, the block producer executes it in one of its next
+    /// blocks. If `await_commit` is set, the transaction is returned once its block is
+    /// committed, or an error with the reason if it is squeezed out of the txpool instead, e.g.
+    /// because the block producer can't execute it.
+    #[graphql(guard = "PrimaryGuard")]
+    async fn submit(
+        &self,
+        ctx: &Context<'_>,
+        tx: HexString,
+        await_commit: Option<bool>,
+    ) -> async_graphql::Result<Transaction> {
+        let txpool = ctx.data_unchecked::<Arc<TxPoolService>>();
         let mut tx = FuelTx::from_bytes(&tx.0)?;
         tx.precompute_metadata();
         let id = tx.id();
 
-        // make executor from transaction database view.
-        let executor = Executor {
-            database: transaction.deref().clone(),
-            config: cfg.clone(),
-        };
-        executor.submit_txs(vec![Arc::new(tx)]).await?;
-        // get receipts from db transaction
-        let receipts = Storage::<FuelBytes32, Vec<FuelReceipt>>::get(transaction.deref(), &id)?
-            .unwrap_or_default();
-        Ok(receipts.iter().map(Into::into).collect())
+        // subscribe before inserting, so the commit of the transaction can't be missed
+        let status_changes = await_commit.unwrap_or(false).then(|| {
+            status_changes(
+                ctx.data_unchecked::<Database>().clone(),
+                id,
+                txpool.sender().clone(),
+                txpool.subscribe_ch(),
+                ctx.data_unchecked::<Arc<BlockImporterService>>()
+                    .subscribe(),
+            )
+        });
+
+        let ret = txpool.sender().insert(vec![Arc::new(tx.clone())]).await?;
+        ret.get(0).unwrap().as_ref()?;
+
+        if let Some(status_changes) = status_changes {
+            futures::pin_mut!(status_changes);
+            while let Some(status) = status_changes.next().await {
+                match status? {
+                    TransactionStatus::SqueezedOut(status) => {
+                        return Err(async_graphql::Error::new(format!(
+                            "Transaction {:#x} was squeezed out: {}",
+                            id, status.reason
+                        )))
+                    }
+                    status if status.is_final() => break,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Transaction(tx))
     }
+}
 
-    /// Submits transaction to the txpool
-    async fn submit(&self, ctx: &Context<'_>, tx: HexString) -> async_graphql::Result<Transaction> {
-        let db = ctx.data_unchecked::<Database>();
-        let txpool = ctx.data_unchecked::<Arc<TxPoolService>>();
-        let cfg = ctx.data_unchecked::<Config>().clone();
-        let mut tx = FuelTx::from_bytes(&tx.0)?;
-        tx.precompute_metadata();
+#[derive(Default)]
+pub struct TxStatusSubscription;
 
-        // only allow one block to be produced at a time
-        let _block_production_guard = self.block_production_lock.lock().await;
+#[Subscription]
+impl TxStatusSubscription {
+    /// Emits every status transition of the transaction. The stream ends once the transaction
+    /// reaches a final state, i.e. it was either executed in a block or squeezed out of the txpool.
+    async fn status_change(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "The ID of the transaction")] id: TransactionId,
+    ) -> impl Stream<Item = async_graphql::Result<TransactionStatus>> {
+        let db = ctx.data_unchecked::<Database>().clone();
+        // subscribe before reading the current status, so no transition is lost in between
+        let txpool = ctx.data_unchecked::<Arc<TxPoolService>>();
+        let tx_updates = txpool.subscribe_ch();
+        let block_updates = ctx
+            .data_unchecked::<Arc<BlockImporterService>>()
+            .subscribe();
+        status_changes(db, id.0, txpool.sender().clone(), tx_updates, block_updates)
+    }
+}
 
-        let includable = if cfg.utxo_validation {
-            // include transaction
-            let ret = txpool.sender().insert(vec![Arc::new(tx.clone())]).await?;
-            ret.get(0).unwrap().as_ref()?;
+/// Emits the current status of the transaction and its following transitions until it reaches
+/// a final state. The updates must be subscribed to before the current status is read.
+fn status_changes(
+    db: Database,
+    id: FuelBytes32,
+    txpool: TxPoolSender,
+    mut tx_updates: broadcast::Receiver<TxStatusBroadcast>,
+    mut block_updates: broadcast::Receiver<ImportBlockBroadcast>,
+) -> impl Stream<Item = async_graphql::Result<TransactionStatus>> {
+    async_stream::stream! {
+        match db.get_tx_status(&id) {
+            Ok(Some(status)) => {
+                let status = TransactionStatus::from(status);
+                let is_final = status.is_final();
+                yield Ok(status);
+                if is_final {
+                    return;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                yield Err(e.into());
+                return;
+            }
+        }
 
-            // get includable transactions
-            let txs = txpool.sender().includable().await?;
+        loop {
+            let status = tokio::select! {
+                event = tx_updates.recv() => match event {
+                    Ok(TxStatusBroadcast { tx, status }) if tx.id() == id => match status {
+                        // reported with the submission time of the txpool, like the polled status
+                        TxStatus::Submitted => match txpool.find_one(id).await {
+                            Ok(Some(tx_info)) => {
+                                Some(Ok(TransactionStatus::submitted(tx_info.submited_time())))
+                            }
+                            // already removed from the txpool, its next status follows
+                            Ok(None) => None,
+                            Err(e) => Some(Err(e.into())),
+                        },
+                        // the block producer removes the transactions of its blocks from the
+                        // txpool once they are committed
+                        TxStatus::SqueezedOut { reason } => final_status(&db, &id).or_else(|| {
+                            Some(Ok(TransactionStatus::squeezed_out(reason.to_string())))
+                        }),
+                        // the execution result is reported once the block is committed
+                        TxStatus::Executed => None,
+                    },
+                    Err(RecvError::Closed) => break,
+                    _ => None,
+                },
+                event = block_updates.recv() => match event {
+                    Ok(ImportBlockBroadcast::SealedFuelBlockImported { block, .. })
+                        if block.block.transactions.iter().any(|tx| tx.id() == id) =>
+                    {
+                        final_status(&db, &id)
+                    }
+                    // the block containing the transaction may have been skipped
+                    Err(RecvError::Lagged(_)) => final_status(&db, &id),
+                    Err(RecvError::Closed) => break,
+                    _ => None,
+                },
+            };
+
+            if let Some(status) = status {
+                let is_final = status.as_ref().map_or(true, TransactionStatus::is_final);
+                yield status;
+                if is_final {
+                    break;
+                }
+            }
+        }
+    }
+}
 
-            txpool
-                .sender()
-                .remove(txs.iter().map(|tx| tx.id()).collect())
-                .await?;
-            txs
-        } else {
-            vec![Arc::new(tx.clone())]
-        };
+/// Executes the transactions one after the other on a fork of the database which is dropped
+/// afterwards. A transaction rejected by the executor fails the whole dry-run.
+async fn dry_run_txs(
+    ctx: &Context<'_>,
+    txs: Vec<HexString>,
+    utxo_validation: Option<bool>,
+) -> async_graphql::Result<Vec<DryRunTransactionResult>> {
+    check_dry_run_size(ctx, txs.len())?;
+    let fork = ctx.data_unchecked::<Database>().transaction();
+    let mut cfg = ctx.data_unchecked::<Config>().clone();
+    // override utxo_validation if set
+    if let Some(utxo_validation) = utxo_validation {
+        cfg.utxo_validation = utxo_validation;
+    }
 
-        // next part can be extracted to saparate endpoint that will trigger block building
-        // just inlude call to txpool.includable
+    let mut results = Vec::with_capacity(txs.len());
+    for (index, tx) in txs.into_iter().enumerate() {
+        let mut tx = FuelTx::from_bytes(&tx.0)?;
+        tx.precompute_metadata();
+        let id = tx.id();
 
+        // execute every transaction in a nested db transaction, to isolate the changes it made
+        let transaction = fork.transaction();
         let executor = Executor {
-            database: db.clone(),
+            database: transaction.deref().clone(),
             config: cfg.clone(),
         };
-        executor.submit_txs(includable).await?;
+        let block = executor.submit_txs(vec![Arc::new(tx)]).await.map_err(|e| {
+            async_graphql::Error::new(format!(
+                "Transaction {} ({:#x}) was rejected: {}",
+                index, id, e
+            ))
+        })?;
+        // each transaction is executed in its own block, which contains its malleated version
+        let tx = block
+            .transactions
+            .into_iter()
+            .next()
+            .ok_or(KvStoreError::NotFound)?;
+
+        // get status and receipts from db transaction
+        let status = transaction
+            .get_tx_status(&id)?
+            .and_then(DryRunTransactionStatus::from_executed)
+            .ok_or(KvStoreError::NotFound)?;
+        let receipts = Storage::<FuelBytes32, Vec<FuelReceipt>>::get(transaction.deref(), &id)?
+            .unwrap_or_default()
+            .into_owned();
+        let fee = DryRunFee {
+            byte_fee: executor.byte_fee_paid(&tx),
+            total: executor.total_fee_paid(&tx, &receipts)?,
+        };
+        let (coins_created, coins_spent) = DryRunTransactionResult::coins(&transaction, &tx)?;
+        let contract_changes = ContractChanges::collect(&fork, &transaction)?;
+
+        // make the changes visible to the next transactions
+        transaction.commit()?;
+
+        results.push(DryRunTransactionResult {
+            tx,
+            status,
+            receipts,
+            fee,
+            coins_created,
+            coins_spent,
+            contract_changes,
+        });
+    }
+    Ok(results)
+}
+
+/// Returns the status of the transaction stored in the database, if it is a final one.
+fn final_status(
+    db: &Database,
+    id: &FuelBytes32,
+) -> Option<async_graphql::Result<TransactionStatus>> {
+    match db.get_tx_status(id) {
+        Ok(Some(status)) => Some(TransactionStatus::from(status))
+            .filter(TransactionStatus::is_final)
+            .map(Ok),
+        Ok(None) => None,
+        Err(e) => Some(Err(e.into())),
+    }
+}
 
-        // probably need to fetch executed tx that is now in db.
-        let tx = Transaction(tx);
-        Ok(tx)
+#[cfg(test)]
+mod tests {
+    use crate::config::{chain_config::ProductionStrategy, Config};
+    use crate::test_utils::{start_schema, TestDatabase};
+    use async_graphql::{value, Request};
+    use fuel_core_interfaces::common::{
+        fuel_crypto::SecretKey,
+        fuel_tx::{Address, AssetId, TransactionBuilder},
+        fuel_types::bytes::SerializableVec,
+        fuel_vm::{consts::REG_ONE, prelude::Opcode},
+    };
+    use futures::{poll, StreamExt};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[tokio::test]
+    async fn status_change_reports_the_execution_of_pooled_transactions() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let secret = SecretKey::random(&mut rng);
+        let owner = Address::from(*secret.public_key().hash());
+        let mut db = TestDatabase::default();
+        let (utxo_id, coin) = db.make_coin(owner, 1000, AssetId::default());
+        let mut tx = TransactionBuilder::script(Opcode::RET(REG_ONE).to_bytes().to_vec(), vec![])
+            .gas_limit(1_000_000)
+            .add_unsigned_coin_input(utxo_id, &secret, coin.amount, coin.asset_id, 0)
+            .finalize();
+
+        // the txpool checks the coin of the transaction against the database
+        let mut config = Config::local_node();
+        config.utxo_validation = true;
+        let (schema, _modules) = start_schema(config, db.as_ref().clone()).await;
+        let statuses = schema.execute_stream(Request::new(format!(
+            r#"subscription {{ statusChange(id: "{:#x}") {{ __typename }} }}"#,
+            tx.id()
+        )));
+        futures::pin_mut!(statuses);
+        // the first poll subscribes to the status updates
+        assert!(poll!(statuses.next()).is_pending());
+
+        let submitted = schema
+            .execute(format!(
+                r#"mutation {{ submit(tx: "0x{}") {{ id }} }}"#,
+                hex::encode(tx.to_bytes())
+            ))
+            .await;
+        assert!(submitted.errors.is_empty(), "{:?}", submitted.errors);
+
+        // the transaction is removed from the txpool once it's committed, which isn't reported
+        // as squeezed out
+        let statuses: Vec<_> = statuses.map(|status| status.data).collect().await;
+        assert_eq!(
+            statuses.last(),
+            Some(&value!({ "statusChange": { "__typename": "SuccessStatus" } }))
+        );
+    }
+
+    #[tokio::test]
+    async fn status_change_reports_the_submission_time_of_the_txpool() {
+        let mut config = Config::local_node();
+        // the transaction stays in the txpool
+        config.chain_conf.block_production = ProductionStrategy::Manual;
+        let (schema, _modules) =
+            start_schema(config, TestDatabase::default().as_ref().clone()).await;
+        let mut tx = TransactionBuilder::script(Opcode::RET(REG_ONE).to_bytes().to_vec(), vec![])
+            .gas_limit(1_000_000)
+            .finalize();
+        let statuses = schema.execute_stream(Request::new(format!(
+            r#"subscription {{ statusChange(id: "{:#x}") {{ ... on SubmittedStatus {{ time }} }} }}"#,
+            tx.id()
+        )));
+        futures::pin_mut!(statuses);
+        assert!(poll!(statuses.next()).is_pending());
+
+        let submitted = schema
+            .execute(format!(
+                r#"mutation {{ submit(tx: "0x{}") {{ id }} }}"#,
+                hex::encode(tx.to_bytes())
+            ))
+            .await;
+        assert!(submitted.errors.is_empty(), "{:?}", submitted.errors);
+        // a status stamped once it is pushed would be later than the submission
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        let pushed = statuses.next().await.unwrap().data;
+
+        let polled = schema
+            .execute(format!(
+                r#"{{ transaction(id: "{:#x}") {{ status {{ ... on SubmittedStatus {{ time }} }} }} }}"#,
+                tx.id()
+            ))
+            .await;
+        assert!(polled.errors.is_empty(), "{:?}", polled.errors);
+        let polled = polled.data.into_json().unwrap();
+        assert_eq!(
+            pushed.into_json().unwrap()["statusChange"]["time"],
+            polled["transaction"]["status"]["time"]
+        );
     }
 }
@@ -0,0 +1,340 @@
+use super::{
+    receipt::Receipt,
+    types::{ProgramState, Transaction},
+};
+use crate::{
+    database::{
+        columns::{BALANCES, CONTRACTS_STATE},
+        transactional::DatabaseTransaction,
+        Database, KvStoreError,
+    },
+    model::Coin as CoinModel,
+    schema::{
+        coin::Coin,
+        contract::Contract,
+        scalars::{AssetId, Bytes32, TransactionId, U64},
+    },
+    state::Error,
+    tx_pool::TransactionStatus as TxStatus,
+};
+use async_graphql::{Object, Union};
+use fuel_core_interfaces::common::{
+    fuel_storage::Storage,
+    fuel_tx::{self, Input, UtxoId},
+    fuel_types::{AssetId as FuelAssetId, Bytes32 as FuelBytes32, ContractId},
+    fuel_vm::prelude::{MerkleStorage, ProgramState as VmProgramState, Word},
+};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+type StoredCoins = Vec<(UtxoId, CoinModel)>;
+
+/// Outcome of a single transaction of a dry-run.
+pub struct DryRunTransactionResult {
+    pub(crate) tx: fuel_tx::Transaction,
+    pub(crate) status: DryRunTransactionStatus,
+    pub(crate) receipts: Vec<fuel_tx::Receipt>,
+    pub(crate) fee: DryRunFee,
+    pub(crate) coins_created: StoredCoins,
+    pub(crate) coins_spent: StoredCoins,
+    pub(crate) contract_changes: Vec<ContractChanges>,
+}
+
+#[Object]
+impl DryRunTransactionResult {
+    async fn id(&self) -> TransactionId {
+        TransactionId(self.tx.id())
+    }
+
+    /// The transaction as it was executed, i.e. with its malleable fields set by the executor.
+    async fn transaction(&self) -> Transaction {
+        Transaction(self.tx.clone())
+    }
+
+    async fn status(&self) -> &DryRunTransactionStatus {
+        &self.status
+    }
+
+    async fn receipts(&self) -> Vec<Receipt> {
+        self.receipts.iter().map(Into::into).collect()
+    }
+
+    /// The fees the transaction would pay if it was included in a block.
+    async fn fee(&self) -> &DryRunFee {
+        &self.fee
+    }
+
+    /// Coins created by the outputs of the transaction.
+    async fn coins_created(&self) -> Vec<Coin> {
+        self.coins_created
+            .iter()
+            .map(|(id, coin)| Coin(*id, coin.clone()))
+            .collect()
+    }
+
+    /// Coins spent by the inputs of the transaction.
+    async fn coins_spent(&self) -> Vec<Coin> {
+        self.coins_spent
+            .iter()
+            .map(|(id, coin)| Coin(*id, coin.clone()))
+            .collect()
+    }
+
+    /// Storage slots and balances of contracts modified by the transaction.
+    async fn contract_changes(&self) -> &Vec<ContractChanges> {
+        &self.contract_changes
+    }
+}
+
+impl DryRunTransactionResult {
+    /// Collects the coins touched by the transaction, as they are stored in `db` after its execution.
+    pub(crate) fn coins(
+        db: &Database,
+        tx: &fuel_tx::Transaction,
+    ) -> Result<(StoredCoins, StoredCoins), KvStoreError> {
+        let created = (0..tx.outputs().len()).map(|index| UtxoId::new(tx.id(), index as u8));
+        let spent = tx.inputs().iter().filter_map(|input| match input {
+            Input::CoinSigned { utxo_id, .. } | Input::CoinPredicate { utxo_id, .. } => {
+                Some(*utxo_id)
+            }
+            Input::Contract { .. } => None,
+        });
+
+        Ok((stored_coins(db, created)?, stored_coins(db, spent)?))
+    }
+}
+
+fn stored_coins(
+    db: &Database,
+    ids: impl Iterator<Item = UtxoId>,
+) -> Result<StoredCoins, KvStoreError> {
+    ids.filter_map(|id| {
+        Storage::<UtxoId, CoinModel>::get(db, &id)
+            .map(|coin| coin.map(|coin| (id, coin.into_owned())))
+            .transpose()
+    })
+    .collect()
+}
+
+pub struct DryRunFee {
+    pub(crate) byte_fee: Word,
+    pub(crate) total: Word,
+}
+
+#[Object]
+impl DryRunFee {
+    /// Fee paid for the size of the transaction.
+    async fn byte_fee(&self) -> U64 {
+        self.byte_fee.into()
+    }
+
+    /// Fee paid for the gas used by the execution of the transaction.
+    async fn gas_fee(&self) -> U64 {
+        (self.total - self.byte_fee).into()
+    }
+
+    async fn total(&self) -> U64 {
+        self.total.into()
+    }
+}
+
+/// Changes made to the state of a single contract.
+pub struct ContractChanges {
+    contract_id: ContractId,
+    storage: Vec<StorageSlotChange>,
+    balances: Vec<BalanceChange>,
+}
+
+#[Object]
+impl ContractChanges {
+    async fn contract(&self) -> Contract {
+        Contract(self.contract_id)
+    }
+
+    async fn storage(&self) -> &Vec<StorageSlotChange> {
+        &self.storage
+    }
+
+    async fn balances(&self) -> &Vec<BalanceChange> {
+        &self.balances
+    }
+}
+
+impl ContractChanges {
+    fn new(contract_id: ContractId) -> Self {
+        Self {
+            contract_id,
+            storage: vec![],
+            balances: vec![],
+        }
+    }
+
+    /// Compares the contract storage slots and balances modified by `changes` against their
+    /// values in `base`, grouped by contract.
+    pub(crate) fn collect(
+        base: &Database,
+        changes: &DatabaseTransaction,
+    ) -> Result<Vec<ContractChanges>, Error> {
+        let mut contracts = BTreeMap::<ContractId, ContractChanges>::new();
+
+        for key in changes.changed_keys(CONTRACTS_STATE) {
+            let (contract_id, key) = split_key::<FuelBytes32>(&key)?;
+            let get = |db: &Database| {
+                MerkleStorage::<ContractId, FuelBytes32, FuelBytes32>::get(db, &contract_id, &key)
+                    .map(|value| value.map(|value| *value))
+            };
+            let (before, after) = (get(base)?, get(changes)?);
+            if before != after {
+                contracts
+                    .entry(contract_id)
+                    .or_insert_with(|| ContractChanges::new(contract_id))
+                    .storage
+                    .push(StorageSlotChange { key, before, after });
+            }
+        }
+
+        for key in changes.changed_keys(BALANCES) {
+            let (contract_id, asset_id) = split_key::<FuelAssetId>(&key)?;
+            let get = |db: &Database| {
+                MerkleStorage::<ContractId, FuelAssetId, Word>::get(db, &contract_id, &asset_id)
+                    .map(|value| value.map(|value| *value).unwrap_or_default())
+            };
+            let (before, after) = (get(base)?, get(changes)?);
+            if before != after {
+                contracts
+                    .entry(contract_id)
+                    .or_insert_with(|| ContractChanges::new(contract_id))
+                    .balances
+                    .push(BalanceChange {
+                        asset_id,
+                        before,
+                        after,
+                    });
+            }
+        }
+
+        Ok(contracts
+            .into_values()
+            .map(|mut changes| {
+                changes.storage.sort_by_key(|slot| slot.key);
+                changes.balances.sort_by_key(|balance| balance.asset_id);
+                changes
+            })
+            .collect())
+    }
+}
+
+/// Splits a key of a contract owned column into the contract id and the key within the contract.
+fn split_key<K>(key: &[u8]) -> Result<(ContractId, K), Error>
+where
+    K: for<'a> TryFrom<&'a [u8]>,
+{
+    let invalid_key = || Error::Codec;
+    let (contract_id, key) = (
+        key.get(..32).ok_or_else(invalid_key)?,
+        key.get(32..).ok_or_else(invalid_key)?,
+    );
+    Ok((
+        ContractId::try_from(contract_id).map_err(|_| invalid_key())?,
+        K::try_from(key).map_err(|_| invalid_key())?,
+    ))
+}
+
+pub struct StorageSlotChange {
+    key: FuelBytes32,
+    before: Option<FuelBytes32>,
+    after: Option<FuelBytes32>,
+}
+
+#[Object]
+impl StorageSlotChange {
+    async fn key(&self) -> Bytes32 {
+        self.key.into()
+    }
+
+    /// The value of the slot before the transaction, null if it wasn't set.
+    async fn before(&self) -> Option<Bytes32> {
+        self.before.map(Into::into)
+    }
+
+    /// The value of the slot after the transaction, null if it was removed.
+    async fn after(&self) -> Option<Bytes32> {
+        self.after.map(Into::into)
+    }
+}
+
+pub struct BalanceChange {
+    asset_id: FuelAssetId,
+    before: Word,
+    after: Word,
+}
+
+#[Object]
+impl BalanceChange {
+    async fn asset_id(&self) -> AssetId {
+        self.asset_id.into()
+    }
+
+    async fn before(&self) -> U64 {
+        self.before.into()
+    }
+
+    async fn after(&self) -> U64 {
+        self.after.into()
+    }
+}
+
+/// Execution status of a dry-run transaction. Unlike `TransactionStatus`, it doesn't reference
+/// a block since nothing was committed.
+#[derive(Union)]
+pub enum DryRunTransactionStatus {
+    Success(DryRunSuccessStatus),
+    Failed(DryRunFailureStatus),
+}
+
+impl DryRunTransactionStatus {
+    /// Converts the status recorded by the executor, `None` if the transaction wasn't executed.
+    pub(crate) fn from_executed(status: TxStatus) -> Option<Self> {
+        match status {
+            TxStatus::Submitted { .. } => None,
+            TxStatus::Success { result, .. } => {
+                Some(DryRunTransactionStatus::Success(DryRunSuccessStatus {
+                    result,
+                }))
+            }
+            TxStatus::Failed { reason, result, .. } => {
+                Some(DryRunTransactionStatus::Failed(DryRunFailureStatus {
+                    reason,
+                    state: result,
+                }))
+            }
+        }
+    }
+}
+
+pub struct DryRunSuccessStatus {
+    result: VmProgramState,
+}
+
+#[Object]
+impl DryRunSuccessStatus {
+    async fn program_state(&self) -> ProgramState {
+        self.result.into()
+    }
+}
+
+pub struct DryRunFailureStatus {
+    reason: String,
+    state: Option<VmProgramState>,
+}
+
+#[Object]
+impl DryRunFailureStatus {
+    async fn reason(&self) -> String {
+        self.reason.clone()
+    }
+
+    async fn program_state(&self) -> Option<ProgramState> {
+        self.state.map(Into::into)
+    }
+}
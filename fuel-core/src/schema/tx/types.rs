@@ -74,6 +74,22 @@ pub enum TransactionStatus {
     Submitted(SubmittedStatus),
     Success(SuccessStatus),
     Failed(FailureStatus),
+    SqueezedOut(SqueezedOutStatus),
+}
+
+impl TransactionStatus {
+    pub(crate) fn submitted(time: DateTime<Utc>) -> Self {
+        TransactionStatus::Submitted(SubmittedStatus(time))
+    }
+
+    pub(crate) fn squeezed_out(reason: String) -> Self {
+        TransactionStatus::SqueezedOut(SqueezedOutStatus { reason })
+    }
+
+    /// Whether the transaction can't transition to any other status after this one.
+    pub(crate) fn is_final(&self) -> bool {
+        !matches!(self, TransactionStatus::Submitted(_))
+    }
 }
 
 pub struct SubmittedStatus(DateTime<Utc>);
@@ -142,6 +158,17 @@ impl FailureStatus {
     }
 }
 
+pub struct SqueezedOutStatus {
+    pub(crate) reason: String,
+}
+
+#[Object]
+impl SqueezedOutStatus {
+    async fn reason(&self) -> String {
+        self.reason.clone()
+    }
+}
+
 impl From<TxStatus> for TransactionStatus {
     fn from(s: TxStatus) -> Self {
         match s {
@@ -241,15 +268,21 @@ impl Transaction {
             let time = transaction_in_pool.submited_time();
             Ok(Some(TransactionStatus::Submitted(SubmittedStatus(time))))
         } else {
-            let status = db.get_tx_status(&self.0.id())?;
+            let status = db.get_tx_status(&id)?;
+            if status.is_none() {
+                db.ensure_not_pruned(&id)?;
+            }
             Ok(status.map(Into::into))
         }
     }
 
     async fn receipts(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<Vec<Receipt>>> {
         let db = ctx.data_unchecked::<Database>();
-        let receipts =
-            Storage::<fuel_types::Bytes32, Vec<fuel_tx::Receipt>>::get(db, &self.0.id())?;
+        let id = self.0.id();
+        let receipts = Storage::<fuel_types::Bytes32, Vec<fuel_tx::Receipt>>::get(db, &id)?;
+        if receipts.is_none() {
+            db.ensure_not_pruned(&id)?;
+        }
         Ok(receipts.map(|receipts| receipts.iter().cloned().map(Receipt).collect()))
     }
 
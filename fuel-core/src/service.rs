@@ -1,18 +1,23 @@
 use crate::{
-    config::{Config, DbType},
-    database::Database,
+    config::{Config, DbMode, DbType},
+    database::{migration::AppliedMigration, Database, VERSION},
+    state::Error as DatabaseError,
 };
 use anyhow::Error as AnyError;
 use modules::Modules;
-use std::{net::SocketAddr, panic};
+use std::{net::SocketAddr, panic, path::Path};
 use thiserror::Error;
 use tokio::task::JoinHandle;
 use tracing::log::warn;
 
+pub(crate) mod block_production;
 pub(crate) mod genesis;
 pub mod graph_api;
 pub mod metrics;
 pub mod modules;
+pub(crate) mod pruning;
+pub(crate) mod rate_limit;
+pub(crate) mod replica;
 
 pub struct FuelService {
     tasks: Vec<JoinHandle<Result<(), AnyError>>>,
@@ -27,15 +32,69 @@ impl FuelService {
     #[tracing::instrument(skip(config))]
     pub async fn new_node(config: Config) -> Result<Self, AnyError> {
         // initialize database
-        let database = match config.database_type {
+        let database = Self::open_database(&config)?;
+        // initialize service
+        Self::init_service(database, config).await
+    }
+
+    /// Opens the database configured for the node and upgrades it to the current layout.
+    /// Databases written by a newer version of the node are refused, and so are outdated ones
+    /// opened by a replica, as only the primary can upgrade them.
+    pub fn open_database(config: &Config) -> Result<Database, AnyError> {
+        let database = Self::open_database_unchecked(config)?;
+        match config.database_mode {
+            DbMode::Primary => {
+                database.migrate(false)?;
+            }
+            DbMode::ReadOnlyReplica => {
+                if database.version()? != Some(VERSION) {
+                    return Err(DatabaseError::InvalidDatabaseVersion.into());
+                }
+            }
+        }
+        Ok(database)
+    }
+
+    /// Returns the migrations which would upgrade the database configured for the node, without
+    /// applying them.
+    pub fn pending_migrations(config: &Config) -> Result<Vec<AppliedMigration>, AnyError> {
+        Ok(Self::open_database_unchecked(config)?.migrate(true)?)
+    }
+
+    fn open_database_unchecked(config: &Config) -> Result<Database, AnyError> {
+        Ok(match config.database_type {
             #[cfg(feature = "rocksdb")]
-            DbType::RocksDb => Database::open(&config.database_path)?,
+            DbType::RocksDb => match config.database_mode {
+                DbMode::Primary => Database::open(&config.database_path)?,
+                DbMode::ReadOnlyReplica => Database::open_replica(&config.database_path)?,
+            },
             DbType::InMemory => Database::in_memory(),
             #[cfg(not(feature = "rocksdb"))]
             _ => Database::in_memory(),
-        };
-        // initialize service
-        Self::init_service(database, config).await
+        })
+    }
+
+    /// Replaces the database configured for the node by a copy of the checkpoint at `checkpoint`,
+    /// which must belong to the configured chain. The node must be stopped.
+    pub fn restore_database(config: &Config, checkpoint: &Path) -> Result<(), AnyError> {
+        match config.database_type {
+            #[cfg(feature = "rocksdb")]
+            DbType::RocksDb => {
+                let replaced = Database::restore_checkpoint(
+                    checkpoint,
+                    &config.database_path,
+                    &config.chain_conf.chain_name,
+                )?;
+                if let Some(replaced) = replaced {
+                    warn!("The replaced database was moved to {}", replaced.display());
+                }
+                Ok(())
+            }
+            _ => Err(anyhow::anyhow!(
+                "Only RocksDB databases can be restored from {}",
+                checkpoint.display()
+            )),
+        }
     }
 
     #[cfg(any(test, feature = "test-helpers"))]
@@ -52,13 +111,33 @@ impl FuelService {
         }
 
         // initialize state
-        Self::import_state(&config.chain_conf, &database)?;
+        match config.database_mode {
+            DbMode::Primary => Self::import_state(&config, &database)?,
+            DbMode::ReadOnlyReplica => Self::check_replica(&config, &database)?,
+        }
 
         // start modules
         let modules = modules::start_modules(&config, &database).await?;
 
         // start background tasks
         let mut tasks = vec![];
+        match config.database_mode {
+            DbMode::Primary => {
+                if let Some(pruning) = config.pruning.clone() {
+                    tasks.push(pruning::start(
+                        database.clone(),
+                        pruning,
+                        modules.block_commits.clone(),
+                    ));
+                }
+            }
+            DbMode::ReadOnlyReplica => tasks.push(replica::start(
+                database.clone(),
+                modules.block_importer.clone(),
+                modules.block_commits.clone(),
+                config.replica.catch_up_interval,
+            )),
+        }
         let (bound_address, api_server) =
             graph_api::start_server(config.clone(), database, &modules).await?;
         tasks.push(api_server);
@@ -72,6 +151,23 @@ impl FuelService {
         })
     }
 
+    /// Checks that a replica follows a database its primary initialized for the configured
+    /// chain and upgraded to the current version.
+    fn check_replica(config: &Config, database: &Database) -> Result<(), AnyError> {
+        if database.version()? != Some(VERSION) {
+            return Err(DatabaseError::InvalidDatabaseVersion.into());
+        }
+        match database.get_chain_name()? {
+            Some(name) if name == config.chain_conf.chain_name => Ok(()),
+            Some(found) => Err(DatabaseError::WrongChain {
+                expected: config.chain_conf.chain_name.clone(),
+                found,
+            }
+            .into()),
+            None => Err(DatabaseError::ChainUninitialized.into()),
+        }
+    }
+
     /// Awaits for the completion of any server background tasks
     pub async fn run(self) {
         for task in self.tasks {
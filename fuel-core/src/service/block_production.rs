@@ -0,0 +1,189 @@
+use crate::{config::Config, database::Database, executor::Executor, model::FuelBlockConsensus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fuel_block_importer::Service as BlockImporterService;
+use fuel_core_interfaces::{
+    block_producer::{BlockExecutor, ExecutedBlock},
+    model::{ArcTx, SealedFuelBlock},
+};
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Serializes the writes of blocks to the database with the readers which need the chain to
+/// stay at the same height, like queries of past states.
+#[derive(Clone, Debug, Default)]
+pub struct BlockCommits(Arc<RwLock<()>>);
+
+impl BlockCommits {
+    /// Waits for the blocks being written and prevents new ones from being written while held.
+    pub async fn read(&self) -> RwLockReadGuard<'_, ()> {
+        self.0.read().await
+    }
+
+    /// Waits for the readers and the other writers, to commit, revert or prune blocks while
+    /// held.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, ()> {
+        self.0.write().await
+    }
+}
+
+/// Commits the blocks of the block producer to the database and announces them.
+pub(crate) struct BlockCommitter {
+    pub database: Database,
+    pub config: Config,
+    pub block_importer: Arc<BlockImporterService>,
+    pub block_commits: BlockCommits,
+}
+
+#[async_trait]
+impl BlockExecutor for BlockCommitter {
+    async fn execute_block(
+        &self,
+        txs: Vec<ArcTx>,
+        time: DateTime<Utc>,
+    ) -> anyhow::Result<ExecutedBlock> {
+        let _commits = self.block_commits.write().await;
+        let executor = Executor {
+            database: self.database.clone(),
+            config: self.config.clone(),
+        };
+        // there is no consensus yet, so blocks are sealed with empty consensus data
+        let consensus = FuelBlockConsensus::default();
+        let (block, skipped) = executor.produce_block(txs, time, &consensus).await?;
+        let sealed_block = Arc::new(SealedFuelBlock { block, consensus });
+        self.block_importer
+            .notify_block_imported(sealed_block.clone(), true);
+        Ok(ExecutedBlock {
+            block: sealed_block,
+            rejected: skipped
+                .into_iter()
+                .map(|(id, e)| (id, e.to_string()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::{Coin, CoinStatus},
+        tx_pool::TransactionStatus,
+    };
+    use fuel_core_interfaces::{
+        block_importer::ImportBlockBroadcast,
+        common::{
+            fuel_asm::Opcode,
+            fuel_crypto::SecretKey,
+            fuel_storage::Storage,
+            fuel_tx::{Bytes32, Input, Output, Transaction, TransactionBuilder, UtxoId},
+            fuel_vm::consts::REG_ONE,
+        },
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// A transaction spending a coin maturing at `maturity`, which is only created if there is one
+    fn transfer(rng: &mut StdRng, db: &mut Database, maturity: Option<u32>) -> ArcTx {
+        let tx =
+            TransactionBuilder::script(vec![Opcode::RET(REG_ONE)].into_iter().collect(), vec![])
+                .add_unsigned_coin_input(
+                    rng.gen(),
+                    &SecretKey::random(rng),
+                    100,
+                    Default::default(),
+                    0,
+                )
+                .add_output(Output::Coin {
+                    to: rng.gen(),
+                    amount: 100,
+                    asset_id: Default::default(),
+                })
+                .finalize();
+        if let (Input::CoinSigned { utxo_id, owner, .. }, Some(maturity)) =
+            (&tx.inputs()[0], maturity)
+        {
+            let coin = Coin {
+                owner: *owner,
+                amount: 100,
+                asset_id: Default::default(),
+                maturity: maturity.into(),
+                status: CoinStatus::Unspent,
+                block_created: Default::default(),
+            };
+            Storage::<UtxoId, Coin>::insert(db, utxo_id, &coin).unwrap();
+        }
+        Arc::new(tx)
+    }
+
+    #[tokio::test]
+    async fn transactions_which_fail_are_left_out_of_the_block() {
+        let mut rng = StdRng::seed_from_u64(2322);
+        let mut database = Database::default();
+        let mut config = Config::local_node();
+        config.utxo_validation = true;
+        let committer = BlockCommitter {
+            database: database.clone(),
+            config,
+            block_importer: Arc::new(
+                BlockImporterService::new(&Default::default(), ())
+                    .await
+                    .unwrap(),
+            ),
+            block_commits: Default::default(),
+        };
+        let mut blocks = committer.block_importer.subscribe();
+        let missing_coin = transfer(&mut rng, &mut database, None);
+        let valid = transfer(&mut rng, &mut database, Some(0));
+        // executable in the block after the next one only
+        let immature = transfer(&mut rng, &mut database, Some(2));
+
+        let ExecutedBlock { block, rejected } = committer
+            .execute_block(
+                vec![missing_coin.clone(), valid.clone(), immature.clone()],
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.transactions[0].id(), valid.id());
+        // the transactions left out are rejected with the reason
+        assert_eq!(
+            rejected,
+            vec![
+                (
+                    missing_coin.id(),
+                    "Invalid transaction: The specified coin doesn't exist".to_string()
+                ),
+                (
+                    immature.id(),
+                    "Invalid transaction: Coin has not yet reached maturity".to_string()
+                ),
+            ]
+        );
+        for left_out in [&missing_coin, &immature] {
+            assert!(
+                !Storage::<Bytes32, Transaction>::contains_key(&database, &left_out.id()).unwrap()
+            );
+            assert!(database.get_tx_status(&left_out.id()).unwrap().is_none());
+        }
+        assert!(matches!(
+            database.get_tx_status(&valid.id()).unwrap(),
+            Some(TransactionStatus::Success { block_id, .. } | TransactionStatus::Failed { block_id, .. })
+                if block_id == block.id()
+        ));
+        // the block is sealed and announced
+        assert_eq!(database.get_block_height().unwrap(), Some(1u32.into()));
+        assert!(database
+            .get_sealed_block_by_id(&block.id())
+            .unwrap()
+            .is_some());
+        assert!(matches!(
+            blocks.try_recv(),
+            Ok(ImportBlockBroadcast::SealedFuelBlockImported {
+                is_created_by_self: true,
+                ..
+            })
+        ));
+    }
+}
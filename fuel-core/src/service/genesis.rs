@@ -1,167 +1,410 @@
 use crate::{
-    config::chain_config::{ChainConfig, ContractConfig, StateConfig},
-    database::Database,
+    config::{
+        chain_config::{CoinConfig, ContractConfig, StateConfig},
+        state_file::{read_state_file, StateEntry},
+        Config,
+    },
+    database::{transactional::DatabaseTransaction, Database},
+    model::BlockHeight,
     service::FuelService,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use fuel_core_interfaces::{
     common::{
+        fuel_merkle::{
+            binary::MerkleTree,
+            common::{StorageError, StorageMap},
+        },
         fuel_storage::{MerkleStorage, Storage},
         fuel_tx::{Contract, UtxoId},
         fuel_types::{bytes::WORD_SIZE, AssetId, Bytes32, ContractId, Salt, Word},
     },
-    model::{Coin, CoinStatus},
+    model::{Coin, CoinStatus, FuelBlockConsensus, FuelBlockDb, FuelBlockHeader},
 };
+use fuel_merkle_storage::Storage as MerkleTreeStorage;
 use itertools::Itertools;
+use std::borrow::Cow;
+use tracing::info;
 
-impl FuelService {
-    /// Loads state from the chain config into database
-    pub(crate) fn import_state(config: &ChainConfig, database: &Database) -> Result<()> {
-        // start a db transaction for bulk-writing
-        let mut import_tx = database.transaction();
-        let database = import_tx.as_mut();
+/// Number of state entries written to the database in a single batch while importing.
+const IMPORT_BATCH_SIZE: usize = 10_000;
 
+impl FuelService {
+    /// Loads the initial state into the database, from the state file if there is one, from the
+    /// file the chain config was loaded from, or from the chain config itself otherwise.
+    ///
+    /// The state is streamed and committed in batches, so it doesn't have to fit in memory. The
+    /// chain name is written last: an interrupted import isn't considered done and starts over
+    /// on the next launch.
+    pub(crate) fn import_state(config: &Config, database: &Database) -> Result<()> {
         // check if chain is initialized
-        if database.get_chain_name()?.is_none() {
-            // initialize the chain id
-            database.init_chain_name(config.chain_name.clone())?;
-
-            if let Some(initial_state) = &config.initial_state {
-                Self::init_block_height(database, initial_state)?;
-                Self::init_coin_state(database, initial_state)?;
-                Self::init_contracts(database, initial_state)?;
+        if database.get_chain_name()?.is_some() {
+            return Ok(());
+        }
+
+        let (mut coins_storage, mut contracts_storage) = (DiscardedNodes, DiscardedNodes);
+        let mut importer = StateImporter::new(
+            database,
+            MerkleTree::new(&mut coins_storage),
+            MerkleTree::new(&mut contracts_storage),
+        );
+        let state_file = config
+            .genesis_state
+            .as_ref()
+            .or(config.chain_conf.initial_state_file.as_ref());
+        match state_file {
+            Some(path) => {
+                info!("Importing the genesis state from {:?}", path);
+                read_state_file(path, |entry| importer.import(entry))?;
+            }
+            None => {
+                for entry in config
+                    .chain_conf
+                    .initial_state
+                    .iter()
+                    .flat_map(StateConfig::entries)
+                {
+                    importer.import(entry)?;
+                }
             }
         }
+        importer.finish(config.chain_conf.chain_name.clone())
+    }
 
-        // Write transaction to db
-        import_tx.commit()?;
+    /// Stores the genesis block at the starting height.
+    ///
+    /// The genesis block has no transactions and no previous blocks, so its `prev_root`
+    /// commits to the initial state instead: it is the binary merkle root over the coins root
+    /// and the contracts root. The header doesn't depend on the time of the import, so every
+    /// node started from the same chain config agrees on the genesis block id.
+    fn init_genesis_block(
+        db: &mut Database,
+        height: BlockHeight,
+        coins_root: Bytes32,
+        contracts_root: Bytes32,
+    ) -> Result<()> {
+        let mut storage = StorageMap::new();
+        let mut state_merkle = MerkleTree::new(&mut storage);
+        state_merkle
+            .push(coins_root.as_ref())
+            .expect("In-memory impl should be infallible");
+        state_merkle
+            .push(contracts_root.as_ref())
+            .expect("In-memory impl should be infallible");
+
+        let mut headers = FuelBlockHeader {
+            height,
+            prev_root: state_merkle
+                .root()
+                .expect("In-memory impl should be infallible")
+                .into(),
+            transactions_root: empty_merkle_root(),
+            ..Default::default()
+        };
+        headers.recalculate_metadata();
 
+        let block = FuelBlockDb {
+            headers,
+            transactions: vec![],
+        };
+        let _ = Storage::<Bytes32, FuelBlockDb>::insert(db, &block.id(), &block)?;
+        // the genesis block is trusted by configuration, so it doesn't carry any signatures
+        let _ = Storage::<Bytes32, FuelBlockConsensus>::insert(
+            db,
+            &block.id(),
+            &FuelBlockConsensus::default(),
+        )?;
         Ok(())
     }
+}
 
-    /// initialize starting block height if set
-    fn init_block_height(db: &Database, state: &StateConfig) -> Result<()> {
-        if let Some(height) = state.height {
-            db.init_chain_height(height)?;
-        }
-        Ok(())
-    }
+/// Writes the entries of the initial state to the database in batches, while building the
+/// merkle roots over the coins and the contracts.
+struct StateImporter<'a> {
+    database: &'a Database,
+    batch: DatabaseTransaction,
+    batch_entries: usize,
+    imported_entries: usize,
+    height: Option<BlockHeight>,
+    // TODO: Store merkle sum tree root over coins with unspecified utxo ids.
+    coins_merkle: MerkleTree<'a, StorageError>,
+    contracts_merkle: MerkleTree<'a, StorageError>,
+    generated_output_index: u64,
+    contracts_count: u64,
+    /// The contract the following storage slots and balances belong to. It is added to the
+    /// contracts root once all of them are imported.
+    current_contract: Option<ImportedContract>,
+}
 
-    /// initialize coins
-    fn init_coin_state(db: &mut Database, state: &StateConfig) -> Result<()> {
-        // TODO: Store merkle sum tree root over coins with unspecified utxo ids.
-        let mut generated_output_index: u64 = 0;
-        if let Some(coins) = &state.coins {
-            for coin in coins {
-                let utxo_id = UtxoId::new(
-                    // generated transaction id([0..[out_index/255]])
-                    coin.tx_id.unwrap_or_else(|| {
-                        Bytes32::try_from(
-                            (0..(Bytes32::LEN - WORD_SIZE))
-                                .map(|_| 0u8)
-                                .chain((generated_output_index / 255).to_be_bytes().into_iter())
-                                .collect_vec()
-                                .as_slice(),
-                        )
-                        .expect("Incorrect genesis transaction id byte length")
-                    }),
-                    coin.output_index.map(|i| i as u8).unwrap_or_else(|| {
-                        generated_output_index += 1;
-                        (generated_output_index % 255) as u8
-                    }),
-                );
-
-                let coin = Coin {
-                    owner: coin.owner,
-                    amount: coin.amount,
-                    asset_id: coin.asset_id,
-                    maturity: coin.maturity.unwrap_or_default(),
-                    status: CoinStatus::Unspent,
-                    block_created: coin.block_created.unwrap_or_default(),
-                };
-
-                let _ = Storage::<UtxoId, Coin>::insert(db, &utxo_id, &coin)?;
-            }
+struct ImportedContract {
+    id: ContractId,
+    code_root: Bytes32,
+    salt: Salt,
+}
+
+impl<'a> StateImporter<'a> {
+    fn new(
+        database: &'a Database,
+        coins_merkle: MerkleTree<'a, StorageError>,
+        contracts_merkle: MerkleTree<'a, StorageError>,
+    ) -> Self {
+        Self {
+            database,
+            batch: database.transaction(),
+            batch_entries: 0,
+            imported_entries: 0,
+            height: None,
+            coins_merkle,
+            contracts_merkle,
+            generated_output_index: 0,
+            contracts_count: 0,
+            current_contract: None,
         }
-        Ok(())
     }
 
-    fn init_contracts(db: &mut Database, state: &StateConfig) -> Result<()> {
-        // initialize contract state
-        if let Some(contracts) = &state.contracts {
-            for (generated_output_index, contract_config) in contracts.iter().enumerate() {
-                let contract = Contract::from(contract_config.code.as_slice());
-                let salt = contract_config.salt;
-                let root = contract.root();
-                let contract_id = contract.id(&salt, &root, &Contract::default_state_root());
-                // insert contract code
-                let _ = Storage::<ContractId, Contract>::insert(db, &contract_id, &contract)?;
-                // insert contract root
-                let _ = Storage::<ContractId, (Salt, Bytes32)>::insert(
-                    db,
+    fn import(&mut self, entry: StateEntry) -> Result<()> {
+        match entry {
+            StateEntry::Height(height) => self.height = Some(height),
+            StateEntry::Coin(coin) => self.import_coin(coin)?,
+            StateEntry::Contract(contract) => {
+                self.finish_contract()?;
+                self.import_contract(contract)?;
+            }
+            StateEntry::ContractState(key, value) => {
+                let contract_id = self.current_contract_id()?;
+                MerkleStorage::<ContractId, Bytes32, Bytes32>::insert(
+                    &mut *self.batch,
                     &contract_id,
-                    &(salt, root),
+                    &key,
+                    &value,
                 )?;
-                let _ = Storage::<ContractId, UtxoId>::insert(
-                    db,
+            }
+            StateEntry::ContractBalance(asset_id, amount) => {
+                let contract_id = self.current_contract_id()?;
+                MerkleStorage::<ContractId, AssetId, Word>::insert(
+                    &mut *self.batch,
                     &contract_id,
-                    &UtxoId::new(
-                        // generated transaction id([0..[out_index/255]])
-                        Bytes32::try_from(
-                            (0..(Bytes32::LEN - WORD_SIZE))
-                                .map(|_| 0u8)
-                                .chain(
-                                    (generated_output_index as u64 / 255)
-                                        .to_be_bytes()
-                                        .into_iter(),
-                                )
-                                .collect_vec()
-                                .as_slice(),
-                        )
-                        .expect("Incorrect genesis transaction id byte length"),
-                        generated_output_index as u8,
-                    ),
+                    &asset_id,
+                    &amount,
                 )?;
-                Self::init_contract_state(db, &contract_id, contract_config)?;
-                Self::init_contract_balance(db, &contract_id, contract_config)?;
             }
         }
+
+        self.batch_entries += 1;
+        if self.batch_entries >= IMPORT_BATCH_SIZE {
+            self.commit_batch()?;
+        }
         Ok(())
     }
 
-    fn init_contract_state(
-        db: &mut Database,
-        contract_id: &ContractId,
-        contract: &ContractConfig,
-    ) -> Result<()> {
+    fn commit_batch(&mut self) -> Result<()> {
+        let batch = std::mem::replace(&mut self.batch, self.database.transaction());
+        batch.commit()?;
+        self.imported_entries += self.batch_entries;
+        self.batch_entries = 0;
+        info!("Imported {} genesis state entries", self.imported_entries);
+        Ok(())
+    }
+
+    fn import_coin(&mut self, coin: CoinConfig) -> Result<()> {
+        let utxo_id = UtxoId::new(
+            coin.tx_id
+                .unwrap_or_else(|| generated_tx_id(self.generated_output_index)),
+            coin.output_index.map(|i| i as u8).unwrap_or_else(|| {
+                self.generated_output_index += 1;
+                (self.generated_output_index % 255) as u8
+            }),
+        );
+
+        let coin = Coin {
+            owner: coin.owner,
+            amount: coin.amount,
+            asset_id: coin.asset_id,
+            maturity: coin.maturity.unwrap_or_default(),
+            status: CoinStatus::Unspent,
+            block_created: coin.block_created.unwrap_or_default(),
+        };
+
+        let _ = Storage::<UtxoId, Coin>::insert(&mut *self.batch, &utxo_id, &coin)?;
+        self.coins_merkle
+            .push(
+                &[
+                    utxo_id.tx_id().as_ref(),
+                    &[utxo_id.output_index()],
+                    coin.owner.as_ref(),
+                    &coin.amount.to_be_bytes(),
+                    coin.asset_id.as_ref(),
+                    &coin.maturity.to_bytes(),
+                    &coin.block_created.to_bytes(),
+                ]
+                .concat(),
+            )
+            .expect("In-memory impl should be infallible");
+        Ok(())
+    }
+
+    fn import_contract(&mut self, contract_config: ContractConfig) -> Result<()> {
+        let generated_output_index = self.contracts_count;
+        self.contracts_count += 1;
+
+        let contract = Contract::from(contract_config.code.as_slice());
+        let salt = contract_config.salt;
+        let root = contract.root();
+        let contract_id = contract_config
+            .contract_id
+            .unwrap_or_else(|| contract.id(&salt, &root, &Contract::default_state_root()));
+        let db = &mut *self.batch;
+        // insert contract code
+        let _ = Storage::<ContractId, Contract>::insert(db, &contract_id, &contract)?;
+        // insert contract root
+        let _ = Storage::<ContractId, (Salt, Bytes32)>::insert(db, &contract_id, &(salt, root))?;
+        let _ = Storage::<ContractId, UtxoId>::insert(
+            db,
+            &contract_id,
+            &UtxoId::new(
+                contract_config
+                    .tx_id
+                    .unwrap_or_else(|| generated_tx_id(generated_output_index)),
+                contract_config
+                    .output_index
+                    .map(|i| i as u8)
+                    .unwrap_or(generated_output_index as u8),
+            ),
+        )?;
         // insert state related to contract
-        if let Some(contract_state) = &contract.state {
-            for (key, value) in contract_state {
-                MerkleStorage::<ContractId, Bytes32, Bytes32>::insert(db, contract_id, key, value)?;
-            }
+        for (key, value) in contract_config.state.iter().flatten() {
+            MerkleStorage::<ContractId, Bytes32, Bytes32>::insert(db, &contract_id, key, value)?;
         }
+        // insert balances related to contract
+        for (asset_id, amount) in contract_config.balances.iter().flatten() {
+            MerkleStorage::<ContractId, AssetId, Word>::insert(db, &contract_id, asset_id, amount)?;
+        }
+
+        self.current_contract = Some(ImportedContract {
+            id: contract_id,
+            code_root: root,
+            salt,
+        });
         Ok(())
     }
 
-    fn init_contract_balance(
-        db: &mut Database,
-        contract_id: &ContractId,
-        contract: &ContractConfig,
-    ) -> Result<()> {
-        // insert balances related to contract
-        if let Some(balances) = &contract.balances {
-            for (key, value) in balances {
-                MerkleStorage::<ContractId, AssetId, Word>::insert(db, contract_id, key, value)?;
-            }
+    fn current_contract_id(&self) -> Result<ContractId> {
+        self.current_contract
+            .as_ref()
+            .map(|contract| contract.id)
+            .ok_or_else(|| anyhow!("Contract storage found before any contract"))
+    }
+
+    /// Adds the current contract to the contracts root, now that its storage is complete.
+    fn finish_contract(&mut self) -> Result<()> {
+        if let Some(contract) = self.current_contract.take() {
+            let db = &mut *self.batch;
+            let state_root = MerkleStorage::<ContractId, Bytes32, Bytes32>::root(db, &contract.id)?;
+            let balances_root = MerkleStorage::<ContractId, AssetId, Word>::root(db, &contract.id)?;
+            self.contracts_merkle
+                .push(
+                    &[
+                        contract.id.as_ref(),
+                        contract.code_root.as_ref(),
+                        contract.salt.as_ref(),
+                        state_root.as_ref(),
+                        balances_root.as_ref(),
+                    ]
+                    .concat(),
+                )
+                .expect("In-memory impl should be infallible");
+        }
+        Ok(())
+    }
+
+    /// Stores the genesis block and the chain name along with the last entries.
+    fn finish(mut self, chain_name: String) -> Result<()> {
+        self.finish_contract()?;
+        let coins_root = self
+            .coins_merkle
+            .root()
+            .expect("In-memory impl should be infallible")
+            .into();
+        let contracts_root = self
+            .contracts_merkle
+            .root()
+            .expect("In-memory impl should be infallible")
+            .into();
+
+        let mut batch = self.batch;
+        // initialize starting block height if set
+        if let Some(height) = self.height {
+            batch.init_chain_height(height)?;
         }
+        FuelService::init_genesis_block(
+            &mut batch,
+            self.height.unwrap_or_default(),
+            coins_root,
+            contracts_root,
+        )?;
+        // initialize the chain id
+        batch.init_chain_name(chain_name)?;
+        batch.commit()?;
+
+        info!(
+            "Imported {} genesis state entries",
+            self.imported_entries + self.batch_entries
+        );
         Ok(())
     }
 }
 
+/// Generated transaction id of the outputs without one: `[0..[out_index/255]]`
+fn generated_tx_id(output_index: u64) -> Bytes32 {
+    Bytes32::try_from(
+        (0..(Bytes32::LEN - WORD_SIZE))
+            .map(|_| 0u8)
+            .chain((output_index / 255).to_be_bytes().into_iter())
+            .collect_vec()
+            .as_slice(),
+    )
+    .expect("Incorrect genesis transaction id byte length")
+}
+
+/// Storage of the merkle trees over the initial state, which doesn't keep their nodes. Pushing
+/// leaves and computing the root only use the O(log n) subtrees held by the tree itself, so
+/// the roots of states of any size are computed in bounded memory.
+struct DiscardedNodes;
+
+impl<K, V: Clone> MerkleTreeStorage<K, V> for DiscardedNodes {
+    type Error = StorageError;
+
+    fn insert(&mut self, _: &K, _: &V) -> Result<Option<V>, StorageError> {
+        Ok(None)
+    }
+
+    fn remove(&mut self, _: &K) -> Result<Option<V>, StorageError> {
+        Ok(None)
+    }
+
+    fn get<'a>(&'a self, _: &K) -> Result<Option<Cow<'a, V>>, StorageError> {
+        Ok(None)
+    }
+
+    fn contains_key(&self, _: &K) -> Result<bool, StorageError> {
+        Ok(false)
+    }
+}
+
+/// The root of a binary merkle tree without any leaves
+fn empty_merkle_root() -> Bytes32 {
+    let mut storage = StorageMap::new();
+    MerkleTree::new(&mut storage)
+        .root()
+        .expect("In-memory impl should be infallible")
+        .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::chain_config::{CoinConfig, ContractConfig, StateConfig};
+    use crate::config::chain_config::ChainConfig;
+    use crate::config::state_file::BinaryStateWriter;
     use crate::config::Config;
     use crate::model::BlockHeight;
     use fuel_core_interfaces::common::{
@@ -170,6 +413,7 @@ mod tests {
     };
     use itertools::Itertools;
     use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+    use std::str::FromStr;
 
     #[tokio::test]
     async fn config_initializes_chain_name() {
@@ -222,6 +466,76 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn config_initializes_genesis_block_at_starting_height() {
+        let test_height = BlockHeight::from(99u32);
+        let service_config = Config {
+            chain_conf: ChainConfig {
+                initial_state: Some(StateConfig {
+                    height: Some(test_height),
+                    ..Default::default()
+                }),
+                ..ChainConfig::local_testnet()
+            },
+            ..Config::local_node()
+        };
+
+        let db = Database::default();
+        FuelService::from_database(db.clone(), service_config)
+            .await
+            .unwrap();
+
+        let genesis = get_block(&db, test_height);
+        assert_eq!(genesis.headers.height, test_height);
+        assert_eq!(genesis.headers.parent_hash, Bytes32::zeroed());
+        assert!(genesis.transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn genesis_block_commits_to_initial_state() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let asset_id: AssetId = rng.gen();
+        let contract_config = ContractConfig {
+            code: Opcode::RET(0x10).to_bytes().to_vec(),
+            salt: rng.gen(),
+            state: None,
+            balances: Some(vec![(asset_id, 100)]),
+            contract_id: None,
+            tx_id: None,
+            output_index: None,
+        };
+        let other_contract_config = ContractConfig {
+            balances: Some(vec![(asset_id, 101)]),
+            ..contract_config.clone()
+        };
+
+        let genesis_for = |contract: ContractConfig| async move {
+            let service_config = Config {
+                chain_conf: ChainConfig {
+                    initial_state: Some(StateConfig {
+                        contracts: Some(vec![contract]),
+                        ..Default::default()
+                    }),
+                    ..ChainConfig::local_testnet()
+                },
+                ..Config::local_node()
+            };
+            let db = Database::default();
+            FuelService::from_database(db.clone(), service_config)
+                .await
+                .unwrap();
+            get_block(&db, BlockHeight::from(0u32))
+        };
+
+        let genesis = genesis_for(contract_config.clone()).await;
+        let same_genesis = genesis_for(contract_config).await;
+        let other_genesis = genesis_for(other_contract_config).await;
+
+        assert_eq!(genesis.id(), same_genesis.id());
+        assert_ne!(genesis.headers.prev_root, other_genesis.headers.prev_root);
+        assert_ne!(genesis.id(), other_genesis.id());
+    }
+
     #[tokio::test]
     async fn config_state_initializes_multiple_coins_with_different_owners_and_asset_ids() {
         let mut rng = StdRng::seed_from_u64(10);
@@ -337,6 +651,9 @@ mod tests {
                         salt,
                         state: Some(state),
                         balances: None,
+                        contract_id: None,
+                        tx_id: None,
+                        output_index: None,
                     }]),
                     ..Default::default()
                 }),
@@ -378,6 +695,9 @@ mod tests {
                         salt,
                         state: None,
                         balances: Some(balances),
+                        contract_id: None,
+                        tx_id: None,
+                        output_index: None,
                     }]),
                     ..Default::default()
                 }),
@@ -399,6 +719,182 @@ mod tests {
         assert_eq!(test_balance, ret)
     }
 
+    #[tokio::test]
+    async fn state_file_is_imported_like_the_chain_config() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let coin = |rng: &mut StdRng| CoinConfig {
+            tx_id: None,
+            output_index: None,
+            block_created: None,
+            maturity: None,
+            owner: rng.gen(),
+            amount: rng.gen(),
+            asset_id: rng.gen(),
+        };
+        let contract = |rng: &mut StdRng| ContractConfig {
+            code: Opcode::RET(0x10).to_bytes().to_vec(),
+            salt: rng.gen(),
+            state: Some(vec![(rng.gen(), rng.gen()), (rng.gen(), rng.gen())]),
+            balances: Some(vec![(rng.gen(), rng.gen())]),
+            contract_id: None,
+            tx_id: None,
+            output_index: None,
+        };
+        let state = StateConfig {
+            // enough coins to generate more than one transaction id
+            coins: Some((0..300).map(|_| coin(&mut rng)).collect()),
+            contracts: Some((0..3).map(|_| contract(&mut rng)).collect()),
+            height: Some(7u32.into()),
+        };
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut writer = BinaryStateWriter::new(file.reopen().unwrap()).unwrap();
+        for entry in state.entries() {
+            writer.write(entry).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let from_file = Database::default();
+        FuelService::from_database(
+            from_file.clone(),
+            Config {
+                genesis_state: Some(file.path().to_path_buf()),
+                ..Config::local_node()
+            },
+        )
+        .await
+        .unwrap();
+        let from_config = Database::default();
+        FuelService::from_database(
+            from_config.clone(),
+            Config {
+                chain_conf: ChainConfig {
+                    initial_state: Some(state),
+                    ..ChainConfig::local_testnet()
+                },
+                ..Config::local_node()
+            },
+        )
+        .await
+        .unwrap();
+
+        let height = 7u32.into();
+        assert_eq!(
+            get_block(&from_file, height).id(),
+            get_block(&from_config, height).id()
+        );
+        assert_eq!(
+            from_file.get_state_config().unwrap(),
+            from_config.get_state_config().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn json_snapshot_is_imported_as_genesis_state() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let state = StateConfig {
+            coins: Some(vec![CoinConfig {
+                tx_id: Some(rng.gen()),
+                output_index: Some(0),
+                block_created: None,
+                maturity: None,
+                owner: rng.gen(),
+                amount: rng.gen(),
+                asset_id: rng.gen(),
+            }]),
+            contracts: Some(vec![ContractConfig {
+                code: Opcode::RET(0x10).to_bytes().to_vec(),
+                salt: rng.gen(),
+                state: Some(vec![(rng.gen(), rng.gen())]),
+                balances: Some(vec![(rng.gen(), rng.gen())]),
+                contract_id: None,
+                tx_id: Some(rng.gen()),
+                output_index: Some(1),
+            }]),
+            height: Some(3u32.into()),
+        };
+        let db = Database::default();
+        FuelService::from_database(
+            db.clone(),
+            Config {
+                chain_conf: ChainConfig {
+                    initial_state: Some(state),
+                    ..ChainConfig::local_testnet()
+                },
+                ..Config::local_node()
+            },
+        )
+        .await
+        .unwrap();
+
+        // written like `snapshot --format json` does, a chain config holding the state
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let snapshot = ChainConfig {
+            initial_state: Some(db.get_state_config().unwrap()),
+            ..ChainConfig::local_testnet()
+        };
+        serde_json::to_writer_pretty(file.reopen().unwrap(), &snapshot).unwrap();
+
+        let forked = Database::default();
+        FuelService::from_database(
+            forked.clone(),
+            Config {
+                genesis_state: Some(file.path().to_path_buf()),
+                ..Config::local_node()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            forked.get_state_config().unwrap(),
+            db.get_state_config().unwrap()
+        );
+        assert!(!forked.get_state_config().unwrap().coins.unwrap().is_empty());
+
+        // passed as `--chain`, the state is left in the file and streamed from it
+        let chain_conf = ChainConfig::from_str(file.path().to_str().unwrap()).unwrap();
+        assert_eq!(chain_conf.initial_state, None);
+        let forked = Database::default();
+        FuelService::from_database(
+            forked.clone(),
+            Config {
+                chain_conf,
+                ..Config::local_node()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            forked.get_state_config().unwrap(),
+            db.get_state_config().unwrap()
+        );
+    }
+
+    #[test]
+    fn merkle_roots_are_computed_without_storing_the_nodes() {
+        for leaves in 0..20u8 {
+            let (mut storage, mut nodes) = (StorageMap::new(), DiscardedNodes);
+            let mut stored = MerkleTree::new(&mut storage);
+            let mut discarded = MerkleTree::new(&mut nodes);
+            for leaf in 0..leaves {
+                stored.push(&[leaf]).unwrap();
+                discarded.push(&[leaf]).unwrap();
+            }
+            assert_eq!(stored.root().unwrap(), discarded.root().unwrap());
+        }
+    }
+
+    fn get_block(db: &Database, height: BlockHeight) -> FuelBlockDb {
+        let id = db
+            .get_block_id(height)
+            .unwrap()
+            .expect("Expected a block at the given height");
+        Storage::<Bytes32, FuelBlockDb>::get(db, &id)
+            .unwrap()
+            .expect("Expected the block to be stored")
+            .into_owned()
+    }
+
     fn get_coins(db: &Database, owner: Address) -> Vec<(UtxoId, Coin)> {
         db.owned_coins(owner, None, None)
             .map(|r| {
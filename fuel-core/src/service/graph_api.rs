@@ -1,32 +1,70 @@
 use super::modules::Modules;
-use crate::config::Config;
+use crate::config::{Config, DbMode};
 use crate::database::Database;
-use crate::schema::{build_schema, dap, CoreSchema};
-use crate::service::metrics::metrics;
-use anyhow::Result;
+use crate::schema::{
+    admin::BearerToken,
+    build_schema, dap,
+    limits::{QueryLimits, SubscriptionsOnly},
+    CoreSchema,
+};
+use crate::service::{
+    metrics::metrics,
+    rate_limit::{ConnectionLimiter, ConnectionPermit, RateLimiter},
+};
+use anyhow::{Context, Result};
 use async_graphql::{
-    extensions::Tracing, http::playground_source, http::GraphQLPlaygroundConfig, Request, Response,
+    extensions::Tracing,
+    http::{
+        playground_source, GraphQLPlaygroundConfig, WebSocket as GraphQLWebSocket,
+        WebSocketProtocols, WsMessage, ALL_WEBSOCKET_PROTOCOLS,
+    },
+    parser::{
+        parse_query,
+        types::{DocumentOperations, OperationType},
+    },
+    Data, Request, Response,
 };
 use axum::{
-    extract::Extension,
+    body::{Body, Bytes},
+    error_handling::HandleErrorLayer,
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Extension,
+    },
     http::{
-        header::{
-            ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
-        },
-        HeaderValue,
+        header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, SEC_WEBSOCKET_PROTOCOL},
+        HeaderMap, HeaderValue, Request as HttpRequest, StatusCode,
     },
+    middleware::{self, Next},
     response::Html,
-    response::IntoResponse,
+    response::{IntoResponse, Response as HttpResponse},
     routing::{get, post},
-    Json, Router,
+    BoxError, Json, Router,
 };
+use futures::{future, SinkExt, StreamExt};
+use hyper::{body::HttpBody, client::HttpConnector, Client, Uri};
 use serde_json::json;
 use std::net::{SocketAddr, TcpListener};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal::unix::SignalKind;
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use tower_http::{set_header::SetResponseHeaderLayer, trace::TraceLayer};
+use tokio::time::{interval_at, Instant};
+use tower::{limit::GlobalConcurrencyLimitLayer, ServiceBuilder};
+use tower_http::{
+    cors::{Any, CorsLayer, Origin},
+    trace::TraceLayer,
+};
 use tracing::info;
 
+/// How often the server pings websocket clients to keep idle subscriptions alive.
+const WEBSOCKET_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Close code sent to websocket clients when the node shuts down.
+const WEBSOCKET_GOING_AWAY: u16 = 1001;
+
 /// Spawns the api server for this node
 pub async fn start_server(
     config: Config,
@@ -35,6 +73,22 @@ pub async fn start_server(
 ) -> Result<(SocketAddr, JoinHandle<Result<()>>)> {
     let network_addr = config.addr;
     let params = config.chain_conf.transaction_parameters;
+    let limits = QueryLimits::new(&config.graphql);
+    let cors = cors_layer(config.graphql.allowed_origins.as_deref())?;
+    let max_body_size = config.graphql.max_body_size;
+    let request_timeout = config.graphql.request_timeout;
+    let max_concurrent_requests = config.graphql.max_concurrent_requests;
+    let rate_limiter = config
+        .graphql
+        .max_requests_per_second
+        .map(|limit| Arc::new(RateLimiter::new(limit)));
+    let websocket_connections = Arc::new(ConnectionLimiter::new(
+        config.graphql.max_websocket_connections_per_ip,
+    ));
+    let primary = match (config.database_mode, &config.replica.primary_url) {
+        (DbMode::ReadOnlyReplica, Some(url)) => Some(Primary::new(url)?),
+        _ => None,
+    };
     let schema = build_schema()
         .data(config)
         .data(db)
@@ -42,28 +96,49 @@ pub async fn start_server(
         .data(modules.block_importer.clone())
         .data(modules.block_producer.clone())
         .data(modules.sync.clone())
-        .data(modules.bft.clone());
-    let schema = dap::init(schema, params).extension(Tracing).finish();
+        .data(modules.bft.clone())
+        .data(modules.block_commits.clone());
+    let schema = dap::init(schema, params)
+        .extension(Tracing)
+        .extension(limits)
+        .finish();
+
+    // Notifies websocket connections about the shutdown. Upgraded connections are detached
+    // from the server, so they aren't covered by its graceful shutdown.
+    let (stop_subscriptions, subscriptions_stopped) = watch::channel(());
 
     let router = Router::new()
         .route("/playground", get(graphql_playground))
-        .route("/graphql", post(graphql_handler).options(ok))
+        .route(
+            "/graphql",
+            post(graphql_handler)
+                .options(ok)
+                .route_layer(middleware::from_fn(move |req, next| {
+                    forward_mutations(req, next, primary.clone())
+                })),
+        )
+        .route("/graphql-ws", get(graphql_subscription_handler))
         .route("/metrics", get(metrics))
         .route("/health", get(health))
         .layer(Extension(schema))
-        .layer(TraceLayer::new_for_http())
-        .layer(SetResponseHeaderLayer::<_>::overriding(
-            ACCESS_CONTROL_ALLOW_ORIGIN,
-            HeaderValue::from_static("*"),
-        ))
-        .layer(SetResponseHeaderLayer::<_>::overriding(
-            ACCESS_CONTROL_ALLOW_METHODS,
-            HeaderValue::from_static("*"),
-        ))
-        .layer(SetResponseHeaderLayer::<_>::overriding(
-            ACCESS_CONTROL_ALLOW_HEADERS,
-            HeaderValue::from_static("*"),
-        ));
+        .layer(Extension(subscriptions_stopped))
+        .layer(Extension(websocket_connections))
+        .layer(middleware::from_fn(move |req, next| {
+            limit_body_size(req, next, max_body_size)
+        }))
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_overload))
+                .load_shed()
+                // the router applies layers to every route, the limit has to be shared by all
+                .layer(GlobalConcurrencyLimitLayer::new(max_concurrent_requests))
+                .timeout(request_timeout),
+        )
+        .layer(middleware::from_fn(move |req, next| {
+            limit_rate(req, next, rate_limiter.clone())
+        }))
+        .layer(cors)
+        .layer(TraceLayer::new_for_http());
 
     let (tx, rx) = tokio::sync::oneshot::channel();
     let listener = TcpListener::bind(&network_addr)?;
@@ -73,8 +148,11 @@ pub async fn start_server(
     let handle = tokio::spawn(async move {
         let server = axum::Server::from_tcp(listener)
             .unwrap()
-            .serve(router.into_make_service())
-            .with_graceful_shutdown(shutdown_signal());
+            .serve(router.into_make_service_with_connect_info::<SocketAddr, _>())
+            .with_graceful_shutdown(async move {
+                shutdown_signal().await;
+                let _ = stop_subscriptions.send(());
+            });
 
         tx.send(()).unwrap();
         server.await.map_err(Into::into)
@@ -86,6 +164,183 @@ pub async fn start_server(
     Ok((bound_addr, handle))
 }
 
+fn cors_layer(allowed_origins: Option<&[String]>) -> Result<CorsLayer> {
+    let cors = CorsLayer::new().allow_methods(Any).allow_headers(Any);
+    Ok(match allowed_origins {
+        Some(origins) => cors.allow_origin(Origin::list(
+            origins
+                .iter()
+                .map(|origin| {
+                    HeaderValue::from_str(origin)
+                        .with_context(|| format!("Invalid allowed origin: {}", origin))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        None => cors.allow_origin(Any),
+    })
+}
+
+/// Rejects requests with a body larger than `max_body_size`. The body is buffered, so it's
+/// also enforced for requests without a `Content-Length`.
+async fn limit_body_size(
+    req: HttpRequest<Body>,
+    next: Next<Body>,
+    max_body_size: usize,
+) -> impl IntoResponse {
+    let too_large = || {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Request body exceeds the maximum of {} bytes",
+                max_body_size
+            ),
+        )
+            .into_response()
+    };
+
+    let content_length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<usize>().ok());
+    if matches!(content_length, Some(length) if length > max_body_size) {
+        return too_large();
+    }
+
+    let (parts, mut body) = req.into_parts();
+    let mut buffer = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        };
+        if buffer.len() + chunk.len() > max_body_size {
+            return too_large();
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    next.run(HttpRequest::from_parts(parts, Body::from(buffer)))
+        .await
+}
+
+/// Rejects requests of clients which exceeded their rate limit, if there is one.
+async fn limit_rate(
+    req: HttpRequest<Body>,
+    next: Next<Body>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) -> impl IntoResponse {
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    match (rate_limiter, client) {
+        (Some(rate_limiter), Some(client)) if !rate_limiter.check(client) => {
+            (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response()
+        }
+        _ => next.run(req).await,
+    }
+}
+
+/// The primary node a read-only replica forwards the mutations it receives to
+#[derive(Clone)]
+struct Primary {
+    uri: Uri,
+    client: Client<HttpConnector>,
+}
+
+impl Primary {
+    fn new(url: &str) -> Result<Self> {
+        let uri = format!("{}/graphql", url.trim_end_matches('/'))
+            .parse()
+            .with_context(|| format!("Invalid primary url: {}", url))?;
+        Ok(Self {
+            uri,
+            client: Client::new(),
+        })
+    }
+
+    async fn forward(&self, headers: &HeaderMap, body: Bytes) -> HttpResponse {
+        let mut request =
+            HttpRequest::post(self.uri.clone()).header(CONTENT_TYPE, "application/json");
+        // the primary checks the admin token itself
+        if let Some(authorization) = headers.get(AUTHORIZATION) {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let response = async {
+            let request = request.body(Body::from(body))?;
+            Ok::<_, anyhow::Error>(self.client.request(request).await?)
+        }
+        .await;
+        match response {
+            Ok(response) => response.into_response(),
+            Err(e) => (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to forward the mutation to the primary: {}", e),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Forwards the mutations received by a read-only replica to its primary, if it has one. The
+/// queries are served by the replica itself.
+async fn forward_mutations(
+    req: HttpRequest<Body>,
+    next: Next<Body>,
+    primary: Option<Primary>,
+) -> HttpResponse {
+    let primary = match primary {
+        Some(primary) => primary,
+        None => return next.run(req).await,
+    };
+    let (parts, body) = req.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    if is_mutation(&body) {
+        primary.forward(&parts.headers, body).await
+    } else {
+        next.run(HttpRequest::from_parts(parts, Body::from(body)))
+            .await
+    }
+}
+
+/// Whether the GraphQL request in `body` runs a mutation. Invalid requests are served locally,
+/// which reports their errors.
+fn is_mutation(body: &[u8]) -> bool {
+    let request: Request = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(_) => return false,
+    };
+    let document = match parse_query(&request.query) {
+        Ok(document) => document,
+        Err(_) => return false,
+    };
+    let operation = match (&document.operations, &request.operation_name) {
+        (DocumentOperations::Single(operation), _) => Some(operation),
+        (DocumentOperations::Multiple(operations), Some(name)) => operations.get(name.as_str()),
+        (DocumentOperations::Multiple(operations), None) if operations.len() == 1 => {
+            operations.values().next()
+        }
+        _ => None,
+    };
+    matches!(operation, Some(operation) if operation.node.ty == OperationType::Mutation)
+}
+
+async fn handle_overload(error: BoxError) -> impl IntoResponse {
+    if error.is::<tower::timeout::error::Elapsed>() {
+        (StatusCode::REQUEST_TIMEOUT, "Request timed out".to_string())
+    } else if error.is::<tower::load_shed::error::Overloaded>() {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many concurrent requests".to_string(),
+        )
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
 async fn shutdown_signal() {
     #[cfg(unix)]
     {
@@ -117,15 +372,133 @@ async fn shutdown_signal() {
 }
 
 async fn graphql_playground() -> impl IntoResponse {
-    Html(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+    Html(playground_source(
+        GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/graphql-ws"),
+    ))
 }
 
 async fn health() -> Json<serde_json::Value> {
     Json(json!({ "up": true }))
 }
 
-async fn graphql_handler(schema: Extension<CoreSchema>, req: Json<Request>) -> Json<Response> {
-    schema.execute(req.0).await.into()
+async fn graphql_handler(
+    schema: Extension<CoreSchema>,
+    // must come before `HeaderMap`, which takes the headers out of the request
+    req: Json<Request>,
+    headers: HeaderMap,
+) -> Json<Response> {
+    let mut req = req.0;
+    // checked against the configured admin token by the fields that require it
+    if let Some(token) = headers
+        .get(AUTHORIZATION)
+        .and_then(|authorization| authorization.to_str().ok())
+        .and_then(BearerToken::from_header)
+    {
+        req = req.data(token);
+    }
+    schema.execute(req).await.into()
+}
+
+async fn graphql_subscription_handler(
+    schema: Extension<CoreSchema>,
+    subscriptions_stopped: Extension<watch::Receiver<()>>,
+    connections: Extension<Arc<ConnectionLimiter>>,
+    client: Option<ConnectInfo<SocketAddr>>,
+    // must come before `HeaderMap`, which takes the headers out of the request
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // the limits of http requests only apply to the upgrade, a connection is held until closed
+    let permit = match client {
+        Some(ConnectInfo(addr)) => match connections.acquire(addr.ip()) {
+            Some(permit) => Some(permit),
+            None => {
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "Too many websocket connections",
+                )
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+
+    let requested = headers
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|protocols| protocols.to_str().ok())
+        .unwrap_or_default();
+    let protocol = ALL_WEBSOCKET_PROTOCOLS
+        .into_iter()
+        .find(|protocol| requested.split(',').any(|p| p.trim() == *protocol))
+        .and_then(|protocol| WebSocketProtocols::from_str(protocol).ok());
+
+    match protocol {
+        Some(protocol) => ws
+            .protocols([protocol.sec_websocket_protocol()])
+            .on_upgrade(move |socket| {
+                serve_subscriptions(socket, schema.0, protocol, subscriptions_stopped.0, permit)
+            })
+            .into_response(),
+        None => (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Unsupported websocket protocol, expected one of: {}",
+                ALL_WEBSOCKET_PROTOCOLS.join(", ")
+            ),
+        )
+            .into_response(),
+    }
+}
+
+async fn serve_subscriptions(
+    socket: WebSocket,
+    schema: CoreSchema,
+    protocol: WebSocketProtocols,
+    mut subscriptions_stopped: watch::Receiver<()>,
+    // released once the connection is closed
+    _permit: Option<ConnectionPermit>,
+) {
+    let (mut sink, stream) = socket.split();
+    let requests = stream
+        .take_while(|message| future::ready(message.is_ok()))
+        .filter_map(|message| {
+            future::ready(match message {
+                Ok(message @ (Message::Text(_) | Message::Binary(_))) => Some(message.into_data()),
+                _ => None,
+            })
+        });
+    let mut session = Data::default();
+    session.insert(SubscriptionsOnly);
+    let mut responses = GraphQLWebSocket::new(schema, requests, protocol).connection_data(session);
+
+    let mut keep_alive = interval_at(
+        Instant::now() + WEBSOCKET_KEEP_ALIVE_INTERVAL,
+        WEBSOCKET_KEEP_ALIVE_INTERVAL,
+    );
+
+    loop {
+        let message = tokio::select! {
+            response = responses.next() => match response {
+                Some(WsMessage::Text(text)) => Message::Text(text),
+                Some(WsMessage::Close(code, reason)) => Message::Close(Some(CloseFrame {
+                    code,
+                    reason: reason.into(),
+                })),
+                None => break,
+            },
+            _ = keep_alive.tick() => Message::Ping(vec![]),
+            // also fires if the server task was aborted and dropped the sender
+            _ = subscriptions_stopped.changed() => Message::Close(Some(CloseFrame {
+                code: WEBSOCKET_GOING_AWAY,
+                reason: "node is shutting down".into(),
+            })),
+        };
+
+        let is_close = matches!(message, Message::Close(_));
+        if sink.send(message).await.is_err() || is_close {
+            break;
+        }
+    }
 }
 
 async fn ok() -> Result<(), ()> {
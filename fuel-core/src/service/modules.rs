@@ -1,5 +1,6 @@
-use crate::config::Config;
+use crate::config::{Config, DbMode};
 use crate::database::Database;
+use crate::service::block_production::{BlockCommits, BlockCommitter};
 use anyhow::Result;
 use fuel_core_interfaces::txpool::TxPoolDb;
 use futures::future::join_all;
@@ -12,6 +13,7 @@ pub struct Modules {
     pub block_producer: Arc<fuel_block_producer::Service>,
     pub bft: Arc<fuel_core_bft::Service>,
     pub sync: Arc<fuel_sync::Service>,
+    pub block_commits: BlockCommits,
 }
 
 impl Modules {
@@ -34,15 +36,38 @@ impl Modules {
 pub async fn start_modules(config: &Config, database: &Database) -> Result<Modules> {
     let db = ();
     // Initialize and bind all components
-    let block_importer = fuel_block_importer::Service::new(&config.block_importer, db).await?;
-    let block_producer = fuel_block_producer::Service::new(&config.block_producer, db).await?;
+    let block_importer =
+        Arc::new(fuel_block_importer::Service::new(&config.block_importer, db).await?);
+    let mut block_producer_config = config.block_producer.clone();
+    // blocks are produced as configured for the chain
+    block_producer_config.trigger = config.chain_conf.block_production.trigger();
+    // the producer may pack smaller blocks than the chain allows, but not larger ones
+    block_producer_config.max_gas_per_block = Some(
+        block_producer_config
+            .max_gas_per_block
+            .unwrap_or(u64::MAX)
+            .min(config.chain_conf.max_gas_per_block),
+    );
+    block_producer_config.max_block_size = Some(
+        block_producer_config
+            .max_block_size
+            .unwrap_or(u64::MAX)
+            .min(config.chain_conf.max_block_size),
+    );
+    let block_producer = fuel_block_producer::Service::new(&block_producer_config, db).await?;
     let bft = fuel_core_bft::Service::new(&config.bft, db).await?;
     let sync = fuel_sync::Service::new(&config.sync).await?;
     // let mut relayer = FuelRelayer::new(FuelRelayerConfig::default());
     // let mut p2p = FuelP2P::new(FuelP2PConfig::default());
+    let mut txpool_config = config.txpool.clone();
+    // the txpool checks the coin inputs against the database as the executor does
+    txpool_config.utxo_validation = config.utxo_validation;
+    // transactions which can't fit into any block are refused
+    txpool_config.max_gas_per_block = block_producer_config.max_gas_per_block.unwrap_or(u64::MAX);
+    txpool_config.max_block_size = block_producer_config.max_block_size.unwrap_or(u64::MAX);
     let txpool = fuel_txpool::Service::new(
         Box::new(database.clone()) as Box<dyn TxPoolDb>,
-        config.txpool.clone(),
+        txpool_config,
     )?;
 
     let p2p_mpsc = ();
@@ -50,32 +75,50 @@ pub async fn start_modules(config: &Config, database: &Database) -> Result<Modul
     let p2p_broadcast_block = ();
     let relayer_mpsc = ();
 
+    // the api of read-only replicas uses the txpool to look up transactions and the block
+    // importer to announce new blocks, they stay out of block production
     block_importer.start().await;
     txpool.start(block_importer.subscribe()).await;
-    block_producer.start(txpool.sender().clone()).await;
-    bft.start(
-        relayer_mpsc,
-        p2p_broadcast_consensus,
-        block_producer.sender().clone(),
-        block_importer.sender().clone(),
-        block_importer.subscribe(),
-    )
-    .await;
+    let block_commits = BlockCommits::default();
+    if config.database_mode == DbMode::Primary {
+        let block_committer = BlockCommitter {
+            database: database.clone(),
+            config: config.clone(),
+            block_importer: block_importer.clone(),
+            block_commits: block_commits.clone(),
+        };
+        block_producer
+            .start(
+                txpool.sender().clone(),
+                txpool.subscribe_ch(),
+                Arc::new(block_committer),
+            )
+            .await;
+        bft.start(
+            relayer_mpsc,
+            p2p_broadcast_consensus,
+            block_producer.sender().clone(),
+            block_importer.sender().clone(),
+            block_importer.subscribe(),
+        )
+        .await;
 
-    sync.start(
-        p2p_broadcast_block,
-        p2p_mpsc,
-        relayer_mpsc,
-        bft.sender().clone(),
-        block_importer.sender().clone(),
-    )
-    .await;
+        sync.start(
+            p2p_broadcast_block,
+            p2p_mpsc,
+            relayer_mpsc,
+            bft.sender().clone(),
+            block_importer.sender().clone(),
+        )
+        .await;
+    }
 
     Ok(Modules {
         txpool: Arc::new(txpool),
-        block_importer: Arc::new(block_importer),
+        block_importer,
         block_producer: Arc::new(block_producer),
         bft: Arc::new(bft),
         sync: Arc::new(sync),
+        block_commits,
     })
 }
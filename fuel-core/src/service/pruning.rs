@@ -0,0 +1,40 @@
+use crate::{config::PruningConfig, database::Database, service::block_production::BlockCommits};
+use anyhow::Error as AnyError;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Periodically prunes the history of the blocks older than the ones the node keeps.
+///
+/// Blocks are pruned one at a time while holding `block_commits`, so no block is committed while
+/// the history is pruned from the current height.
+pub(crate) fn start(
+    database: Database,
+    config: PruningConfig,
+    block_commits: BlockCommits,
+) -> JoinHandle<Result<(), AnyError>> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            let mut blocks = 0;
+            let pruned = loop {
+                let _commits = block_commits.write().await;
+                let database = database.clone();
+                let next =
+                    tokio::task::spawn_blocking(move || database.prune_next(config.keep_blocks))
+                        .await?;
+                match next {
+                    Ok(Some(_)) => blocks += 1,
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            };
+            if blocks > 0 {
+                info!("Pruned the history of {} blocks", blocks);
+            }
+            if let Err(e) = pruned {
+                warn!("Failed to prune the history of old blocks: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Clients are forgotten once their window is over, but only after this many are tracked, so
+/// the map doesn't have to be scanned on every request.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Limits the number of requests accepted from each IP address per second.
+pub(crate) struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    clients: Mutex<HashMap<IpAddr, Window>>,
+}
+
+struct Window {
+    start: Instant,
+    requests: u32,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_requests_per_second: u32) -> Self {
+        Self {
+            max_requests: max_requests_per_second,
+            window: Duration::from_secs(1),
+            clients: Default::default(),
+        }
+    }
+
+    /// Records a request from `ip`, returns false if the client exceeded its limit.
+    pub(crate) fn check(&self, ip: IpAddr) -> bool {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut clients = self.clients.lock().expect("poisoned lock");
+        if clients.len() >= MAX_TRACKED_CLIENTS {
+            clients.retain(|_, window| now.duration_since(window.start) < self.window);
+        }
+
+        let window = clients.entry(ip).or_insert(Window {
+            start: now,
+            requests: 0,
+        });
+        if now.duration_since(window.start) >= self.window {
+            window.start = now;
+            window.requests = 0;
+        }
+        if window.requests < self.max_requests {
+            window.requests += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Limits the number of websocket connections open at the same time from each IP address.
+pub(crate) struct ConnectionLimiter {
+    max_connections: usize,
+    clients: Mutex<HashMap<IpAddr, usize>>,
+}
+
+/// Counts as an open connection of its client until it is dropped.
+pub(crate) struct ConnectionPermit {
+    limiter: Arc<ConnectionLimiter>,
+    ip: IpAddr,
+}
+
+impl ConnectionLimiter {
+    pub(crate) fn new(max_connections_per_ip: usize) -> Self {
+        Self {
+            max_connections: max_connections_per_ip,
+            clients: Default::default(),
+        }
+    }
+
+    /// Records a connection from `ip`, returns `None` if the client has too many open already.
+    pub(crate) fn acquire(self: &Arc<Self>, ip: IpAddr) -> Option<ConnectionPermit> {
+        let mut clients = self.clients.lock().expect("poisoned lock");
+        let connections = clients.entry(ip).or_default();
+        if *connections >= self.max_connections {
+            return None;
+        }
+        *connections += 1;
+        Some(ConnectionPermit {
+            limiter: self.clone(),
+            ip,
+        })
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut clients = self.limiter.clients.lock().expect("poisoned lock");
+        if let Some(connections) = clients.get_mut(&self.ip) {
+            *connections -= 1;
+            if *connections == 0 {
+                clients.remove(&self.ip);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn requests_are_limited_per_ip_and_second() {
+        let limiter = RateLimiter::new(2);
+        let (a, b) = (
+            IpAddr::from(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::from(Ipv4Addr::new(10, 0, 0, 2)),
+        );
+        let now = Instant::now();
+
+        assert!(limiter.check_at(a, now));
+        assert!(limiter.check_at(a, now + Duration::from_millis(10)));
+        assert!(!limiter.check_at(a, now + Duration::from_millis(20)));
+        // other clients have their own limit
+        assert!(limiter.check_at(b, now + Duration::from_millis(20)));
+        // the limit is reset once the window is over
+        assert!(limiter.check_at(a, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn connections_are_limited_per_ip_until_closed() {
+        let limiter = Arc::new(ConnectionLimiter::new(2));
+        let (a, b) = (
+            IpAddr::from(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::from(Ipv4Addr::new(10, 0, 0, 2)),
+        );
+
+        let first = limiter.acquire(a).expect("below the limit");
+        let _second = limiter.acquire(a).expect("below the limit");
+        assert!(limiter.acquire(a).is_none());
+        // other clients have their own limit
+        assert!(limiter.acquire(b).is_some());
+
+        // closing a connection frees its slot
+        drop(first);
+        assert!(limiter.acquire(a).is_some());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{database::Database, service::block_production::BlockCommits};
+use anyhow::Error as AnyError;
+use fuel_block_importer::Service as BlockImporterService;
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Periodically catches up with the writes of the primary and announces the blocks it committed
+/// to the subscribers of the replica. The writes of the primary are read while holding
+/// `block_commits`, as blocks committed by the replica itself.
+pub(crate) fn start(
+    database: Database,
+    block_importer: Arc<BlockImporterService>,
+    block_commits: BlockCommits,
+    interval: Duration,
+) -> JoinHandle<Result<(), AnyError>> {
+    // the blocks committed before the replica started aren't announced
+    let announced = database.get_block_height();
+    tokio::spawn(async move {
+        let mut announced = announced?.map(u32::from);
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            let primary = database.clone();
+            let commits = block_commits.write().await;
+            let caught_up =
+                tokio::task::spawn_blocking(move || primary.catch_up_with_primary()).await?;
+            drop(commits);
+            if let Err(e) = caught_up {
+                warn!("Failed to catch up with the primary: {}", e);
+                continue;
+            }
+
+            let latest: u32 = match database.get_block_height()? {
+                Some(latest) => latest.into(),
+                None => continue,
+            };
+            // the primary rolled back some blocks
+            if matches!(announced, Some(announced) if announced > latest) {
+                announced = Some(latest);
+            }
+            let next = announced.map_or(0, |announced| announced + 1);
+            for height in next..=latest {
+                // there is no block at the starting height of the chain
+                let block_id = match database.get_block_id(height.into())? {
+                    Some(block_id) => block_id,
+                    None => continue,
+                };
+                // the block isn't sealed yet, it's announced by the next run
+                let block = match database.get_sealed_block_by_id(&block_id)? {
+                    Some(block) => block,
+                    None => break,
+                };
+                block_importer.notify_block_imported(Arc::new(block), false);
+                announced = Some(height);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, executor::Executor, model::FuelBlockConsensus};
+    use fuel_core_interfaces::{
+        block_importer::ImportBlockBroadcast,
+        common::{fuel_storage::Storage, fuel_tx::Bytes32},
+    };
+
+    #[tokio::test]
+    async fn blocks_of_the_primary_are_announced() {
+        let database = Database::default();
+        let block_importer = Arc::new(
+            BlockImporterService::new(&Default::default(), ())
+                .await
+                .unwrap(),
+        );
+        let mut blocks = block_importer.subscribe();
+        let task = start(
+            database.clone(),
+            block_importer.clone(),
+            Default::default(),
+            Duration::from_millis(10),
+        );
+
+        // the primary commits a block, then seals it
+        let executor = Executor {
+            database: database.clone(),
+            config: Config::local_node(),
+        };
+        let block = executor.submit_txs(vec![]).await.unwrap();
+        Storage::<Bytes32, FuelBlockConsensus>::insert(
+            &mut database.clone(),
+            &block.id(),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let announced = tokio::time::timeout(Duration::from_secs(5), blocks.recv())
+            .await
+            .expect("the block wasn't announced");
+        match announced.unwrap() {
+            ImportBlockBroadcast::SealedFuelBlockImported {
+                block: announced,
+                is_created_by_self,
+            } => {
+                assert_eq!(announced.id(), block.id());
+                assert!(!is_created_by_self);
+            }
+            _ => panic!("Unexpected event"),
+        }
+        task.abort();
+    }
+}
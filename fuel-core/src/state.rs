@@ -1,5 +1,6 @@
 use crate::state::in_memory::transaction::MemoryTransactionView;
-use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, marker::PhantomData, path::Path, sync::Arc};
 
 pub type Result<T> = core::result::Result<T, Error>;
 pub type DataSource = Arc<dyn TransactableStorage>;
@@ -69,23 +70,12 @@ impl Default for IterDirection {
 pub use fuel_core_interfaces::db::Error;
 
 pub trait BatchOperations: KeyValueStore {
-    fn batch_write(&self, entries: &mut dyn Iterator<Item = WriteOperation>) -> Result<()> {
-        for entry in entries {
-            match entry {
-                // TODO: error handling
-                WriteOperation::Insert(key, column, value) => {
-                    let _ = self.put(key, column, value);
-                }
-                WriteOperation::Remove(key, column) => {
-                    let _ = self.delete(&key, column);
-                }
-            }
-        }
-        Ok(())
-    }
+    /// Applies the operations atomically: either all of them are written, or none is and the
+    /// error is returned.
+    fn batch_write(&self, entries: &mut dyn Iterator<Item = WriteOperation>) -> Result<()>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WriteOperation {
     Insert(Vec<u8>, ColumnId, Vec<u8>),
     Remove(Vec<u8>, ColumnId),
@@ -99,7 +89,20 @@ pub trait Transaction {
 
 pub type TransactionResult<T> = core::result::Result<T, TransactionError>;
 
-pub trait TransactableStorage: KeyValueStore + BatchOperations + Debug + Send + Sync {}
+pub trait TransactableStorage: KeyValueStore + BatchOperations + Debug + Send + Sync {
+    /// Writes a consistent copy of the store to the new directory `path`, while the store keeps
+    /// being written.
+    fn checkpoint(&self, _path: &Path) -> Result<()> {
+        Err(Error::DatabaseError(
+            "checkpoints are only supported by RocksDB databases".into(),
+        ))
+    }
+
+    /// Reads the latest writes of the primary, if the store follows the store of another node.
+    fn catch_up_with_primary(&self) -> Result<()> {
+        Ok(())
+    }
+}
 
 #[derive(Clone, Debug)]
 pub enum TransactionError {
@@ -1,5 +1,7 @@
 use crate::state::ColumnId;
 
+#[cfg(test)]
+pub(crate) mod faulty_store;
 pub mod memory_store;
 pub mod transaction;
 
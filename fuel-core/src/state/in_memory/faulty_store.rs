@@ -0,0 +1,59 @@
+//! A store whose batch writes can be made to fail, to test how failed commits are handled.
+
+use crate::state::{
+    in_memory::memory_store::MemoryStore, BatchOperations, ColumnId, Error, IterDirection,
+    KeyValueStore, Result, TransactableStorage, WriteOperation,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Default)]
+pub(crate) struct FaultyStore {
+    inner: MemoryStore,
+    fail_batches: AtomicBool,
+}
+
+impl FaultyStore {
+    /// Makes the following batch writes fail, or succeed again.
+    pub(crate) fn fail_batches(&self, fail: bool) {
+        self.fail_batches.store(fail, Ordering::SeqCst);
+    }
+}
+
+impl KeyValueStore for FaultyStore {
+    fn get(&self, key: &[u8], column: ColumnId) -> Result<Option<Vec<u8>>> {
+        self.inner.get(key, column)
+    }
+
+    fn put(&self, key: Vec<u8>, column: ColumnId, value: Vec<u8>) -> Result<Option<Vec<u8>>> {
+        self.inner.put(key, column, value)
+    }
+
+    fn delete(&self, key: &[u8], column: ColumnId) -> Result<Option<Vec<u8>>> {
+        self.inner.delete(key, column)
+    }
+
+    fn exists(&self, key: &[u8], column: ColumnId) -> Result<bool> {
+        self.inner.exists(key, column)
+    }
+
+    fn iter_all(
+        &self,
+        column: ColumnId,
+        prefix: Option<Vec<u8>>,
+        start: Option<Vec<u8>>,
+        direction: IterDirection,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        self.inner.iter_all(column, prefix, start, direction)
+    }
+}
+
+impl BatchOperations for FaultyStore {
+    fn batch_write(&self, entries: &mut dyn Iterator<Item = WriteOperation>) -> Result<()> {
+        if self.fail_batches.load(Ordering::SeqCst) {
+            return Err(Error::DatabaseError("injected batch write failure".into()));
+        }
+        self.inner.batch_write(entries)
+    }
+}
+
+impl TransactableStorage for FaultyStore {}
@@ -1,6 +1,7 @@
 use crate::state::{
     in_memory::{column_key, is_column},
     BatchOperations, ColumnId, IterDirection, KeyValueStore, Result, TransactableStorage,
+    WriteOperation,
 };
 use itertools::Itertools;
 use std::{collections::HashMap, fmt::Debug, mem::size_of, sync::Mutex};
@@ -86,6 +87,22 @@ impl KeyValueStore for MemoryStore {
     }
 }
 
-impl BatchOperations for MemoryStore {}
+impl BatchOperations for MemoryStore {
+    fn batch_write(&self, entries: &mut dyn Iterator<Item = WriteOperation>) -> Result<()> {
+        // the batch is applied under a single lock, so readers never see a part of it
+        let mut inner = self.inner.lock().expect("poisoned");
+        for entry in entries {
+            match entry {
+                WriteOperation::Insert(key, column, value) => {
+                    inner.insert(column_key(&key, column), value);
+                }
+                WriteOperation::Remove(key, column) => {
+                    inner.remove(&column_key(&key, column));
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
 impl TransactableStorage for MemoryStore {}
@@ -25,15 +25,51 @@ impl MemoryTransactionView {
         }
     }
 
+    /// Returns the keys of the column which were written or removed in this view.
+    pub fn changed_keys(&self, column: ColumnId) -> Vec<Vec<u8>> {
+        self.changes
+            .lock()
+            .expect("poisoned lock")
+            .values()
+            .filter_map(|op| match op {
+                WriteOperation::Insert(key, c, _) | WriteOperation::Remove(key, c)
+                    if *c == column =>
+                {
+                    Some(key.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the operations reverting the changes of this view once they are committed, by
+    /// restoring the values the changed keys have in the data source.
+    pub fn revert_operations(&self) -> Result<Vec<WriteOperation>> {
+        self.changes
+            .lock()
+            .expect("poisoned lock")
+            .values()
+            .map(|op| {
+                let (key, column) = match op {
+                    WriteOperation::Insert(key, column, _)
+                    | WriteOperation::Remove(key, column) => (key, *column),
+                };
+                Ok(match self.data_source.get(key, column)? {
+                    Some(previous) => WriteOperation::Insert(key.clone(), column, previous),
+                    None => WriteOperation::Remove(key.clone(), column),
+                })
+            })
+            .collect()
+    }
+
+    /// Writes the changes to the data source in a single batch. They are kept if the write
+    /// fails, so the data source is left untouched and the commit can be retried.
     pub fn commit(&self) -> crate::state::Result<()> {
-        self.data_source.batch_write(
-            &mut self
-                .changes
-                .lock()
-                .expect("poisoned lock")
-                .drain()
-                .map(|t| t.1),
-        )
+        let mut changes = self.changes.lock().expect("poisoned lock");
+        self.data_source
+            .batch_write(&mut changes.values().cloned())?;
+        changes.clear();
+        Ok(())
     }
 }
 
@@ -138,7 +174,22 @@ impl KeyValueStore for MemoryTransactionView {
     }
 }
 
-impl BatchOperations for MemoryTransactionView {}
+impl BatchOperations for MemoryTransactionView {
+    fn batch_write(&self, entries: &mut dyn Iterator<Item = WriteOperation>) -> Result<()> {
+        let mut changes = self.changes.lock().expect("poisoned lock");
+        let entries: Vec<_> = entries.collect();
+        self.view_layer.batch_write(&mut entries.iter().cloned())?;
+        for entry in entries {
+            let key = match &entry {
+                WriteOperation::Insert(key, column, _) | WriteOperation::Remove(key, column) => {
+                    column_key(key, *column)
+                }
+            };
+            changes.insert(key, entry);
+        }
+        Ok(())
+    }
+}
 
 impl<T> Transaction for Arc<T>
 where
@@ -162,6 +213,7 @@ impl TransactableStorage for MemoryTransactionView {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::in_memory::faulty_store::FaultyStore;
     use crate::state::TransactionError;
 
     #[test]
@@ -220,6 +272,22 @@ mod tests {
         assert_eq!(ret, Some(vec![1, 2, 3]))
     }
 
+    #[test]
+    fn changed_keys_include_inserted_and_removed_keys_of_column() {
+        // setup
+        let store = Arc::new(MemoryStore::default());
+        store.put(vec![0xA], 0, vec![1]).unwrap();
+        let view = MemoryTransactionView::new(store);
+        view.put(vec![0xB], 0, vec![2]).unwrap();
+        view.delete(&[0xA], 0).unwrap();
+        view.put(vec![0xC], 1, vec![3]).unwrap();
+        // test
+        let mut keys = view.changed_keys(0);
+        keys.sort();
+        // verify
+        assert_eq!(keys, vec![vec![0xA], vec![0xB]])
+    }
+
     #[test]
     fn delete_value_from_view_returns_value() {
         // setup
@@ -340,6 +408,49 @@ mod tests {
         assert_eq!(ret, None)
     }
 
+    #[test]
+    fn failed_commit_leaves_store_untouched() {
+        // setup
+        let store = Arc::new(FaultyStore::default());
+        let key = vec![0xA, 0xB, 0xC];
+        store.put(key.clone(), 0, vec![1, 2, 3]).unwrap();
+        let view = MemoryTransactionView::new(store.clone());
+        view.put(key.clone(), 0, vec![4, 5, 6]).unwrap();
+        view.put(vec![0xD], 0, vec![7]).unwrap();
+        view.delete(&[0xE], 0).unwrap();
+        store.fail_batches(true);
+        // test
+        assert!(view.commit().is_err());
+        // verify
+        assert_eq!(store.get(&key, 0).unwrap(), Some(vec![1, 2, 3]));
+        assert!(!store.exists(&[0xD], 0).unwrap());
+        // the changes are kept, so the commit can be retried
+        assert_eq!(view.get(&key, 0).unwrap(), Some(vec![4, 5, 6]));
+        store.fail_batches(false);
+        view.commit().unwrap();
+        assert_eq!(store.get(&key, 0).unwrap(), Some(vec![4, 5, 6]));
+        assert_eq!(store.get(&[0xD], 0).unwrap(), Some(vec![7]));
+    }
+
+    #[test]
+    fn commit_of_nested_view_is_applied_to_parent_view() {
+        // setup
+        let store = Arc::new(MemoryStore::default());
+        let key = vec![0xA, 0xB, 0xC];
+        store.put(key.clone(), 0, vec![1, 2, 3]).unwrap();
+        let parent = Arc::new(MemoryTransactionView::new(store.clone()));
+        let view = MemoryTransactionView::new(parent.clone());
+        view.delete(&key, 0).unwrap();
+        view.put(vec![0xD], 0, vec![4]).unwrap();
+        // test
+        view.commit().unwrap();
+        // verify
+        assert!(!parent.exists(&key, 0).unwrap());
+        assert_eq!(parent.get(&[0xD], 0).unwrap(), Some(vec![4]));
+        assert!(store.exists(&key, 0).unwrap());
+        assert_eq!(parent.changed_keys(0).len(), 2);
+    }
+
     #[test]
     fn transaction_commit_is_applied_if_successful() {
         let mut store = Arc::new(MemoryStore::default());
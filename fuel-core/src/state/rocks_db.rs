@@ -1,22 +1,21 @@
 #[cfg(feature = "prometheus")]
 use crate::service::metrics::prometheus_metrics::DATABASE_METRICS;
 use crate::{
-    database::{columns, columns::METADATA, VERSION},
+    database::columns,
     state::{
         BatchOperations, ColumnId, Error, IterDirection, KeyValueStore, TransactableStorage,
         WriteOperation,
     },
 };
 use rocksdb::{
-    BoundColumnFamily, ColumnFamilyDescriptor, DBCompressionType, DBWithThreadMode, IteratorMode,
-    MultiThreaded, Options, ReadOptions, SliceTransform, WriteBatch,
+    checkpoint::Checkpoint, BoundColumnFamily, ColumnFamilyDescriptor, DBCompressionType,
+    DBWithThreadMode, IteratorMode, MultiThreaded, Options, ReadOptions, SliceTransform,
+    WriteBatch,
 };
-use std::{convert::TryFrom, path::Path, sync::Arc};
+use std::{path::Path, sync::Arc};
 
 type DB = DBWithThreadMode<MultiThreaded>;
 
-const VERSION_KEY: &[u8] = b"version";
-
 #[derive(Debug)]
 pub struct RocksDb {
     db: DBWithThreadMode<MultiThreaded>,
@@ -30,6 +29,8 @@ impl RocksDb {
 
         let mut opts = Options::default();
         opts.create_if_missing(true);
+        // columns added by newer versions are created in existing databases
+        opts.create_missing_column_families(true);
         opts.set_compression_type(DBCompressionType::Lz4);
         let db = match DB::open_cf_descriptors(&opts, &path, cf_descriptors) {
             Err(_) => {
@@ -49,31 +50,22 @@ impl RocksDb {
             ok => ok,
         }
         .map_err(|e| Error::DatabaseError(Box::new(e)))?;
-        let rocks_db = RocksDb { db };
-        rocks_db.validate_or_set_db_version()?;
-        Ok(rocks_db)
+        Ok(RocksDb { db })
     }
 
-    fn validate_or_set_db_version(&self) -> Result<(), Error> {
-        let data = self.get(VERSION_KEY, METADATA)?;
-        match data {
-            None => {
-                self.put(
-                    VERSION_KEY.to_vec(),
-                    METADATA,
-                    VERSION.to_be_bytes().to_vec(),
-                )?;
-            }
-            Some(v) => {
-                let b =
-                    <[u8; 4]>::try_from(v.as_slice()).map_err(|_| Error::InvalidDatabaseVersion)?;
-                let version = u32::from_be_bytes(b);
-                if version != VERSION {
-                    return Err(Error::InvalidDatabaseVersion);
-                }
-            }
-        };
-        Ok(())
+    /// Opens the database of a primary instance at `primary` as a secondary, which only reads and
+    /// follows the writes of the primary. The secondary keeps its own logs at `secondary`.
+    pub fn open_secondary(primary: &Path, secondary: &Path, cols: u32) -> Result<RocksDb, Error> {
+        let cf_descriptors: Vec<_> = (0..cols)
+            .map(|i| ColumnFamilyDescriptor::new(RocksDb::col_name(i), Self::cf_opts(i)))
+            .collect();
+
+        let mut opts = Options::default();
+        // the files of the primary may be deleted by its compactions while the secondary reads them
+        opts.set_max_open_files(-1);
+        let db = DB::open_cf_descriptors_as_secondary(&opts, primary, secondary, cf_descriptors)
+            .map_err(|e| Error::DatabaseError(Box::new(e)))?;
+        Ok(RocksDb { db })
     }
 
     fn cf(&self, column: ColumnId) -> Arc<BoundColumnFamily> {
@@ -244,7 +236,19 @@ impl BatchOperations for RocksDb {
     }
 }
 
-impl TransactableStorage for RocksDb {}
+impl TransactableStorage for RocksDb {
+    fn checkpoint(&self, path: &Path) -> crate::state::Result<()> {
+        Checkpoint::new(&self.db)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(path))
+            .map_err(|e| Error::DatabaseError(Box::new(e)))
+    }
+
+    fn catch_up_with_primary(&self) -> crate::state::Result<()> {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(|e| Error::DatabaseError(Box::new(e)))
+    }
+}
 
 impl From<IterDirection> for rocksdb::Direction {
     fn from(d: IterDirection) -> Self {
@@ -332,4 +336,38 @@ mod tests {
 
         assert_eq!(db.get(&key, 0).unwrap(), None);
     }
+
+    #[test]
+    fn checkpoint_is_a_consistent_copy() {
+        let key = vec![0xA, 0xB, 0xC];
+
+        let (db, tmp) = create_db(1);
+        db.put(key.clone(), 0, vec![1, 2, 3]).unwrap();
+        let path = tmp.path().join("checkpoint");
+        db.checkpoint(&path).unwrap();
+        // later writes aren't part of the checkpoint
+        db.put(key.clone(), 0, vec![2, 4, 6]).unwrap();
+
+        let checkpoint = RocksDb::open(&path, 1).unwrap();
+        assert_eq!(checkpoint.get(&key, 0).unwrap(), Some(vec![1, 2, 3]));
+        // the checkpoint directory must not exist yet
+        assert!(db.checkpoint(&path).is_err());
+    }
+
+    #[test]
+    fn secondary_catches_up_with_primary() {
+        let key = vec![0xA, 0xB, 0xC];
+
+        let (db, tmp) = create_db(1);
+        db.put(key.clone(), 0, vec![1, 2, 3]).unwrap();
+        let secondary =
+            RocksDb::open_secondary(tmp.path(), &tmp.path().join("secondary"), 1).unwrap();
+        assert_eq!(secondary.get(&key, 0).unwrap(), Some(vec![1, 2, 3]));
+
+        db.put(key.clone(), 0, vec![2, 4, 6]).unwrap();
+        secondary.catch_up_with_primary().unwrap();
+
+        assert_eq!(secondary.get(&key, 0).unwrap(), Some(vec![2, 4, 6]));
+        assert!(secondary.put(key, 0, vec![]).is_err());
+    }
 }
@@ -1,5 +1,8 @@
+use crate::config::Config;
 use crate::database::Database;
 use crate::model::{Coin, CoinStatus};
+use crate::schema::{build_schema, dap, CoreSchema};
+use crate::service::modules::{start_modules, Modules};
 use fuel_core_interfaces::common::{
     fuel_asm::Word,
     fuel_storage::Storage,
@@ -54,3 +57,20 @@ impl AsRef<Database> for TestDatabase {
         self.database.as_ref()
     }
 }
+
+/// Starts the services of a node on top of `database` and builds the GraphQL schema over them,
+/// the same way the API server does.
+pub async fn start_schema(config: Config, database: Database) -> (CoreSchema, Modules) {
+    let modules = start_modules(&config, &database).await.unwrap();
+    let params = config.chain_conf.transaction_parameters;
+    let schema = build_schema()
+        .data(config)
+        .data(database)
+        .data(modules.txpool.clone())
+        .data(modules.block_importer.clone())
+        .data(modules.block_producer.clone())
+        .data(modules.sync.clone())
+        .data(modules.bft.clone())
+        .data(modules.block_commits.clone());
+    (dap::init(schema, params).finish(), modules)
+}
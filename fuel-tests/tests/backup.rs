@@ -0,0 +1,86 @@
+use fuel_core::{
+    config::{Config, GraphQLConfig},
+    service::FuelService,
+};
+use fuel_gql_client::client::FuelClient;
+use std::path::Path;
+use tempfile::TempDir;
+
+async fn node_with_checkpoint_dir(admin_token: Option<&str>, dir: &Path) -> FuelService {
+    let mut config = Config::local_node();
+    config.graphql = GraphQLConfig {
+        admin_token: admin_token.map(ToString::to_string),
+        checkpoint_dir: Some(dir.to_path_buf()),
+        ..Default::default()
+    };
+    FuelService::new_node(config).await.unwrap()
+}
+
+#[tokio::test]
+async fn create_checkpoint_requires_admin_token() {
+    let tmp_dir = TempDir::new().unwrap();
+    let srv = node_with_checkpoint_dir(None, tmp_dir.path()).await;
+    let client = FuelClient::from(srv.bound_address);
+
+    let err = client.create_checkpoint("checkpoint").await.err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("disabled on nodes without an admin token"),
+        "{}",
+        err
+    );
+    assert!(!tmp_dir.path().join("checkpoint").exists());
+}
+
+#[tokio::test]
+async fn create_checkpoint_requires_valid_admin_token() {
+    let tmp_dir = TempDir::new().unwrap();
+    let srv = node_with_checkpoint_dir(Some("secret"), tmp_dir.path()).await;
+    let client = FuelClient::from(srv.bound_address);
+
+    for client in [client.clone(), client.with_admin_token("wrong")] {
+        let err = client.create_checkpoint("checkpoint").await.err().unwrap();
+        assert!(
+            err.to_string().contains("requires a valid admin token"),
+            "{}",
+            err
+        );
+    }
+    assert!(!tmp_dir.path().join("checkpoint").exists());
+}
+
+#[tokio::test]
+async fn checkpoints_stay_in_the_checkpoint_directory() {
+    let tmp_dir = TempDir::new().unwrap();
+    let dir = tmp_dir.path().join("checkpoints");
+    std::fs::create_dir(&dir).unwrap();
+    let srv = node_with_checkpoint_dir(Some("secret"), &dir).await;
+    let client = FuelClient::from(srv.bound_address).with_admin_token("secret");
+
+    let outside = tmp_dir.path().join("checkpoint");
+    for path in ["../checkpoint", outside.to_str().unwrap()] {
+        let err = client.create_checkpoint(path).await.err().unwrap();
+        assert!(
+            err.to_string()
+                .contains("can only be written to the checkpoint directory"),
+            "{}",
+            err
+        );
+    }
+    assert!(!outside.exists());
+}
+
+#[tokio::test]
+async fn in_memory_node_has_no_checkpoints() {
+    let tmp_dir = TempDir::new().unwrap();
+    let srv = node_with_checkpoint_dir(Some("secret"), tmp_dir.path()).await;
+    let client = FuelClient::from(srv.bound_address).with_admin_token("secret");
+
+    let err = client.create_checkpoint("checkpoint").await.err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("checkpoints are only supported by RocksDB databases"),
+        "{}",
+        err
+    );
+}
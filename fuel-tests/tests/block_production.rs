@@ -0,0 +1,164 @@
+use chrono::{TimeZone, Utc};
+use fuel_core::{
+    config::{chain_config::ProductionStrategy, Config},
+    service::FuelService,
+};
+use fuel_core_interfaces::common::fuel_tx;
+use fuel_gql_client::client::{types::TransactionStatus, FuelClient};
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+
+fn script(data: u64) -> fuel_tx::Transaction {
+    fuel_tx::Transaction::script(
+        0,
+        1_000_000,
+        0,
+        0,
+        vec![],
+        data.to_be_bytes().to_vec(),
+        vec![],
+        vec![],
+        vec![],
+    )
+}
+
+/// Waits until the transaction is executed and returns the id of its block
+async fn executed_in(client: &FuelClient, tx: &fuel_tx::Transaction) -> String {
+    let id = tx.id().to_string();
+    timeout(Duration::from_secs(5), async {
+        loop {
+            match client.transaction_status(&id).await {
+                Ok(TransactionStatus::Success { block_id, .. })
+                | Ok(TransactionStatus::Failure { block_id, .. }) => return block_id,
+                _ => {}
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("the transaction wasn't executed")
+}
+
+#[tokio::test]
+async fn transactions_are_executed_every_block_time() {
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Interval {
+        block_time: Duration::from_millis(100),
+        gas_threshold: None,
+        size_threshold: None,
+    };
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let tx = script(0);
+    client.submit(&tx).await.unwrap();
+
+    let block_id = executed_in(&client, &tx).await;
+    let block = client.block(&block_id).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 1);
+}
+
+#[tokio::test]
+async fn pending_transactions_are_executed_together_once_the_gas_threshold_is_reached() {
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Interval {
+        block_time: Duration::from_secs(3600),
+        gas_threshold: Some(3_000_000),
+        size_threshold: None,
+    };
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let txs = (0..3).map(script).collect::<Vec<_>>();
+    for tx in &txs {
+        client.submit(tx).await.unwrap();
+    }
+
+    let block_id = executed_in(&client, &txs[0]).await;
+    for tx in &txs[1..] {
+        assert_eq!(executed_in(&client, tx).await, block_id);
+    }
+    let block = client.block(&block_id).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+    assert_eq!(block.height.0, 1);
+}
+
+#[tokio::test]
+async fn blocks_are_produced_on_demand_with_deterministic_times() {
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Manual;
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let tx = script(0);
+    client.submit(&tx).await.unwrap();
+    // the transaction waits for the next block
+    assert!(matches!(
+        client.transaction_status(&tx.id().to_string()).await,
+        Ok(TransactionStatus::Submitted { .. })
+    ));
+
+    let start_time = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+    let height = client
+        .produce_blocks(3, Some(start_time), Some(10))
+        .await
+        .unwrap();
+
+    assert_eq!(height, 3);
+    let block_id = executed_in(&client, &tx).await;
+    let first = client.block_by_height(1).await.unwrap().unwrap();
+    assert_eq!(first.id.0.to_string(), block_id);
+    assert_eq!(first.time, start_time);
+    let last = client.block_by_height(3).await.unwrap().unwrap();
+    assert!(last.transactions.is_empty());
+    assert_eq!(last.time, start_time + chrono::Duration::seconds(20));
+}
+
+#[tokio::test]
+async fn blocks_are_only_produced_on_demand_with_the_manual_strategy() {
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let result = client.produce_blocks(1, None, None).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn blocks_are_filled_up_to_the_gas_limit() {
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Manual;
+    // room for two of the transactions
+    config.chain_conf.max_gas_per_block = 2_000_000;
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let txs = (0..3).map(script).collect::<Vec<_>>();
+    for tx in &txs {
+        client.submit(tx).await.unwrap();
+    }
+    client.produce_blocks(2, None, None).await.unwrap();
+
+    let first = client.block_by_height(1).await.unwrap().unwrap();
+    assert_eq!(first.transactions.len(), 2);
+    let second = client.block_by_height(2).await.unwrap().unwrap();
+    assert_eq!(second.transactions.len(), 1);
+}
+
+#[tokio::test]
+async fn transactions_larger_than_a_block_are_refused() {
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Manual;
+    config.chain_conf.max_gas_per_block = 999_999;
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let err = client.submit(&script(0)).await.unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Its gas limit exceeds the gas limit of a block"),
+        "{}",
+        err
+    );
+}
@@ -6,9 +6,12 @@ use fuel_core::{
     schema::scalars::BlockId,
     service::FuelService,
 };
-use fuel_core_interfaces::common::{fuel_storage::Storage, fuel_types};
+use fuel_core_interfaces::common::{fuel_storage::Storage, fuel_tx, fuel_types};
 use fuel_gql_client::client::{FuelClient, PageDirection, PaginationRequest};
+use futures::StreamExt;
 use itertools::{rev, Itertools};
+use std::time::Duration;
+use tokio::time::timeout;
 
 #[tokio::test]
 async fn block() {
@@ -32,6 +35,108 @@ async fn block() {
     assert!(block.is_some());
 }
 
+#[tokio::test]
+async fn get_genesis_block() {
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    // run test
+    let genesis = client.block_by_height(0).await.unwrap().unwrap();
+    assert_eq!(genesis.height.0, 0);
+    assert!(genesis.transactions.is_empty());
+
+    let latest_block = client.chain_info().await.unwrap().latest_block;
+    assert_eq!(latest_block.id.0 .0, genesis.id.0 .0);
+
+    let blocks = client
+        .blocks(PaginationRequest {
+            cursor: None,
+            results: 10,
+            direction: PageDirection::Forward,
+        })
+        .await
+        .unwrap();
+    assert_eq!(blocks.results.len(), 1);
+    assert_eq!(blocks.results[0].id.0 .0, genesis.id.0 .0);
+}
+
+#[tokio::test]
+async fn block_header_and_consensus() {
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    let genesis = client.block_by_height(0).await.unwrap().unwrap();
+
+    // produce a block
+    client
+        .submit_and_await_commit(&fuel_tx::Transaction::default())
+        .await
+        .unwrap();
+
+    // run test
+    let block = client.block_by_height(1).await.unwrap().unwrap();
+    assert_eq!(block.header.id.0 .0, block.id.0 .0);
+    assert_eq!(block.header.height.0, 1);
+    assert_eq!(block.header.parent_hash.0 .0, genesis.id.0 .0);
+    let consensus = block
+        .consensus
+        .expect("Expected produced blocks to be sealed");
+    assert_eq!(consensus.required_stake.0, 0);
+    assert!(consensus.validators.is_empty());
+}
+
+#[tokio::test]
+async fn block_fees_are_paid_to_the_coinbase_recipient() {
+    let recipient = fuel_types::Address::new([7u8; 32]);
+    let mut config = Config::local_node();
+    config.block_producer.coinbase_recipient = Some(recipient);
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    // produce a block paying fees
+    let tx = fuel_tx::Transaction::script(
+        1,
+        1_000,
+        1,
+        0,
+        vec![],
+        vec![],
+        vec![fuel_tx::Input::coin_signed(
+            Default::default(),
+            Default::default(),
+            1_000_000,
+            Default::default(),
+            0,
+            0,
+        )],
+        vec![fuel_tx::Output::change(
+            Default::default(),
+            0,
+            Default::default(),
+        )],
+        vec![fuel_tx::Witness::default()],
+    );
+    let id = client.submit_and_await_commit(&tx).await.unwrap();
+
+    // run test
+    let block = client.block_by_height(1).await.unwrap().unwrap();
+    assert_eq!(block.producer.0 .0, recipient);
+    assert_eq!(block.header.producer.0 .0, recipient);
+    let executed = client.transaction(&id.to_string()).await.unwrap().unwrap();
+    let change = match executed.transaction.outputs()[0] {
+        fuel_tx::Output::Change { amount, .. } => amount,
+        _ => panic!("the change output is missing"),
+    };
+    let balance = client
+        .balance(format!("{:#x}", recipient).as_str(), None)
+        .await
+        .unwrap();
+    // the recipient is credited what the spender was charged
+    assert!(change < 1_000_000);
+    assert_eq!(balance, 1_000_000 - change);
+}
+
 #[tokio::test]
 async fn block_connection_first_5() {
     // blocks
@@ -121,3 +226,52 @@ async fn block_connection_last_5() {
         rev(5..10).collect_vec()
     );
 }
+
+#[tokio::test]
+async fn subscribe_new_blocks() {
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    let mut blocks = client.subscribe_new_blocks().await.unwrap();
+
+    // the subscription is started asynchronously on the server,
+    // so keep producing blocks until the first one is received
+    let mut submitted = vec![];
+    let block = loop {
+        let tx = fuel_tx::Transaction::script(
+            0,
+            1_000_000,
+            0,
+            0,
+            vec![],
+            (submitted.len() as u64).to_be_bytes().to_vec(),
+            vec![],
+            vec![],
+            vec![],
+        );
+        submitted.push(tx.id());
+        client.submit(&tx).await.unwrap();
+
+        if let Ok(block) = timeout(Duration::from_millis(500), blocks.next()).await {
+            break block.unwrap().unwrap();
+        }
+    };
+
+    assert_eq!(block.transactions.len(), 1);
+    assert!(submitted.contains(&block.transactions[0].id.0 .0));
+}
+
+#[tokio::test]
+async fn subscriptions_end_when_node_stops() {
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    let mut blocks = client.subscribe_new_blocks().await.unwrap();
+
+    srv.stop().await;
+
+    let next = timeout(Duration::from_secs(5), blocks.next())
+        .await
+        .expect("the stream should be closed by the node");
+    assert!(next.is_none());
+}
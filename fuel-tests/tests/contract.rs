@@ -1,6 +1,11 @@
 use crate::helpers::{TestContext, TestSetupBuilder};
-use fuel_core_interfaces::common::fuel_vm::prelude::*;
-use fuel_gql_client::client::{PageDirection, PaginationRequest};
+use fuel_core_interfaces::common::{
+    fuel_crypto::SecretKey,
+    fuel_tx::{ConsensusParameters, TransactionBuilder},
+    fuel_vm::{consts::*, prelude::*, script_with_data_offset},
+};
+use fuel_gql_client::client::{schema::coin::CoinStatus, PageDirection, PaginationRequest};
+use rand::Rng;
 use rstest::rstest;
 
 const SEED: u64 = 2322;
@@ -81,3 +86,110 @@ async fn test_first_5_contract_balances(#[case] direction: PageDirection) {
     assert_eq!(contract_balances.results[1].amount.0, 400);
     assert_eq!(contract_balances.results[2].amount.0, 700);
 }
+
+#[tokio::test]
+async fn contract_state_at_height() {
+    let mut test_builder = TestSetupBuilder::new(SEED);
+    // a contract storing a word under its own id
+    let (_, contract_id) = test_builder.setup_contract(
+        [
+            Opcode::MOVI(0x10, 42),
+            Opcode::SWW(REG_FP, 0x10),
+            Opcode::RET(REG_ONE),
+        ]
+        .iter()
+        .flat_map(|op| u32::from(*op).to_be_bytes())
+        .collect(),
+        None,
+    );
+
+    // call the contract, forwarding some coins to it
+    let asset_id = AssetId::default();
+    let forwarded = 100;
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            Opcode::MOVI(0x10, data_offset + AssetId::LEN as Immediate18),
+            Opcode::MOVI(0x11, forwarded),
+            Opcode::MOVI(0x12, data_offset),
+            Opcode::CALL(0x10, 0x11, 0x12, REG_CGAS),
+            Opcode::RET(REG_ONE),
+        ]
+        .into_iter()
+        .flat_map(|op| u32::from(op).to_be_bytes())
+        .collect::<Vec<u8>>(),
+        ConsensusParameters::DEFAULT.tx_offset()
+    );
+    let script_data = asset_id
+        .iter()
+        .copied()
+        .chain(Call::new(contract_id, 0, 0).to_bytes())
+        .collect();
+    let secret = SecretKey::random(&mut test_builder.rng);
+    let owner = Input::coin_owner(&secret.public_key());
+    let call = TransactionBuilder::script(script, script_data)
+        .gas_limit(1_000_000)
+        .add_unsigned_coin_input(test_builder.rng.gen(), &secret, 1000, asset_id, 0)
+        .add_input(Input::contract(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            contract_id,
+        ))
+        .add_output(Output::contract(1, Default::default(), Default::default()))
+        .add_output(Output::change(owner, 0, asset_id))
+        .finalize();
+    test_builder.config_coin_inputs_from_transactions(&[&call]);
+    let TestContext { client, .. } = test_builder.finalize().await;
+    client.submit_and_await_commit(&call).await.unwrap();
+
+    let contract = format!("{:#x}", contract_id);
+    let owner = format!("{:#x}", owner);
+    let coin = format!("{:#x}", call.inputs()[0].utxo_id());
+    let mut stored = Bytes32::zeroed();
+    stored[..8].copy_from_slice(&42u64.to_be_bytes());
+
+    // the state before the call
+    let slot = client
+        .contract_storage_slot(&contract, &contract, Some(0))
+        .await
+        .unwrap();
+    assert_eq!(Bytes32::from(slot), Bytes32::zeroed());
+    assert_eq!(
+        client
+            .contract_balance_at_height(&contract, None, 0)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        client.balance_at_height(&owner, None, 0).await.unwrap(),
+        1000
+    );
+    let spent = client.coin_at_height(&coin, 0).await.unwrap().unwrap();
+    assert_eq!(spent.status, CoinStatus::Unspent);
+
+    // the state after the call
+    let slot = client
+        .contract_storage_slot(&contract, &contract, None)
+        .await
+        .unwrap();
+    assert_eq!(Bytes32::from(slot), stored);
+    assert_eq!(
+        client
+            .contract_balance_at_height(&contract, None, 1)
+            .await
+            .unwrap(),
+        forwarded as u64
+    );
+    assert_eq!(
+        client.balance(&owner, None).await.unwrap(),
+        1000 - forwarded as u64
+    );
+    let spent = client.coin(&coin).await.unwrap().unwrap();
+    assert_eq!(spent.status, CoinStatus::Spent);
+
+    // the future can't be queried
+    let result = client.balance_at_height(&owner, None, 2).await;
+    assert!(matches!(result, Err(err) if err.to_string().contains("above the current height")));
+}
@@ -1,4 +1,7 @@
-use fuel_core::{config::Config, service::FuelService};
+use fuel_core::{
+    config::{Config, GraphQLConfig},
+    service::FuelService,
+};
 use fuel_core_interfaces::common::fuel_vm::{consts::*, prelude::*};
 use fuel_gql_client::client::FuelClient;
 use std::convert::TryInto;
@@ -17,6 +20,43 @@ async fn start_session() {
     assert_ne!(id, id_p);
 }
 
+#[tokio::test]
+async fn debugger_requires_admin_token_if_configured() {
+    let mut config = Config::local_node();
+    config.graphql = GraphQLConfig {
+        admin_token: Some("secret".to_string()),
+        ..Default::default()
+    };
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let err = client.start_session().await.err().unwrap();
+    assert!(
+        err.to_string().contains("requires a valid admin token"),
+        "{}",
+        err
+    );
+    let err = client
+        .clone()
+        .with_admin_token("wrong")
+        .start_session()
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("requires a valid admin token"),
+        "{}",
+        err
+    );
+
+    // the rest of the api stays public
+    assert!(client.health().await.unwrap());
+
+    let client = client.with_admin_token("secret");
+    let session = client.start_session().await.unwrap();
+    assert!(client.end_session(session.as_str()).await.unwrap());
+}
+
 #[tokio::test]
 async fn end_session() {
     let srv = FuelService::new_node(Config::local_node()).await.unwrap();
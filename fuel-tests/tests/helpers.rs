@@ -60,6 +60,9 @@ impl TestSetupBuilder {
                 salt,
                 state: None,
                 balances,
+                contract_id: None,
+                tx_id: None,
+                output_index: None,
             },
         );
 
@@ -0,0 +1,179 @@
+use fuel_core::{
+    config::{Config, GraphQLConfig},
+    service::FuelService,
+};
+use futures::{SinkExt, StreamExt};
+use reqwest::{header::ACCESS_CONTROL_ALLOW_ORIGIN, Response, StatusCode};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Error as WsError, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+async fn node_with(graphql: GraphQLConfig) -> FuelService {
+    FuelService::new_node(Config {
+        graphql,
+        ..Config::local_node()
+    })
+    .await
+    .unwrap()
+}
+
+async fn post_query(addr: SocketAddr, query: &str, origin: Option<&str>) -> Response {
+    let mut request = reqwest::Client::new()
+        .post(format!("http://{}/graphql", addr))
+        .header("Content-Type", "application/json")
+        .body(json!({ "query": query }).to_string());
+    if let Some(origin) = origin {
+        request = request.header("Origin", origin);
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn any_origin_is_allowed_by_default() {
+    let srv = node_with(Default::default()).await;
+
+    let response = post_query(srv.bound_address, "{ health }", Some("http://a.com")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+}
+
+#[tokio::test]
+async fn only_allowed_origins_can_make_cross_origin_requests() {
+    let srv = node_with(GraphQLConfig {
+        allowed_origins: Some(vec!["http://a.com".to_string()]),
+        ..Default::default()
+    })
+    .await;
+
+    let response = post_query(srv.bound_address, "{ health }", Some("http://a.com")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+        "http://a.com"
+    );
+
+    let response = post_query(srv.bound_address, "{ health }", Some("http://b.com")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // requests which aren't cross-origin aren't affected
+    let response = post_query(srv.bound_address, "{ health }", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn request_body_size_is_limited() {
+    let srv = node_with(GraphQLConfig {
+        max_body_size: 100,
+        ..Default::default()
+    })
+    .await;
+
+    let response = post_query(srv.bound_address, "{ health }", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let query = format!("{{ health {} }}", " ".repeat(100));
+    let response = post_query(srv.bound_address, &query, None).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn requests_are_rate_limited_per_ip() {
+    let srv = node_with(GraphQLConfig {
+        max_requests_per_second: Some(2),
+        ..Default::default()
+    })
+    .await;
+
+    for _ in 0..2 {
+        let response = post_query(srv.bound_address, "{ health }", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = post_query(srv.bound_address, "{ health }", None).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+async fn connect_websocket(
+    addr: SocketAddr,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WsError> {
+    let mut request = format!("ws://{}/graphql-ws", addr)
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        HeaderValue::from_static("graphql-transport-ws"),
+    );
+    let (mut socket, _) = connect_async(request).await?;
+    socket
+        .send(Message::Text(
+            json!({ "type": "connection_init" }).to_string(),
+        ))
+        .await?;
+    assert_eq!(next_message(&mut socket).await["type"], "connection_ack");
+    Ok(socket)
+}
+
+async fn next_message(socket: &mut WebSocketStream<MaybeTlsStream<TcpStream>>) -> Value {
+    loop {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+async fn only_subscriptions_are_served_over_websockets() {
+    let srv = node_with(Default::default()).await;
+    let mut socket = connect_websocket(srv.bound_address).await.unwrap();
+
+    for (id, query) in [("1", "{ health }"), ("2", "mutation { startSession }")] {
+        socket
+            .send(Message::Text(
+                json!({ "id": id, "type": "subscribe", "payload": { "query": query } }).to_string(),
+            ))
+            .await
+            .unwrap();
+        let message = next_message(&mut socket).await;
+        assert_eq!(message["id"], id);
+        assert_eq!(message["payload"]["data"], Value::Null);
+        assert_eq!(
+            message["payload"]["errors"][0]["extensions"]["code"],
+            "NOT_A_SUBSCRIPTION"
+        );
+        let message = next_message(&mut socket).await;
+        assert_eq!(message, json!({ "id": id, "type": "complete" }));
+    }
+}
+
+#[tokio::test]
+async fn websocket_connections_are_limited_per_ip() {
+    let srv = node_with(GraphQLConfig {
+        max_websocket_connections_per_ip: 2,
+        ..Default::default()
+    })
+    .await;
+
+    let first = connect_websocket(srv.bound_address).await.unwrap();
+    let _second = connect_websocket(srv.bound_address).await.unwrap();
+    match connect_websocket(srv.bound_address).await {
+        Err(WsError::Http(response)) => {
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS)
+        }
+        _ => panic!("the connection should be refused"),
+    }
+
+    // closing a connection frees its slot
+    drop(first);
+    for _ in 0..100 {
+        if connect_websocket(srv.bound_address).await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("the connection should be accepted once another one is closed");
+}
@@ -1,4 +1,6 @@
+mod backup;
 mod balances;
+mod block_production;
 mod blocks;
 mod chain;
 mod coin;
@@ -7,5 +9,9 @@ mod dap;
 mod debugger;
 mod health;
 mod helpers;
+mod http_limits;
 mod node_info;
+mod pruning;
+mod query_limits;
+mod replica;
 mod tx;
@@ -41,7 +41,7 @@ async fn test_database_metrics() {
         .collect();
 
     _ = client
-        .submit(&fuel_tx::Transaction::script(
+        .submit_and_await_commit(&fuel_tx::Transaction::script(
             0,
             1000000,
             0,
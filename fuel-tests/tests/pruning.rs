@@ -0,0 +1,155 @@
+use fuel_core::{
+    config::{
+        chain_config::{CoinConfig, StateConfig},
+        Config, PruningConfig,
+    },
+    service::FuelService,
+};
+use fuel_core_interfaces::common::{fuel_crypto::SecretKey, fuel_tx};
+use fuel_gql_client::client::{FuelClient, PageDirection, PaginationRequest};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+#[tokio::test]
+async fn pruned_history_is_reported() {
+    let srv = FuelService::new_node(Config {
+        pruning: Some(PruningConfig {
+            keep_blocks: 1,
+            interval: Duration::from_millis(10),
+        }),
+        ..Config::local_node()
+    })
+    .await
+    .unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let mut ids = vec![];
+    for i in 0..3u64 {
+        let tx = fuel_tx::Transaction::script(
+            0,
+            0,
+            0,
+            0,
+            Default::default(),
+            i.to_be_bytes().to_vec(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        ids.push(client.submit_and_await_commit(&tx).await.unwrap());
+    }
+
+    // wait for the background task to prune the first blocks
+    let mut pruned = false;
+    for _ in 0..100 {
+        pruned = matches!(
+            client.transaction(&ids[0].0.to_string()).await,
+            Err(err) if err.to_string().contains("pruned")
+        );
+        if pruned {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(pruned);
+
+    // the latest transaction is kept
+    assert!(client
+        .transaction(&ids[2].0.to_string())
+        .await
+        .unwrap()
+        .is_some());
+    // the outputs of pruned transactions are pruned too, if spent
+    let pruned_id = fuel_tx::Bytes32::from(ids[0].clone());
+    let output = format!("{:#x}", fuel_tx::UtxoId::new(pruned_id, 0));
+    let result = client.coin(&output).await;
+    assert!(matches!(result, Err(err) if err.to_string().contains("pruned")));
+    // unknown transactions and coins aren't reported as pruned
+    let unknown = fuel_tx::Bytes32::from([7u8; 32]);
+    assert!(client
+        .transaction(&unknown.to_string())
+        .await
+        .unwrap()
+        .is_none());
+    let output = format!("{:#x}", fuel_tx::UtxoId::new(unknown, 0));
+    assert!(client.coin(&output).await.unwrap().is_none());
+    // the blocks are kept, but not their transactions
+    let result = client
+        .transactions(PaginationRequest {
+            cursor: None,
+            results: 10,
+            direction: PageDirection::Forward,
+        })
+        .await;
+    assert!(matches!(result, Err(err) if err.to_string().contains("pruned")));
+}
+
+#[tokio::test]
+async fn spent_genesis_coins_are_reported_as_pruned() {
+    let mut rng = StdRng::seed_from_u64(2322);
+    let secret = SecretKey::random(&mut rng);
+    let owner = fuel_tx::Address::from(*secret.public_key().hash());
+    let mut config = Config {
+        utxo_validation: true,
+        pruning: Some(PruningConfig {
+            keep_blocks: 1,
+            interval: Duration::from_millis(10),
+        }),
+        ..Config::local_node()
+    };
+    // the id of the transaction of the coin is generated
+    config.chain_conf.initial_state = Some(StateConfig {
+        coins: Some(vec![CoinConfig {
+            tx_id: None,
+            output_index: None,
+            block_created: None,
+            maturity: None,
+            owner,
+            amount: 100,
+            asset_id: Default::default(),
+        }]),
+        ..Default::default()
+    });
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let coins = client
+        .coins(
+            &format!("{:#x}", owner),
+            None,
+            PaginationRequest {
+                cursor: None,
+                results: 10,
+                direction: PageDirection::Forward,
+            },
+        )
+        .await
+        .unwrap()
+        .results;
+    let genesis_coin: fuel_tx::UtxoId = coins[0].utxo_id.clone().into();
+
+    // every transaction spends the coin of the previous one, until the first one is pruned
+    let mut utxo_id = genesis_coin;
+    let mut pruned = false;
+    for _ in 0..100 {
+        let tx = fuel_tx::TransactionBuilder::script(vec![], vec![])
+            .add_unsigned_coin_input(utxo_id, &secret, 100, Default::default(), 0)
+            .add_output(fuel_tx::Output::Coin {
+                to: owner,
+                amount: 100,
+                asset_id: Default::default(),
+            })
+            .finalize();
+        let tx_id = client.submit_and_await_commit(&tx).await.unwrap();
+        utxo_id = fuel_tx::UtxoId::new(tx_id.into(), 0);
+        pruned = matches!(
+            client.coin(&format!("{:#x}", genesis_coin)).await,
+            Err(err) if err.to_string().contains("pruned")
+        );
+        if pruned {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(pruned);
+}
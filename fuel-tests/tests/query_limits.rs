@@ -0,0 +1,297 @@
+use fuel_core::{
+    config::{Config, GraphQLConfig},
+    database::Database,
+    model::{FuelBlockDb, FuelBlockHeader},
+    service::FuelService,
+};
+use fuel_core_interfaces::common::{
+    fuel_storage::Storage,
+    fuel_tx,
+    fuel_types::{self, Bytes32},
+};
+use fuel_gql_client::client::{FuelClient, PageDirection, PaginationRequest};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+
+async fn node_with_limits(graphql: GraphQLConfig) -> FuelService {
+    FuelService::new_node(Config {
+        graphql,
+        ..Config::local_node()
+    })
+    .await
+    .unwrap()
+}
+
+/// Sends the query and returns the extensions of the first error of the response
+async fn error_extensions(addr: SocketAddr, query: &str) -> Value {
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/graphql", addr))
+        .header("Content-Type", "application/json")
+        .body(json!({ "query": query }).to_string())
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    response["errors"][0]["extensions"].clone()
+}
+
+#[tokio::test]
+async fn page_size_is_limited() {
+    let srv = node_with_limits(GraphQLConfig {
+        max_page_size: 10,
+        ..Default::default()
+    })
+    .await;
+    let client = FuelClient::from(srv.bound_address);
+
+    let request = |results| PaginationRequest {
+        cursor: None,
+        results,
+        direction: PageDirection::Forward,
+    };
+    assert!(client.blocks(request(10)).await.is_ok());
+    let err = client.blocks(request(11)).await.err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("Requested page size of 11 exceeds the maximum of 10"),
+        "{}",
+        err
+    );
+
+    let extensions = error_extensions(
+        srv.bound_address,
+        "{ transactions(last: 11) { edges { node { id } } } }",
+    )
+    .await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "PAGE_SIZE_TOO_LARGE", "value": 11, "limit": 10 })
+    );
+}
+
+#[tokio::test]
+async fn query_depth_is_limited() {
+    let srv = node_with_limits(GraphQLConfig {
+        max_depth: 3,
+        ..Default::default()
+    })
+    .await;
+    let client = FuelClient::from(srv.bound_address);
+
+    assert!(client.health().await.is_ok());
+    let extensions = error_extensions(
+        srv.bound_address,
+        "{ chain { latestBlock { header { id } } } }",
+    )
+    .await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "QUERY_TOO_DEEP", "value": 4, "limit": 3 })
+    );
+}
+
+#[tokio::test]
+async fn query_complexity_grows_with_page_size() {
+    let srv = node_with_limits(GraphQLConfig {
+        max_complexity: 100,
+        ..Default::default()
+    })
+    .await;
+
+    let query = |page_size| {
+        format!(
+            "{{ blocks(first: {}) {{ edges {{ node {{ id }} }} }} }}",
+            page_size
+        )
+    };
+    assert_eq!(
+        error_extensions(srv.bound_address, &query(10)).await,
+        Value::Null
+    );
+    let extensions = error_extensions(srv.bound_address, &query(100)).await;
+    assert_eq!(extensions["code"], "QUERY_TOO_COMPLEX");
+    // every field costs one, the cost of the edges is multiplied by the page size
+    assert_eq!(extensions["value"], 1 + 100 * 3);
+    assert_eq!(extensions["limit"], 100);
+}
+
+#[tokio::test]
+async fn query_complexity_grows_with_the_transactions_of_blocks() {
+    // two blocks of five transactions, besides the genesis one
+    let mut db = Database::default();
+    for height in 1..=2u32 {
+        let txs = (0..5u8)
+            .map(|i| {
+                fuel_tx::Transaction::script(
+                    0,
+                    1_000_000,
+                    0,
+                    0,
+                    vec![],
+                    vec![height as u8, i],
+                    vec![],
+                    vec![],
+                    vec![],
+                )
+            })
+            .collect::<Vec<_>>();
+        for tx in &txs {
+            Storage::<Bytes32, fuel_tx::Transaction>::insert(&mut db, &tx.id(), tx).unwrap();
+        }
+        let block = FuelBlockDb {
+            headers: FuelBlockHeader {
+                height: height.into(),
+                ..Default::default()
+            },
+            transactions: txs.iter().map(|tx| tx.id()).collect(),
+        };
+        Storage::<Bytes32, FuelBlockDb>::insert(&mut db, &block.id(), &block).unwrap();
+    }
+    let srv = FuelService::from_database(
+        db,
+        Config {
+            graphql: GraphQLConfig {
+                max_complexity: 15,
+                ..Default::default()
+            },
+            ..Config::local_node()
+        },
+    )
+    .await
+    .unwrap();
+
+    // the validation counts a single transaction per block, 1 + 2 * 4, every other
+    // transaction adds the cost of its selection once the block is loaded
+    let extensions = error_extensions(
+        srv.bound_address,
+        "{ blocks(last: 2) { edges { node { transactions { id } } } } }",
+    )
+    .await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "QUERY_TOO_COMPLEX", "value": 9 + 2 * 4, "limit": 15 })
+    );
+    assert_eq!(
+        error_extensions(
+            srv.bound_address,
+            "{ blocks(last: 1) { edges { node { transactions { id } } } } }",
+        )
+        .await,
+        Value::Null
+    );
+}
+
+#[tokio::test]
+async fn dry_run_size_is_limited() {
+    let srv = node_with_limits(GraphQLConfig {
+        max_dry_run_transactions: 2,
+        ..Default::default()
+    })
+    .await;
+    let client = FuelClient::from(srv.bound_address);
+
+    let script = |data: u8| {
+        fuel_tx::Transaction::script(
+            0,
+            1_000_000,
+            0,
+            0,
+            vec![],
+            vec![data],
+            vec![],
+            vec![],
+            vec![],
+        )
+    };
+    assert!(client.dry_run_many(&[script(0), script(1)]).await.is_ok());
+    let err = client
+        .dry_run_many(&[script(0), script(1), script(2)])
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("Dry-run of 3 transactions exceeds the maximum of 2"),
+        "{}",
+        err
+    );
+
+    let extensions = error_extensions(
+        srv.bound_address,
+        r#"mutation { dryRun(txs: ["0x00", "0x00", "0x00"]) { id } }"#,
+    )
+    .await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "TOO_MANY_TRANSACTIONS", "value": 3, "limit": 2 })
+    );
+}
+
+#[tokio::test]
+async fn history_depth_is_limited() {
+    let srv = node_with_limits(GraphQLConfig {
+        max_complexity: 5,
+        max_history_depth: 1,
+        ..Default::default()
+    })
+    .await;
+    let client = FuelClient::from(srv.bound_address);
+    // produce two blocks on top of the genesis one
+    for data in 0..2u8 {
+        let tx = fuel_tx::Transaction::script(
+            0,
+            1_000_000,
+            0,
+            0,
+            vec![],
+            vec![data],
+            vec![],
+            vec![],
+            vec![],
+        );
+        client.submit_and_await_commit(&tx).await.unwrap();
+    }
+
+    let owner = format!("{:#x}", fuel_types::Address::default());
+    assert!(client.balance_at_height(&owner, None, 1).await.is_ok());
+    let err = client
+        .balance_at_height(&owner, None, 0)
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("Querying the state 2 blocks below the latest one exceeds the maximum of 1"),
+        "{}",
+        err
+    );
+
+    let balance = |alias: &str, height: u32| {
+        format!(
+            r#"{}: balance(owner: "{}", assetId: "{:#x}", atHeight: "{}") {{ amount }}"#,
+            alias,
+            owner,
+            fuel_types::AssetId::default(),
+            height
+        )
+    };
+    let extensions =
+        error_extensions(srv.bound_address, &format!("{{ {} }}", balance("past", 0))).await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "HISTORY_TOO_DEEP", "value": 2, "limit": 1 })
+    );
+    // every reverted block adds one to the complexity of the query
+    let extensions = error_extensions(
+        srv.bound_address,
+        &format!("{{ {} {} }}", balance("a", 1), balance("b", 1)),
+    )
+    .await;
+    assert_eq!(
+        extensions,
+        json!({ "code": "QUERY_TOO_COMPLEX", "value": 6, "limit": 5 })
+    );
+}
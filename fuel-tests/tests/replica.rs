@@ -0,0 +1,65 @@
+use fuel_core::{
+    config::{Config, DbMode},
+    database::Database,
+    service::FuelService,
+};
+use fuel_core_interfaces::common::{
+    fuel_tx,
+    fuel_vm::{consts::REG_ONE, prelude::*},
+};
+use fuel_gql_client::client::FuelClient;
+
+/// A primary node and a replica following its database
+async fn primary_and_replica(forward_mutations: bool) -> (FuelService, FuelService) {
+    let db = Database::default();
+    db.migrate(false).unwrap();
+    let primary = FuelService::from_database(db.clone(), Config::local_node())
+        .await
+        .unwrap();
+
+    let mut config = Config::local_node();
+    config.database_mode = DbMode::ReadOnlyReplica;
+    if forward_mutations {
+        config.replica.primary_url = Some(format!("http://{}", primary.bound_address));
+    }
+    let replica = FuelService::from_database(db, config).await.unwrap();
+    (primary, replica)
+}
+
+fn script_tx() -> fuel_tx::Transaction {
+    let script = vec![Opcode::RET(REG_ONE)].into_iter().collect();
+    fuel_tx::Transaction::script(0, 1_000_000, 0, 0, script, vec![], vec![], vec![], vec![])
+}
+
+#[tokio::test]
+async fn replica_rejects_mutations_without_primary() {
+    let (_primary, replica) = primary_and_replica(false).await;
+    let client = FuelClient::from(replica.bound_address);
+    let tx = script_tx();
+
+    let err = client.submit(&tx).await.err().unwrap();
+    assert!(err.to_string().contains("read-only replica"), "{}", err);
+    let err = client.dry_run(&tx).await.err().unwrap();
+    assert!(err.to_string().contains("read-only replica"), "{}", err);
+
+    // queries are served
+    let chain = client.chain_info().await.unwrap();
+    assert_eq!(chain.name, "local_testnet");
+}
+
+#[tokio::test]
+async fn replica_forwards_mutations_to_primary() {
+    let (primary, replica) = primary_and_replica(true).await;
+    let client = FuelClient::from(replica.bound_address);
+    let tx = script_tx();
+
+    assert!(!client.dry_run(&tx).await.unwrap().is_empty());
+    let id = client.submit_and_await_commit(&tx).await.unwrap();
+
+    // the block was produced by the primary and is served by both nodes
+    let primary_client = FuelClient::from(primary.bound_address);
+    let block = primary_client.chain_info().await.unwrap().latest_block;
+    assert_eq!(block.height.0, 1);
+    let transaction = client.transaction(&id.0.to_string()).await.unwrap();
+    assert!(transaction.is_some());
+}
@@ -1,7 +1,7 @@
 use crate::helpers::TestContext;
 use chrono::Utc;
 use fuel_core::{
-    config::Config,
+    config::{chain_config::ProductionStrategy, Config},
     database::Database,
     executor::{ExecutionMode, Executor},
     model::{FuelBlock, FuelBlockHeader},
@@ -9,13 +9,16 @@ use fuel_core::{
 };
 use fuel_core_interfaces::common::{
     fuel_tx,
-    fuel_vm::{consts::*, prelude::*},
+    fuel_tx::ConsensusParameters,
+    fuel_vm::{consts::*, prelude::*, script_with_data_offset},
 };
 use fuel_gql_client::client::{
-    types::TransactionStatus, FuelClient, PageDirection, PaginationRequest,
+    types::{DryRunStatus, TransactionStatus},
+    FuelClient, PageDirection, PaginationRequest,
 };
+use futures::StreamExt;
 use itertools::Itertools;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::io;
 
 mod predicates;
@@ -85,6 +88,225 @@ async fn dry_run() {
         } if val == 1));
 }
 
+#[tokio::test]
+async fn dry_run_many_chains_state_without_committing() {
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    // deploy a contract
+    let contract_code: Vec<u8> = [Opcode::RET(REG_ONE)]
+        .iter()
+        .flat_map(|op| u32::from(*op).to_be_bytes())
+        .collect();
+    let contract = Contract::from(contract_code.clone());
+    let salt = Salt::zeroed();
+    let state_root = Contract::default_state_root();
+    let contract_id = contract.id(&salt, &contract.root(), &state_root);
+    let create = fuel_tx::Transaction::create(
+        0,
+        0,
+        0,
+        0,
+        0,
+        salt,
+        vec![],
+        vec![],
+        vec![],
+        vec![Output::contract_created(contract_id, state_root)],
+        vec![contract_code.into()],
+    );
+
+    // and use it in the next transaction
+    let script = fuel_tx::Transaction::script(
+        0,
+        1_000_000,
+        0,
+        0,
+        Opcode::RET(REG_ONE).to_bytes().to_vec(),
+        vec![],
+        vec![Input::contract(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            contract_id,
+        )],
+        vec![Output::contract(0, Default::default(), Default::default())],
+        vec![],
+    );
+
+    let results = client
+        .dry_run_many(&[create.clone(), script])
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results
+        .iter()
+        .all(|result| matches!(result.status, DryRunStatus::Success { .. })));
+    assert!(matches!(
+        results[1].receipts.last(),
+        Some(Receipt::ScriptResult { .. })
+    ));
+
+    // the contract input of the script points to the output of the create transaction
+    match &results[1].transaction.inputs()[0] {
+        Input::Contract { utxo_id, .. } => assert_eq!(utxo_id, &UtxoId::new(create.id(), 0)),
+        input => panic!("unexpected input {:?}", input),
+    }
+
+    // nothing was committed
+    let id = format!("{:#x}", create.id());
+    assert!(client.transaction(&id).await.unwrap().is_none());
+    let contract_id = format!("{:#x}", contract_id);
+    assert!(client.contract(&contract_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn dry_run_many_reports_fees_coins_and_contract_changes() {
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    let mut rng = StdRng::seed_from_u64(2322);
+
+    // a contract storing a word under its own id
+    let contract_code: Vec<u8> = [
+        Opcode::MOVI(0x10, 42),
+        Opcode::SWW(REG_FP, 0x10),
+        Opcode::RET(REG_ONE),
+    ]
+    .iter()
+    .flat_map(|op| u32::from(*op).to_be_bytes())
+    .collect();
+    let contract = Contract::from(contract_code.clone());
+    let salt: Salt = rng.gen();
+    let state_root = Contract::default_state_root();
+    let contract_id = contract.id(&salt, &contract.root(), &state_root);
+    let create = fuel_tx::Transaction::create(
+        0,
+        0,
+        0,
+        0,
+        0,
+        salt,
+        vec![],
+        vec![],
+        vec![],
+        vec![Output::contract_created(contract_id, state_root)],
+        vec![contract_code.into()],
+    );
+
+    // call the contract, forwarding some coins to it
+    let asset_id = AssetId::default();
+    let owner: Address = rng.gen();
+    let input_amount = 1000;
+    let forwarded = 100;
+    let (script, _) = script_with_data_offset!(
+        data_offset,
+        vec![
+            Opcode::MOVI(0x10, data_offset + AssetId::LEN as Immediate18),
+            Opcode::MOVI(0x11, forwarded),
+            Opcode::MOVI(0x12, data_offset),
+            Opcode::CALL(0x10, 0x11, 0x12, REG_CGAS),
+            Opcode::RET(REG_ONE),
+        ]
+        .into_iter()
+        .flat_map(|op| u32::from(op).to_be_bytes())
+        .collect::<Vec<u8>>(),
+        ConsensusParameters::DEFAULT.tx_offset()
+    );
+    let script_data: Vec<u8> = asset_id
+        .iter()
+        .copied()
+        .chain(Call::new(contract_id, 0, 0).to_bytes())
+        .collect();
+    let coin_utxo_id: UtxoId = rng.gen();
+    let call = fuel_tx::Transaction::script(
+        0,
+        1_000_000,
+        0,
+        0,
+        script,
+        script_data,
+        vec![
+            Input::coin_signed(coin_utxo_id, owner, input_amount, asset_id, 0, 0),
+            Input::contract(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                contract_id,
+            ),
+        ],
+        vec![
+            Output::contract(1, Default::default(), Default::default()),
+            Output::change(owner, 0, asset_id),
+        ],
+        vec![Witness::default()],
+    );
+
+    let results = client.dry_run_many(&[create, call]).await.unwrap();
+    let result = &results[1];
+    assert!(matches!(result.status, DryRunStatus::Success { .. }));
+
+    // fees are charged at the zero prices of the transaction, not for the gas the script used
+    let gas_used = result
+        .receipts
+        .iter()
+        .find_map(|receipt| match receipt {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .unwrap();
+    assert!(gas_used > 0);
+    assert_eq!(result.fee.gas_fee.0, 0);
+    assert_eq!(result.fee.byte_fee.0, 0);
+    assert_eq!(result.fee.total.0, 0);
+
+    // the input coin is spent and its remainder is returned as change
+    assert_eq!(result.coins_spent.len(), 1);
+    assert_eq!(
+        UtxoId::from(result.coins_spent[0].utxo_id.clone()),
+        coin_utxo_id
+    );
+    assert_eq!(result.coins_created.len(), 1);
+    assert_eq!(
+        result.coins_created[0].amount.0,
+        input_amount - forwarded as u64
+    );
+
+    // the contract stored the word and received the forwarded coins
+    assert_eq!(result.contract_changes.len(), 1);
+    let changes = &result.contract_changes[0];
+    assert_eq!(ContractId::from(changes.contract.id.clone()), contract_id);
+    assert_eq!(changes.storage.len(), 1);
+    assert_eq!(
+        Bytes32::from(changes.storage[0].key.clone()),
+        Bytes32::new(*contract_id)
+    );
+    assert!(changes.storage[0].before.is_none());
+    let mut stored = Bytes32::zeroed();
+    stored[..8].copy_from_slice(&42u64.to_be_bytes());
+    assert_eq!(
+        changes.storage[0].after.clone().map(Bytes32::from),
+        Some(stored)
+    );
+    assert_eq!(changes.balances.len(), 1);
+    assert_eq!(
+        AssetId::from(changes.balances[0].asset_id.clone()),
+        asset_id
+    );
+    assert_eq!(changes.balances[0].before.0, 0);
+    assert_eq!(changes.balances[0].after.0, forwarded as u64);
+}
+
+#[tokio::test]
+async fn dry_run_many_fails_on_rejected_transaction() {
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    // the second execution of the same transaction collides with the first one
+    let tx = create_mock_tx(0);
+    let err = client.dry_run_many(&[tx.clone(), tx]).await.unwrap_err();
+    assert!(err.to_string().contains("Transaction 1"), "{}", err);
+}
+
 #[tokio::test]
 async fn submit() {
     let srv = FuelService::new_node(Config::local_node()).await.unwrap();
@@ -129,15 +351,71 @@ async fn submit() {
     assert_eq!(tx.id(), ret_tx.id());
 }
 
-#[ignore]
 #[tokio::test]
 async fn transaction_status_submitted() {
-    // This test should ensure a transaction's status is Submitted while it is in the mempool
-    // This test should also ensure a transaction's time of submission is correct in the returned status
-    // Currently blocked until https://github.com/FuelLabs/fuel-core/issues/50 is resolved
-    // as execution must be seperate from submission for a tx to persist inside of the txpool
-    // Merge with the submit_utxo_verified_tx test once utxo_verification is the default
-    todo!();
+    // the transaction stays in the txpool until a block is produced on demand
+    let mut config = Config::local_node();
+    config.chain_conf.block_production = ProductionStrategy::Manual;
+    let srv = FuelService::new_node(config).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    let transaction = fuel_tx::Transaction::default();
+    let id = format!("{:#x}", transaction.id());
+
+    let before = Utc::now();
+    client.submit(&transaction).await.unwrap();
+    let after = Utc::now();
+
+    let status = client.transaction_status(&id).await.unwrap();
+    match status {
+        TransactionStatus::Submitted { submitted_at } => {
+            assert!(before <= submitted_at && submitted_at <= after)
+        }
+        status => panic!("unexpected status {:?}", status),
+    }
+
+    client.produce_blocks(1, None, None).await.unwrap();
+    let status = client.transaction_status(&id).await.unwrap();
+    assert!(matches!(status, TransactionStatus::Success { .. }));
+}
+
+#[tokio::test]
+async fn subscribe_transaction_status() {
+    let transaction = fuel_tx::Transaction::default();
+    let id = format!("{:#x}", transaction.id());
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+
+    let statuses = client.subscribe_transaction_status(&id).await.unwrap();
+    let statuses = tokio::spawn(statuses.collect::<Vec<_>>());
+    client.submit(&transaction).await.unwrap();
+
+    // the stream ends once the transaction is executed
+    let statuses = statuses.await.unwrap();
+    assert!(matches!(
+        statuses.last(),
+        Some(Ok(TransactionStatus::Success { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn subscribe_transaction_status_of_executed_transaction() {
+    let transaction = fuel_tx::Transaction::default();
+    let id = format!("{:#x}", transaction.id());
+    // setup server & client
+    let srv = FuelService::new_node(Config::local_node()).await.unwrap();
+    let client = FuelClient::from(srv.bound_address);
+    client.submit_and_await_commit(&transaction).await.unwrap();
+
+    // the final status is emitted right away
+    let statuses: Vec<_> = client
+        .subscribe_transaction_status(&id)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert_eq!(statuses.len(), 1);
+    assert!(matches!(statuses[0], Ok(TransactionStatus::Success { .. })));
 }
 
 #[tokio::test]
@@ -148,7 +426,7 @@ async fn receipts() {
     let srv = FuelService::new_node(Config::local_node()).await.unwrap();
     let client = FuelClient::from(srv.bound_address);
     // submit tx
-    let result = client.submit(&transaction).await;
+    let result = client.submit_and_await_commit(&transaction).await;
     assert!(result.is_ok());
 
     // run test
@@ -166,7 +444,7 @@ async fn get_transaction_by_id() {
     let srv = FuelService::new_node(Config::local_node()).await.unwrap();
     let client = FuelClient::from(srv.bound_address);
     // submit tx to api
-    client.submit(&transaction).await.unwrap();
+    client.submit_and_await_commit(&transaction).await.unwrap();
 
     // run test
     let transaction_response = client.transaction(&format!("{:#x}", id)).await.unwrap();
@@ -189,7 +467,7 @@ async fn get_transparent_transaction_by_id() {
     let client = FuelClient::from(srv.bound_address);
 
     // submit tx
-    let result = client.submit(&transaction).await;
+    let result = client.submit_and_await_commit(&transaction).await;
     assert!(result.is_ok());
 
     let opaque_tx = client
@@ -480,7 +758,10 @@ impl TestContext {
             witnesses: vec![vec![].into()],
             metadata: None,
         };
-        self.client.submit(&tx).await.map(Into::into)
+        self.client
+            .submit_and_await_commit(&tx)
+            .await
+            .map(Into::into)
     }
 }
 
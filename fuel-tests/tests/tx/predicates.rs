@@ -35,8 +35,15 @@ async fn transaction_with_predicates_is_rejected_when_feature_disabled() {
     .finalize()
     .await;
 
-    let result = context.client.submit(&predicate_tx).await;
-    assert!(result.is_err());
+    let result = context.client.submit_and_await_commit(&predicate_tx).await;
+    // the reason the block producer rejected the transaction is reported
+    let err = result.expect_err("the transaction must be rejected");
+    assert!(
+        err.to_string()
+            .contains("predicate inputs which aren't enabled"),
+        "{}",
+        err
+    );
 }
 
 #[tokio::test]
@@ -71,7 +78,11 @@ async fn transaction_with_predicate_is_executed_when_feature_enabled() {
     .finalize()
     .await;
 
-    let transaction_id = context.client.submit(&predicate_tx).await.unwrap();
+    let transaction_id = context
+        .client
+        .submit_and_await_commit(&predicate_tx)
+        .await
+        .unwrap();
 
     // check transaction change amount to see if predicate was spent
     let transaction = context
@@ -119,7 +130,13 @@ async fn transaction_with_invalid_predicate_is_rejected_when_feature_is_enabled(
     .finalize()
     .await;
 
-    let result = context.client.submit(&predicate_tx).await;
+    let result = context.client.submit_and_await_commit(&predicate_tx).await;
 
-    assert!(result.is_err())
+    let err = result.expect_err("the transaction must be rejected");
+    assert!(
+        err.to_string()
+            .contains("predicate which failed to validate"),
+        "{}",
+        err
+    );
 }
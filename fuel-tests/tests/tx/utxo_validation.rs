@@ -58,7 +58,7 @@ async fn submit_utxo_verified_tx_with_min_gas_price() {
 
     // submit transactions and verify their status
     for tx in transactions {
-        let id = client.submit(&tx).await.unwrap();
+        let id = client.submit_and_await_commit(&tx).await.unwrap();
         // verify that the tx returned from the api matches the submitted tx
         let ret_tx = client
             .transaction(&id.0.to_string())
@@ -79,7 +79,6 @@ async fn submit_utxo_verified_tx_with_min_gas_price() {
             assert!(block_exists.is_some());
         }
 
-        // Once https://github.com/FuelLabs/fuel-core/issues/50 is resolved this should rely on the Submitted Status rather than Success
         assert!(matches!(
             transaction_result,
             TransactionStatus::Success { .. }
@@ -237,7 +236,7 @@ async fn concurrent_tx_submission_produces_expected_blocks() {
         .into_iter()
         .map(|tx| {
             let client = client.clone();
-            async move { client.submit(&tx).await }
+            async move { client.submit_and_await_commit(&tx).await }
         })
         .collect_vec();
 
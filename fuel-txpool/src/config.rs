@@ -8,6 +8,13 @@ pub struct Config {
     pub min_gas_price: u64,
     /// The minimum allowed byte price
     pub min_byte_price: u64,
+    /// Whether the coin inputs of transactions must exist in the database and match its coins
+    pub utxo_validation: bool,
+    /// Maximum total gas limit of the transactions of a block, larger transactions are refused
+    pub max_gas_per_block: u64,
+    /// Maximum total size in bytes of the transactions of a block, larger transactions are
+    /// refused
+    pub max_block_size: u64,
 }
 
 impl Default for Config {
@@ -17,6 +24,9 @@ impl Default for Config {
             max_depth: 10,
             min_gas_price: 0,
             min_byte_price: 0,
+            utxo_validation: true,
+            max_gas_per_block: u64::MAX,
+            max_block_size: u64::MAX,
         }
     }
 }
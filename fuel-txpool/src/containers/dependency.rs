@@ -18,6 +18,8 @@ pub struct Dependency {
     contracts: HashMap<ContractId, ContractState>,
     /// max depth of dependency.
     max_depth: usize,
+    /// whether coin inputs are checked against the coins of the database
+    utxo_validation: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -55,11 +57,12 @@ impl ContractState {
 }
 
 impl Dependency {
-    pub fn new(max_depth: usize) -> Self {
+    pub fn new(max_depth: usize, utxo_validation: bool) -> Self {
         Self {
             coins: HashMap::new(),
             contracts: HashMap::new(),
             max_depth,
+            utxo_validation,
         }
     }
 
@@ -117,6 +120,23 @@ impl Dependency {
         }
     }
 
+    /// Checks that the input can spend the coin of the database. Without utxo validation the
+    /// coin isn't checked, nor does it need to exist.
+    fn check_db_coin(
+        &self,
+        db: &dyn TxPoolDb,
+        utxo_id: &UtxoId,
+        input: &Input,
+    ) -> anyhow::Result<()> {
+        if !self.utxo_validation {
+            return Ok(());
+        }
+        let coin = db
+            .utxo(utxo_id)?
+            .ok_or(Error::NotInsertedInputUtxoIdNotExisting(*utxo_id))?;
+        Self::check_if_coin_input_can_spend_db_coin(&coin, input)
+    }
+
     fn check_if_coin_input_can_spend_db_coin(coin: &Coin, input: &Input) -> anyhow::Result<()> {
         match input {
             Input::CoinSigned {
@@ -278,10 +298,7 @@ impl Dependency {
                             } else {
                                 if state.is_in_database() {
                                     //this means it is loaded from db. Get tx to compare output.
-                                    let coin = db.utxo(utxo_id)?.ok_or(
-                                        Error::NotInsertedInputUtxoIdNotExisting(*utxo_id),
-                                    )?;
-                                    Self::check_if_coin_input_can_spend_db_coin(&coin, input)?;
+                                    self.check_db_coin(db, utxo_id, input)?;
                                 } else {
                                     // tx output is in pool
                                     let output_tx = txs.get(utxo_id.tx_id()).unwrap();
@@ -298,11 +315,7 @@ impl Dependency {
                         // if coin is not spend, it will be spend later down the line
                     } else {
                         // fetch from db and check if tx exist.
-                        let coin = db
-                            .utxo(utxo_id)?
-                            .ok_or(Error::NotInsertedInputUtxoIdNotExisting(*utxo_id))?;
-
-                        Self::check_if_coin_input_can_spend_db_coin(&coin, input)?;
+                        self.check_db_coin(db, utxo_id, input)?;
                         max_depth = core::cmp::max(1, max_depth);
                         db_coins.insert(
                             *utxo_id,
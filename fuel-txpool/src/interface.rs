@@ -26,6 +26,22 @@ impl Interface {
                     if matches!(event,Some(TxPoolMpsc::Stop) | None) {
                         break;
                     }
+                    // removal is applied before handling the next request, so transactions
+                    // that were included into a block are never returned as includable again.
+                    match &event {
+                        Some(TxPoolMpsc::Remove { ids }) => {
+                            self.remove(ids.iter().map(|id| (*id, Error::Removed))).await;
+                            continue;
+                        }
+                        Some(TxPoolMpsc::Reject { rejected }) => {
+                            let rejected = rejected
+                                .iter()
+                                .map(|(id, reason)| (*id, Error::Rejected(reason.clone())));
+                            self.remove(rejected).await;
+                            continue;
+                        }
+                        _ => {}
+                    }
                     let interface = self.clone();
 
                     // this is litlle bit risky but we can always add semaphore to limit number of requests.
@@ -49,10 +65,9 @@ impl Interface {
                         TxPoolMpsc::FilterByNegative { ids, response } => {
                             let _ = response.send(interface.filter_by_negative(&ids).await);
                         }
-                        TxPoolMpsc::Remove { ids } => {
-                            let _ = interface.remove(&ids).await;
-                        }
-                        TxPoolMpsc::Stop => {}
+                        TxPoolMpsc::Remove { .. }
+                        | TxPoolMpsc::Reject { .. }
+                        | TxPoolMpsc::Stop => {}
                     }});
                 }
                 _block_updated = new_block.recv() => {
@@ -172,18 +187,25 @@ impl Interface {
     }
 
     /// remove transaction from pool needed on user demand. Low priority
-    async fn remove(&self, tx_ids: &[TxId]) {
+    ///
+    /// Every transaction is squeezed out for its `reason`, the ones depending on it are removed.
+    async fn remove(&self, tx_ids: impl Iterator<Item = (TxId, Error)>) {
         let mut removed = Vec::new();
-        for tx_id in tx_ids {
-            let rem = { self.txpool.write().await.remove_by_tx_id(tx_id) };
-            removed.extend(rem.into_iter());
+        for (tx_id, reason) in tx_ids {
+            let rem = { self.txpool.write().await.remove_by_tx_id(&tx_id) };
+            removed.extend(rem.into_iter().map(|tx| {
+                let reason = if tx.id() == tx_id {
+                    reason.clone()
+                } else {
+                    Error::Removed
+                };
+                (tx, reason)
+            }));
         }
-        for tx in removed {
+        for (tx, reason) in removed {
             let _ = self.broadcast.send(TxStatusBroadcast {
                 tx,
-                status: TxStatus::SqueezedOut {
-                    reason: Error::Removed,
-                },
+                status: TxStatus::SqueezedOut { reason },
             });
         }
     }
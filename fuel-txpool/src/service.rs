@@ -269,4 +269,50 @@ pub mod tests {
             "Second removed should be tx2"
         );
     }
+
+    #[tokio::test]
+    async fn rejected_transactions_are_squeezed_out_with_the_reason() {
+        let config = Config::default();
+        let db = Box::new(DummyDb::filled());
+        let (_bs, br) = broadcast::channel(10);
+
+        let tx1_hash = *TX_ID1;
+        let tx1 = Arc::new(DummyDb::dummy_tx(tx1_hash));
+
+        let service = Service::new(db, config).unwrap();
+        service.start(br).await;
+        let mut subscribe = service.subscribe_ch();
+
+        let out = service.sender().insert(vec![tx1.clone()]).await.unwrap();
+        assert!(out[0].is_ok(), "Tx1 should be OK, got err:{:?}", out);
+        assert_eq!(
+            subscribe.try_recv(),
+            Ok(TxStatusBroadcast {
+                tx: tx1.clone(),
+                status: TxStatus::Submitted,
+            }),
+        );
+
+        service
+            .sender()
+            .reject(vec![(
+                tx1_hash,
+                "Coin has not yet reached maturity".to_string(),
+            )])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tokio::time::timeout(std::time::Duration::from_secs(2), subscribe.recv()).await,
+            Ok(Ok(TxStatusBroadcast {
+                tx: tx1,
+                status: TxStatus::SqueezedOut {
+                    reason: TxpoolError::Rejected("Coin has not yet reached maturity".to_string())
+                }
+            })),
+        );
+        let out = service.sender().find_one(tx1_hash).await.unwrap();
+        assert!(out.is_none(), "Tx1 should be removed:{:?}", out);
+        service.stop().await.unwrap().await.unwrap();
+    }
 }
@@ -20,10 +20,11 @@ pub struct TxPool {
 impl TxPool {
     pub fn new(config: Config) -> Self {
         let max_depth = config.max_depth;
+        let utxo_validation = config.utxo_validation;
         Self {
             by_hash: HashMap::new(),
             by_gas_price: PriceSort::default(),
-            by_dependency: Dependency::new(max_depth),
+            by_dependency: Dependency::new(max_depth, utxo_validation),
             config,
         }
     }
@@ -45,6 +46,8 @@ impl TxPool {
         self.verify_tx_min_gas_price(&tx)?;
         // verify byte price is at least the minimum
         self.verify_tx_min_byte_price(&tx)?;
+        // verify the transaction fits into a block
+        self.verify_tx_block_limits(&tx)?;
 
         if self.by_hash.contains_key(&tx.id()) {
             return Err(Error::NotInsertedTxKnown.into());
@@ -132,6 +135,16 @@ impl TxPool {
         }
         Ok(())
     }
+
+    fn verify_tx_block_limits(&mut self, tx: &Transaction) -> Result<(), Error> {
+        if tx.gas_limit() > self.config.max_gas_per_block {
+            return Err(Error::NotInsertedGasLimitAboveBlockLimit);
+        }
+        if tx.metered_bytes_size() as u64 > self.config.max_block_size {
+            return Err(Error::NotInsertedSizeAboveBlockLimit);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -249,6 +262,23 @@ pub mod tests {
         assert_eq!(out.err().unwrap().to_string(),"Transaction is not inserted. UTXO is not existing: 0x000000000000000000000000000000000000000000000000000000000000001000",);
     }
 
+    #[tokio::test]
+    async fn tx2_missing_utxo_inserted_without_utxo_validation() {
+        let config = Config {
+            utxo_validation: false,
+            ..Default::default()
+        };
+        let db = DummyDb::filled();
+
+        let tx2_hash = *TX_ID2;
+        let tx2 = Arc::new(DummyDb::dummy_tx(tx2_hash));
+
+        let mut txpool = TxPool::new(config);
+
+        let out = txpool.insert(tx2, &db).await;
+        assert!(out.is_ok(), "Tx2 should be OK, get err:{:?}", out);
+    }
+
     #[tokio::test]
     async fn tx1_try_to_use_spend_coin() {
         let config = Config::default();
@@ -552,4 +582,50 @@ pub mod tests {
             Error::NotInsertedBytePriceTooLow
         ));
     }
+
+    #[tokio::test]
+    async fn tx_above_the_block_gas_limit_is_not_insertable() {
+        let tx1 = Arc::new(DummyDb::dummy_tx(*TX_ID1));
+        let config = Config {
+            max_gas_per_block: tx1.gas_limit() - 1,
+            ..Config::default()
+        };
+        let db = DummyDb::filled();
+
+        let mut txpool = TxPool::new(config);
+
+        let err = txpool.insert(tx1, &db).await.err().unwrap();
+        assert!(matches!(
+            err.root_cause().downcast_ref::<Error>().unwrap(),
+            Error::NotInsertedGasLimitAboveBlockLimit
+        ));
+    }
+
+    #[tokio::test]
+    async fn tx_above_the_block_size_limit_is_not_insertable() {
+        let tx1 = Arc::new(DummyDb::dummy_tx(*TX_ID1));
+        let config = Config {
+            max_block_size: tx1.metered_bytes_size() as u64 - 1,
+            ..Config::default()
+        };
+        let db = DummyDb::filled();
+
+        let mut txpool = TxPool::new(config);
+
+        let err = txpool.insert(tx1.clone(), &db).await.err().unwrap();
+        assert!(matches!(
+            err.root_cause().downcast_ref::<Error>().unwrap(),
+            Error::NotInsertedSizeAboveBlockLimit
+        ));
+
+        // a transaction filling the whole block fits
+        let config = Config {
+            max_gas_per_block: tx1.gas_limit(),
+            max_block_size: tx1.metered_bytes_size() as u64,
+            ..Config::default()
+        };
+        let mut txpool = TxPool::new(config);
+        let out = txpool.insert(tx1, &db).await;
+        assert!(out.is_ok(), "Tx1 should be OK, get err:{:?}", out);
+    }
 }